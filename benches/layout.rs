@@ -0,0 +1,95 @@
+//! Compares full and incremental layout passes on a UI with 10k labels
+//!
+//! Run with `cargo bench --bench layout`.
+
+use bevy_reflect::{ParsedPath, Reflect};
+use serde::Deserialize;
+use std::time::{Duration, Instant};
+use viui::nodes::events::{MouseEventKind, UiEvent};
+use viui::observable_state::{ObservableState, TypedPath};
+use viui::result::ViuiResult;
+use viui::ui::UI;
+
+const ROWS: usize = 100;
+const COLUMNS: usize = 100;
+const ITERATIONS: u32 = 20;
+
+#[derive(Debug, Reflect)]
+struct BenchState {
+    counter: i32,
+    rows: Vec<String>,
+    cells: Vec<String>,
+}
+
+#[derive(Debug, Reflect, Deserialize)]
+enum BenchMessage {
+    Increment,
+}
+
+fn main() -> ViuiResult<()> {
+    let counter_path = TypedPath::<i32>::new(ParsedPath::parse("counter")?);
+    let mut ui = UI::new(
+        ObservableState::new(BenchState {
+            counter: 0,
+            rows: (0..ROWS).map(|row| format!("Row {row}")).collect(),
+            cells: (0..COLUMNS).map(|column| format!("{column}")).collect(),
+        }),
+        "Bench".to_string(),
+        move |state, message: &BenchMessage| match message {
+            BenchMessage::Increment => state.apply_change("Increment", |mutator| {
+                mutator.mutate(&counter_path, |counter| *counter += 1);
+            }),
+        },
+    )?;
+    ui.load_ui_source(
+        "component Bench {
+            button(label=`Clicked ${counter} times` @click=Increment)
+            for (row in rows) {
+                hstack {
+                    for (cell in cells) {
+                        label(label=cell)
+                    }
+                }
+            }
+        }",
+    )?;
+    let parameters = ui.add_render_backend()?;
+    parameters.message_receiver.try_iter().for_each(drop);
+    let button = *ui
+        .nodes()
+        .find(|node| node.tag == "button")
+        .unwrap()
+        .bounds();
+
+    let full = measure(|| {
+        ui.invalidate_layout();
+        ui.perform_layout()
+    })?;
+    let unchanged = measure(|| ui.perform_layout())?;
+    let mut changed = Duration::ZERO;
+    for _ in 0..ITERATIONS {
+        ui.handle_ui_event(UiEvent::mouse_move(button.center()))?;
+        ui.handle_ui_event(UiEvent::mouse_input(MouseEventKind::Pressed))?;
+        ui.handle_ui_event(UiEvent::mouse_input(MouseEventKind::Released))?;
+        ui.eval_expressions()?;
+        let start = Instant::now();
+        ui.perform_layout()?;
+        changed += start.elapsed();
+    }
+    changed /= ITERATIONS;
+
+    println!("Layout of {} nodes:", ROWS * COLUMNS);
+    println!("  full layout:          {full:?}");
+    println!("  nothing changed:      {unchanged:?}");
+    println!("  one label changed:    {changed:?}");
+    Ok(())
+}
+
+/// Average duration of the given layout pass
+fn measure(mut layout: impl FnMut() -> ViuiResult<()>) -> ViuiResult<Duration> {
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        layout()?;
+    }
+    Ok(start.elapsed() / ITERATIONS)
+}
@@ -25,7 +25,7 @@ enum AppMessage {
     Set(Float),
     SetName(String),
     Change(Float),
-    ToggleImage,
+    ShowImage(bool),
 }
 
 fn main() {
@@ -75,9 +75,9 @@ fn main_internal() -> ViuiResult<()> {
                     mutator.mutate(&name_path, |name| *name = new_name.to_string());
                 });
             }
-            AppMessage::ToggleImage => {
-                app_state.apply_change("Toggle image", |mutator| {
-                    mutator.mutate(&show_image_path, |show_image| *show_image = !*show_image);
+            AppMessage::ShowImage(value) => {
+                app_state.apply_change("Show image", |mutator| {
+                    mutator.mutate(&show_image_path, |show_image| *show_image = *value);
                 });
             }
             AppMessage::Change(by) => {
@@ -1,6 +1,7 @@
 use rand::random;
 use rgb::bytemuck::Contiguous;
 use std::fmt::{Debug, Formatter};
+use std::hash::{Hash, Hasher};
 use std::num::NonZeroU16;
 use std::ops::{Index, IndexMut};
 
@@ -82,6 +83,16 @@ impl<T> PartialEq for Idx<T> {
     }
 }
 
+impl<T> Eq for Idx<T> {}
+
+impl<T> Hash for Idx<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.arenal_id.hash(state);
+        self.generation.hash(state);
+        self.offset.hash(state);
+    }
+}
+
 impl<T> Default for Arenal<T> {
     fn default() -> Self {
         Self::new()
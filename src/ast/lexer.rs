@@ -25,6 +25,7 @@ pub enum TokenKind {
     In,
     Component,
     Comma,
+    Dot,
 }
 
 #[derive(Debug)]
@@ -115,6 +116,9 @@ impl<'a> Lexer<'a> {
             ',' => {
                 self.create_token(start, TokenKind::Comma);
             }
+            '.' => {
+                self.create_token(start, TokenKind::Dot);
+            }
             'a'..='z' | 'A'..='Z' | '_' | '#' => {
                 self.scanner
                     .eat_while(|c: char| c.is_ascii_alphanumeric() || c == '_' || c == '#');
@@ -381,6 +385,12 @@ mod tests {
             <At> '@' 0+1
             <EOF> '' 1+0
         "#]];
+        test_dot, "click.stop", expect![[r#"
+            <Identifier> 'click' 0+5
+            <Dot> '.' 5+1
+            <Identifier> 'stop' 6+4
+            <EOF> '' 10+0
+        "#]];
         test_component, "component", expect![[r#"
             <Component> 'component' 0+9
             <EOF> '' 9+0
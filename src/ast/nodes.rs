@@ -5,7 +5,6 @@ use termtree::Tree;
 
 #[derive(Debug, Clone)]
 pub struct AstNode<T> {
-    #[allow(dead_code)]
     span: Span,
     data: T,
 }
@@ -15,6 +14,10 @@ impl<T> AstNode<T> {
         Self { span, data }
     }
 
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
     pub fn data(&self) -> &T {
         &self.data
     }
@@ -59,7 +62,17 @@ pub struct NodeDefinition {
     pub tag: String,
     pub props: Vec<PropAst>,
     pub children: Vec<ItemAst>,
-    pub events: Vec<PropAst>,
+    pub events: Vec<EventAst>,
+}
+
+pub type EventAst = AstNode<EventDefinition>;
+
+#[derive(Debug, Clone)]
+pub struct EventDefinition {
+    pub name: String,
+    /// Modifiers following the name, e.g. `stop` in `@click.stop=Save`
+    pub modifiers: Vec<String>,
+    pub expression: ExpressionAst,
 }
 
 pub type PropAst = AstNode<PropDefinition>;
@@ -171,9 +184,12 @@ fn node_ast_to_tree(node_definition: &NodeAst) -> Tree<String> {
         tree.push(prop_tree);
     }
     for event in &node_definition.events {
-        let mut event_tree = prop_ast_to_tree(event);
-        event_tree.root.insert(0, '@');
-        event_tree.root += "=";
+        let mut name = format!("@{}", event.name);
+        for modifier in &event.modifiers {
+            name = format!("{name}.{modifier}");
+        }
+        let mut event_tree = Tree::new(format!("{name}="));
+        event_tree.push(expression_ast_to_tree(&event.expression));
         tree.push(event_tree);
     }
     for child in &node_definition.children {
@@ -1,8 +1,8 @@
 use crate::ast::lexer::{lex, Token, TokenKind};
 use crate::ast::nodes::{
-    ComponentAst, ComponentDefinition, ExpressionAst, ExpressionKind, ForItemDefinition,
-    IfItemDefinition, ItemAst, ItemDefinition, NodeAst, NodeDefinition, PropAst, PropDefinition,
-    UIAst, UIDefinition,
+    ComponentAst, ComponentDefinition, EventAst, EventDefinition, ExpressionAst, ExpressionKind,
+    ForItemDefinition, IfItemDefinition, ItemAst, ItemDefinition, NodeAst, NodeDefinition, PropAst,
+    PropDefinition, UIAst, UIDefinition,
 };
 use crate::ast::span::Span;
 use crate::ast::value::ExpressionValue;
@@ -159,7 +159,7 @@ impl<'a> Parser<'a> {
             while !self.is_at(TokenKind::CloseParen) {
                 if self.is_at(TokenKind::At) {
                     self.advance_token();
-                    events.push(self.parse_node_prop()?);
+                    events.push(self.parse_node_event()?);
                 } else {
                     props.push(self.parse_node_prop()?);
                 }
@@ -198,6 +198,33 @@ impl<'a> Parser<'a> {
         ))
     }
 
+    fn parse_node_event(&mut self) -> ViuiResult<EventAst> {
+        let start = self.current_token().span.start;
+        let name = self
+            .consume(TokenKind::Identifier, "Expected event name")?
+            .lexeme
+            .to_string();
+        let mut modifiers = vec![];
+        while self.is_at(TokenKind::Dot) {
+            self.advance_token();
+            modifiers.push(
+                self.consume(TokenKind::Identifier, "Expected event modifier")?
+                    .lexeme
+                    .to_string(),
+            );
+        }
+        self.consume(TokenKind::Equal, "Expected '='")?;
+        let expression = self.parse_expression()?;
+        Ok(EventAst::new(
+            Span::new(start, self.previous_token().span.end),
+            EventDefinition {
+                name,
+                modifiers,
+                expression,
+            },
+        ))
+    }
+
     fn parse_expression(&mut self) -> ViuiResult<ExpressionAst> {
         self.parse_call()
     }
@@ -267,7 +294,7 @@ impl<'a> Parser<'a> {
                 self.current_token().lexeme.to_string(),
             )),
             TokenKind::Identifier => {
-                ExpressionKind::VarUse(self.current_token().lexeme.to_string())
+                return self.parse_var_use();
             }
             TokenKind::TemplateString => {
                 return self.parse_template_literal();
@@ -280,6 +307,27 @@ impl<'a> Parser<'a> {
         Ok(ast)
     }
 
+    /// Variable, optionally followed by a path of fields such as `file.name`
+    fn parse_var_use(&mut self) -> ViuiResult<ExpressionAst> {
+        let start = self.current_token().span.start;
+        let mut path = self
+            .consume(TokenKind::Identifier, "Expected identifier")?
+            .lexeme
+            .to_string();
+        while self.is_at(TokenKind::Dot) {
+            self.advance_token();
+            path.push('.');
+            path.push_str(
+                self.consume(TokenKind::Identifier, "Expected field name after '.'")?
+                    .lexeme,
+            );
+        }
+        Ok(ExpressionAst::new(
+            Span::new(start, self.previous_token().span.end),
+            ExpressionKind::VarUse(path),
+        ))
+    }
+
     fn parse_template_literal(&mut self) -> ViuiResult<ExpressionAst> {
         let mut strings = Vec::new();
         let mut expressions = Vec::new();
@@ -419,6 +467,10 @@ mod tests {
                 ├── Literal String("foo")
                 └── Literal Float(3.0)
             "#]];
+        parse_field_path, "file.name",
+            expect![[r#"
+                VarUse file.name
+            "#]];
     );
 
     fn test_parse_ui(input: &str, expected_output: Expect) {
@@ -472,6 +524,15 @@ mod tests {
                             └── VarUse Increment
             "#]];
 
+        parse_component_with_event_modifiers, "component event {hstack(@press.capture.stop=Pressed)}",
+            expect![[r#"
+                UIDefinition
+                └── Component event
+                    └── Node hstack
+                        └── @press.capture.stop=
+                            └── VarUse Pressed
+            "#]];
+
         parse_component_with_children, "component event {button{label}}",
             expect![[r#"
                 UIDefinition
@@ -42,6 +42,8 @@ impl TryFrom<&dyn Reflect> for ExpressionValue {
             ExpressionValue::Float(*value)
         } else if let Some(value) = value.downcast_ref::<String>() {
             ExpressionValue::String(value.clone())
+        } else if let Some(value) = value.downcast_ref::<bool>() {
+            ExpressionValue::Bool(*value)
         } else {
             bail!(
                 "Could not convert value to expression value: {:?} {}",
@@ -148,14 +148,14 @@ fn eval_expression(ir_expression: &IrExpression) -> ViuiResult<EvalValue> {
 mod tests {
     use crate::arenal::Arenal;
     use crate::ast::parser::parse_ui;
-    use crate::ir::node::ast_to_ir;
+    use crate::ir::node::{ast_to_ir, WidgetRegistry};
 
     #[test]
     fn test_eval_label() {
         // read source from file
         let source = std::fs::read_to_string("examples/simple/label.viui-component").unwrap();
         let ast = parse_ui(&source).unwrap();
-        let ir = ast_to_ir(&ast).unwrap();
+        let ir = ast_to_ir(&ast, &WidgetRegistry::default()).unwrap();
         let mut arenal = Arenal::new();
         let evaled = super::eval_component(&ir[0], &mut arenal).unwrap();
         dbg!(evaled);
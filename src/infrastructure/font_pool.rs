@@ -93,6 +93,10 @@ impl FontPool {
         TextMeasurer::new(font).measure_text(text, size)
     }
 
+    pub fn text_ascent(&self, font_index: FontIndex, size: Float) -> Float {
+        TextMeasurer::new(&self.fonts[font_index.index]).ascent(size)
+    }
+
     pub fn maximum_font_index(&self) -> usize {
         self.fonts.len()
     }
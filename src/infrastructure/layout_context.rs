@@ -1,14 +1,23 @@
+use crate::infrastructure::font_pool::{FontIndex, FontPool};
 use crate::infrastructure::image_pool::ImagePool;
+use crate::infrastructure::measure_text::{wrap_text, TextMeasurement};
+use crate::render::context::DEFAULT_FONT_SIZE;
 use crate::result::ViuiResult;
-use crate::types::Size;
+use crate::types::{Float, Size};
 
 pub struct LayoutContext<'a> {
     image_pool: &'a mut ImagePool,
+    font_pool: &'a FontPool,
+    is_animated: bool,
 }
 
 impl<'a> LayoutContext<'a> {
-    pub fn new(image_pool: &'a mut ImagePool) -> LayoutContext<'a> {
-        LayoutContext { image_pool }
+    pub fn new(image_pool: &'a mut ImagePool, font_pool: &'a FontPool) -> LayoutContext<'a> {
+        LayoutContext {
+            image_pool,
+            font_pool,
+            is_animated: false,
+        }
     }
 }
 
@@ -16,4 +25,46 @@ impl LayoutContext<'_> {
     pub fn get_image_size(&mut self, path: &str) -> ViuiResult<Size> {
         self.image_pool.get_image_size(path)
     }
+
+    pub fn measure_text(&self, text: &str) -> ViuiResult<TextMeasurement> {
+        self.font_pool
+            .measure_text(FontIndex::new(0), text, DEFAULT_FONT_SIZE)
+    }
+
+    /// Lays out the nodes again on the next frame
+    pub fn set_animated(&mut self) {
+        self.is_animated = true;
+    }
+
+    pub fn reset_animated(&mut self) -> bool {
+        let was_animated = self.is_animated;
+        self.is_animated = false;
+        was_animated
+    }
+}
+
+/// Context passed to measure functions while the layout is computed
+pub struct MeasureContext<'a> {
+    font_size: Float,
+    font_pool: &'a FontPool,
+}
+
+impl<'a> MeasureContext<'a> {
+    pub fn new(font_pool: &'a FontPool) -> MeasureContext<'a> {
+        MeasureContext {
+            font_size: DEFAULT_FONT_SIZE,
+            font_pool,
+        }
+    }
+}
+
+impl MeasureContext<'_> {
+    pub fn measure_text(&self, text: &str) -> ViuiResult<TextMeasurement> {
+        self.font_pool
+            .measure_text(FontIndex::new(0), text, self.font_size)
+    }
+
+    pub fn wrap_text(&self, text: &str, max_width: Option<Float>) -> ViuiResult<Vec<String>> {
+        wrap_text(text, max_width, |line| Ok(self.measure_text(line)?.width))
+    }
 }
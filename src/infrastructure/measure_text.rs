@@ -42,6 +42,42 @@ impl<'a> TextMeasurer<'a> {
             height: face.height() as Float * scale_factor,
         })
     }
+
+    /// Distance from the top of a line to its baseline
+    pub fn ascent(&self, size: Float) -> Float {
+        self.face.ascender() as Float * size / self.face.units_per_em() as Float
+    }
+}
+
+/// Breaks text into lines no wider than `max_width`, breaking at whitespace and newlines
+///
+/// Words wider than `max_width` are put on a line of their own.
+pub fn wrap_text(
+    text: &str,
+    max_width: Option<Float>,
+    measure_width: impl Fn(&str) -> ViuiResult<Float>,
+) -> ViuiResult<Vec<String>> {
+    let mut lines = vec![];
+    for paragraph in text.split('\n') {
+        let Some(max_width) = max_width else {
+            lines.push(paragraph.to_string());
+            continue;
+        };
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            if !line.is_empty() {
+                let candidate = format!("{line} {word}");
+                if measure_width(&candidate)? <= max_width {
+                    line = candidate;
+                    continue;
+                }
+                lines.push(std::mem::take(&mut line));
+            }
+            line.push_str(word);
+        }
+        lines.push(line);
+    }
+    Ok(lines)
 }
 
 #[cfg(test)]
@@ -64,6 +100,35 @@ pub mod tests {
         );
     }
 
+    #[test]
+    fn test_ascent() {
+        let mut font_pool = FontPool::new();
+        let font_idx = font_pool
+            .load_font("assets/fonts/OpenSans-Regular.ttf")
+            .unwrap();
+        assert_eq!(font_pool.text_ascent(font_idx, 16.0), 17.101563);
+    }
+
+    fn wrap(text: &str, max_width: Option<Float>) -> Vec<String> {
+        // Every character is 1 unit wide
+        wrap_text(text, max_width, |line| Ok(line.chars().count() as Float)).unwrap()
+    }
+
+    #[test]
+    fn test_wrap_text() {
+        assert_eq!(
+            wrap("the quick brown fox", Some(10.0)),
+            vec!["the quick", "brown fox"]
+        );
+        assert_eq!(
+            wrap("a verylongword b", Some(5.0)),
+            vec!["a", "verylongword", "b"]
+        );
+        assert_eq!(wrap("one\ntwo three", None), vec!["one", "two three"]);
+        assert_eq!(wrap("", Some(5.0)), vec![""]);
+        assert_eq!(wrap("a b c", Some(0.0)), vec!["a", "b", "c"]);
+    }
+
     #[test]
     fn test_simple() {
         assert_eq!(
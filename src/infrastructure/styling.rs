@@ -8,6 +8,10 @@ pub struct Styling {
     pub text_color: Color,
     pub highlight_color: Color,
     pub inactive_color: Color,
+    /// Marks invalid input
+    pub error_color: Color,
+    /// Dims the content below dialogs
+    pub backdrop_color: Color,
 
     // Button colors
     pub button_color: Color,
@@ -27,6 +31,8 @@ impl Styling {
             text_color: Color::BLACK,
             highlight_color: Color::hsl(300.0, 0.8, 0.3).unwrap(),
             inactive_color: Color::gray(220),
+            error_color: Color::hsl(0.0, 0.8, 0.45).unwrap(),
+            backdrop_color: Color::BLACK.with_alpha(80),
             button_color: Color::gray(210),
             button_hover_color: Color::gray(230),
             button_pressed_color: Color::gray(240),
@@ -42,6 +48,8 @@ impl Styling {
             text_color: Color::gray(230),
             highlight_color: Color::hsl(300.0, 0.8, 0.3).unwrap(),
             inactive_color: Color::gray(80),
+            error_color: Color::hsl(0.0, 0.7, 0.55).unwrap(),
+            backdrop_color: Color::BLACK.with_alpha(140),
             button_color: Color::gray(40),
             button_hover_color: Color::gray(60),
             button_pressed_color: Color::gray(80),
@@ -31,11 +31,12 @@ impl<'a> TextEditState<'a> {
                     self.new_text = Some(new_text);
                 }
             }
-            InputEventKind::KeyInput(key) => match key {
+            InputEventKind::KeyDown { key, .. } => match key {
                 KeyboardKey::ArrowLeft => {
                     if self.cursor_position > 0 {
                         let len = self.text[..self.cursor_position]
-                            .chars().next_back()
+                            .chars()
+                            .next_back()
                             .unwrap()
                             .len_utf8();
                         self.cursor_position -= len;
@@ -68,7 +69,8 @@ impl<'a> TextEditState<'a> {
                     if self.cursor_position > 0 {
                         let mut new_text = self.text.to_string();
                         let len = self.text[..self.cursor_position]
-                            .chars().next_back()
+                            .chars()
+                            .next_back()
                             .unwrap()
                             .len_utf8();
                         self.cursor_position -= len;
@@ -0,0 +1,94 @@
+use crate::ast::value::ExpressionValue;
+use crate::bail;
+use crate::result::ViuiResult;
+
+/// Properties that can be set on any node besides the layout properties, like `tooltip`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CommonProps {
+    /// Position in the Tab order, set by the `tab_index` prop
+    ///
+    /// Nodes with a positive index come first, negative indices are skipped by Tab.
+    pub tab_index: Option<i32>,
+    /// Text shown when the pointer rests on the node, set by the `tooltip` prop
+    pub tooltip: Option<String>,
+}
+
+impl CommonProps {
+    pub const NAMES: &'static [&'static str] = &["tab_index", "tooltip"];
+
+    pub fn is_common_prop(name: &str) -> bool {
+        Self::NAMES.contains(&name)
+    }
+
+    /// Sets a common property from its value in the UI language, validating the value
+    pub fn set(&mut self, name: &str, value: &ExpressionValue) -> ViuiResult<()> {
+        match (name, value) {
+            ("tab_index", ExpressionValue::Float(index))
+                if index.fract() == 0.0 && (i32::MIN as f32..=i32::MAX as f32).contains(index) =>
+            {
+                self.tab_index = Some(*index as i32);
+            }
+            ("tab_index", value) => {
+                bail!(
+                    "Invalid value '{}' for 'tab_index', expected an integer",
+                    value
+                )
+            }
+            ("tooltip", ExpressionValue::String(tooltip)) => {
+                self.tooltip = (!tooltip.is_empty()).then(|| tooltip.clone());
+            }
+            ("tooltip", value) => {
+                bail!("Invalid value '{}' for 'tooltip', expected a string", value)
+            }
+            _ => bail!("Unknown common property '{}'", name),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use expect_test::{expect, Expect};
+
+    fn check_error(name: &str, value: ExpressionValue, expected: Expect) {
+        let error = CommonProps::default().set(name, &value).unwrap_err();
+        expected.assert_eq(&error.0.current_context().to_string());
+    }
+
+    #[test]
+    fn set_common_props() {
+        let mut props = CommonProps::default();
+        props
+            .set("tab_index", &ExpressionValue::Float(-1.0))
+            .unwrap();
+        props
+            .set("tooltip", &ExpressionValue::String("Saves".into()))
+            .unwrap();
+        assert_eq!(props.tab_index, Some(-1));
+        assert_eq!(props.tooltip.as_deref(), Some("Saves"));
+        props
+            .set("tooltip", &ExpressionValue::String("".into()))
+            .unwrap();
+        assert_eq!(props.tooltip, None);
+    }
+
+    #[test]
+    fn invalid_common_props() {
+        check_error(
+            "tab_index",
+            ExpressionValue::Float(1.5),
+            expect!["General Error: Invalid value '1.5' for 'tab_index', expected an integer"],
+        );
+        check_error(
+            "tab_index",
+            ExpressionValue::String("1".into()),
+            expect!["General Error: Invalid value '1' for 'tab_index', expected an integer"],
+        );
+        check_error(
+            "tooltip",
+            ExpressionValue::Float(1.0),
+            expect!["General Error: Invalid value '1' for 'tooltip', expected a string"],
+        );
+    }
+}
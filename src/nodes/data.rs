@@ -1,10 +1,14 @@
 use crate::arenal::Idx;
-use crate::ast::nodes::ExpressionAst;
+use crate::ast::nodes::{ExpressionAst, ExpressionKind};
+use crate::bail;
 use crate::err;
+use crate::nodes::common::CommonProps;
 use crate::nodes::item::ItemIdx;
+use crate::nodes::layout::LayoutProps;
 use crate::nodes::types::{PropsBox, StateBox};
 use crate::result::ViuiResult;
-use crate::types::Rect;
+use crate::types::{Point, Rect, Size, Transform};
+use bevy_reflect::Reflect;
 use std::any::type_name;
 use std::collections::HashMap;
 use std::fmt::{Debug, Formatter};
@@ -13,11 +17,18 @@ pub struct NodeData {
     pub tag: String,
     pub kind_index: usize,
     pub layout: LayoutInfo,
+    pub layout_props: LayoutProps,
     pub state: StateBox,
     pub props: PropsBox,
+    /// Incremented whenever a prop changes, so that only changed nodes are measured again
+    pub props_revision: u64,
+    /// Incremented whenever the state changes, so that only changed nodes are laid out again
+    pub state_revision: u64,
+    pub common_props: CommonProps,
     pub children: Vec<ItemIdx>,
     pub prop_expressions: Vec<PropExpression>,
-    pub event_mappings: HashMap<String, ExpressionAst>,
+    /// Mappings by event name, at most one each for the capture and the bubble phase
+    pub event_mappings: HashMap<String, Vec<EventMapping>>,
 }
 
 pub type NodeIdx = Idx<NodeData>;
@@ -31,6 +42,42 @@ impl Debug for NodeData {
 #[derive(Clone, Debug, Default)]
 pub struct LayoutInfo {
     pub bounds: Rect,
+    /// Transform from node local coordinates to window coordinates, as last rendered
+    pub transform: Transform,
+    /// Clip bounds in window coordinates, as last rendered
+    pub clip_bounds: Option<Rect>,
+    /// Size of the children including any overflow
+    pub content_size: Size,
+    /// Visible area of the enclosing scroll containers in window coordinates
+    pub viewport: Option<Rect>,
+    /// Area of the popup in window coordinates, as last rendered
+    pub popup_bounds: Option<Rect>,
+}
+
+impl LayoutInfo {
+    /// Checks whether the given window position hits the node, taking transforms and clipping into account
+    pub fn contains(&self, position: Point) -> bool {
+        // Popups are not clipped
+        if let Some(popup_bounds) = &self.popup_bounds {
+            if popup_bounds.contains(position) {
+                return true;
+            }
+        }
+        if let Some(viewport) = &self.viewport {
+            if !viewport.contains(position) {
+                return false;
+            }
+        }
+        if let Some(clip_bounds) = &self.clip_bounds {
+            if !clip_bounds.contains(position) {
+                return false;
+            }
+        }
+        let Some(inverse) = self.transform.inverse() else {
+            return false;
+        };
+        Rect::from_size(self.bounds.size).contains(inverse.transform_point(position))
+    }
 }
 
 #[derive(Clone)]
@@ -39,6 +86,36 @@ pub struct PropExpression {
     pub expression: ExpressionAst,
 }
 
+/// Message sent when an event reaches a node, e.g. `@click.stop=Save`
+#[derive(Clone)]
+pub struct EventMapping {
+    pub expression: ExpressionAst,
+    /// Handles the event on the way down to the target instead of on the way up
+    pub capture: bool,
+    pub stop_propagation: bool,
+    pub prevent_default: bool,
+}
+
+impl EventMapping {
+    pub fn new(expression: ExpressionAst, modifiers: &[String]) -> ViuiResult<Self> {
+        let mut mapping = Self {
+            expression,
+            capture: false,
+            stop_propagation: false,
+            prevent_default: false,
+        };
+        for modifier in modifiers {
+            match modifier.as_str() {
+                "capture" => mapping.capture = true,
+                "stop" => mapping.stop_propagation = true,
+                "prevent" => mapping.prevent_default = true,
+                _ => bail!("Unknown event modifier '{}'", modifier),
+            }
+        }
+        Ok(mapping)
+    }
+}
+
 impl NodeData {
     pub fn cast_state_mut_and_props<S: 'static, P: 'static>(&mut self) -> ViuiResult<(&mut S, &P)> {
         let state = self
@@ -68,8 +145,34 @@ impl NodeData {
         Ok((state, props))
     }
 
+    /// Whether the prop is set by an expression other than a literal, which then controls it
+    pub fn is_prop_bound(&self, field_name: &str) -> bool {
+        self.prop_expressions.iter().any(|expression| {
+            expression.field_name == field_name
+                && !matches!(expression.expression.data(), ExpressionKind::Literal(_))
+        })
+    }
+
+    /// Increments the state revision if the state differs from a snapshot taken with `clone_value`
+    pub fn compare_state(&mut self, snapshot: &dyn Reflect) {
+        if self.state.reflect_partial_eq(snapshot) != Some(true) {
+            self.state_revision += 1;
+        }
+    }
+
+    /// Calls `update` with the node, incrementing the state revision if it changed the state
+    pub fn track_state<T>(&mut self, update: impl FnOnce(&mut Self) -> T) -> T {
+        let snapshot = self.state.clone_value();
+        let result = update(self);
+        self.compare_state(snapshot.as_ref());
+        result
+    }
+
     pub fn set_bounds(&mut self, bounds: Rect) {
         self.layout.bounds = bounds;
+        // Untransformed until the node is rendered
+        self.layout.transform = Transform::translation(bounds.origin.x, bounds.origin.y);
+        self.layout.clip_bounds = None;
     }
 
     pub fn bounds(&self) -> &Rect {
@@ -1,19 +1,53 @@
-use crate::ast::nodes::ItemAst;
+use crate::ast::nodes::{ItemAst, NodeAst};
 use crate::infrastructure::layout_context::LayoutContext;
 use crate::nodes::data::NodeData;
-use crate::nodes::elements::kind::LayoutConstraints;
-use crate::nodes::types::{NodeEventHandler, NodeEvents, NodeProps, NodeRenderFn, NodeState};
+use crate::nodes::elements::kind::{
+    EventTrigger, ForCollection, ForValue, LayoutConstraints, LayoutUpdateContext, UpdateContext,
+    ValueField,
+};
+use crate::nodes::types::{
+    NodeEventHandler, NodeEvents, NodeProps, NodeRenderFn, NodeState, PropsBox, StateBox,
+};
 use crate::result::ViuiResult;
 
 pub type LayoutFn =
     Box<dyn Fn(&mut LayoutContext, &mut NodeData) -> ViuiResult<LayoutConstraints> + Send>;
 
+pub type LayoutUpdatedFn =
+    Box<dyn Fn(&mut NodeData, &mut LayoutUpdateContext) -> ViuiResult<()> + Send>;
+
+/// Updates a node taken out of the arena, so that the nodes below it can be updated as well
+pub type UpdateChildrenFn =
+    Box<dyn Fn(&mut StateBox, &PropsBox, &mut UpdateContext) -> ViuiResult<()> + Send>;
+
+pub type ForValuesFn =
+    Box<dyn Fn(&mut NodeData, &dyn ForCollection, &ValueField) -> ViuiResult<Vec<ForValue>> + Send>;
+
+pub type ExpandTemplateFn = Box<dyn Fn(&NodeAst) -> ViuiResult<Option<NodeAst>> + Send>;
+
+pub type ShowsChildrenFn = Box<dyn Fn(&NodeData) -> bool + Send>;
+
+pub type DescendantEventHandler = Box<
+    dyn Fn(&dyn NodeEvents, &mut NodeData, &mut EventTrigger<Box<dyn NodeEvents>>) -> ViuiResult<()>
+        + Send,
+>;
+
 pub struct NodeDescriptor {
     pub(crate) kind_index: usize,
     pub make_state: Box<dyn Fn() -> ViuiResult<Box<dyn NodeState>> + Send>,
     pub make_props: Box<dyn Fn() -> ViuiResult<Box<dyn NodeProps>> + Send>,
     pub event_handler: NodeEventHandler<Box<dyn NodeEvents>>,
     pub layout_fn: LayoutFn,
+    pub layout_updated_fn: LayoutUpdatedFn,
+    pub expand_template_fn: ExpandTemplateFn,
+    pub shows_children_fn: ShowsChildrenFn,
+    pub update_children_fn: UpdateChildrenFn,
+    pub for_values_fn: ForValuesFn,
+    pub descendant_event_handler: DescendantEventHandler,
     pub render_fn: NodeRenderFn,
     pub children: Vec<ItemAst>,
+    /// Whether the node can receive keyboard focus
+    pub focusable: bool,
+    /// Whether the node edits typed text while focused
+    pub edits_text: bool,
 }
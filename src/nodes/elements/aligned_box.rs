@@ -0,0 +1,43 @@
+use crate::infrastructure::layout_context::LayoutContext;
+use crate::nodes::elements::kind::{Alignment, Element, LayoutConstraints, NoEvents};
+use crate::nodes::types::NodeProps;
+use crate::render::context::RenderContext;
+use crate::render::parameters::RenderParameters;
+use crate::result::ViuiResult;
+use bevy_reflect::Reflect;
+
+pub struct AlignedBoxElement {}
+
+impl Element for AlignedBoxElement {
+    const NAME: &'static str = "box";
+    type State = ();
+    type Props = AlignedBoxElementProps;
+    type Events = NoEvents;
+    fn render_element(
+        _render_context: &mut RenderContext,
+        _parameters: &RenderParameters,
+        _state: &Self::State,
+        _props: &Self::Props,
+    ) {
+    }
+
+    fn layout_element(
+        _layout_context: &mut LayoutContext,
+        _state: &mut Self::State,
+        props: &Self::Props,
+    ) -> ViuiResult<LayoutConstraints> {
+        Ok(LayoutConstraints::AlignedLayout {
+            horizontal: Alignment::parse(&props.horizontal)?,
+            vertical: Alignment::parse(&props.vertical)?,
+        })
+    }
+}
+
+/// Alignment of the children, either `start`, `center` or `end`, centered if unset
+#[derive(Default, Reflect, Debug)]
+pub struct AlignedBoxElementProps {
+    pub horizontal: String,
+    pub vertical: String,
+}
+
+impl NodeProps for AlignedBoxElementProps {}
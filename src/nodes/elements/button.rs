@@ -1,33 +1,39 @@
 use crate::infrastructure::layout_context::LayoutContext;
+use crate::nodes::data::LayoutInfo;
 use crate::nodes::elements::kind::{Element, EventTrigger, LayoutConstraints};
 use crate::nodes::events::{InputEvent, InputEventKind};
 use crate::nodes::types::{NodeEvents, NodeProps, NodeState};
-use crate::render::command::RenderCommand;
+use crate::render::command::{DrawMode, RenderCommand};
 use crate::render::context::RenderContext;
 use crate::render::parameters::RenderParameters;
 use crate::result::ViuiResult;
-use crate::types::{Point, Rect, Size};
+use crate::types::{Float, Point, Rect, Size};
 use bevy_reflect::Reflect;
 
 pub struct ButtonElement {}
 
+const PADDING_X: Float = 15.0;
+const PADDING_Y: Float = 5.0;
+
 impl Element for ButtonElement {
     const NAME: &'static str = "button";
     type State = ButtonElementState;
     type Props = ButtonElementProps;
     type Events = ButtonEvents;
+    const FOCUSABLE: bool = true;
 
     fn handle_event(
         event: &InputEvent,
         state: &mut Self::State,
         _props: &Self::Props,
+        _layout: &LayoutInfo,
         event_trigger: &mut EventTrigger<ButtonEvents>,
     ) {
         match event.kind() {
-            InputEventKind::MouseOver => {
+            InputEventKind::MouseEnter => {
                 state.is_hovering = true;
             }
-            InputEventKind::MouseOut => {
+            InputEventKind::MouseLeave => {
                 state.is_hovering = false;
             }
             InputEventKind::MousePress(..) => {
@@ -56,28 +62,44 @@ impl Element for ButtonElement {
             render_context.add_command(RenderCommand::SetFillColor(styling.button_color));
         }
         let stroke_width = 2.0f32;
+        let size = render_context.size();
         render_context.add_command(RenderCommand::SetStrokeColor(styling.text_color));
         render_context.add_command(RenderCommand::SetStrokeWidth(2.0));
-        render_context.add_command(RenderCommand::FillRoundRect {
+        render_context.add_command(RenderCommand::DrawRoundRect {
             rect: Rect::new(
                 Point::new(stroke_width, stroke_width),
-                Size::new(200.0 - stroke_width * 2.0, 40.0 - stroke_width * 2.0),
+                Size::new(
+                    size.width - stroke_width * 2.0,
+                    size.height - stroke_width * 2.0,
+                ),
             ),
             radius: 5.0,
+            mode: DrawMode::FillAndStroke,
+        });
+        // Caption is centered in the button
+        let text_size = render_context.measure_text(&props.label).unwrap();
+        render_context.add_command(RenderCommand::Translate {
+            x: (size.width - text_size.width) / 2.0,
+            y: (size.height - text_size.height) / 2.0 + render_context.text_ascent(),
         });
-        render_context.add_command(RenderCommand::Translate { x: 15.0, y: 25.0 });
         render_context.add_command(RenderCommand::DrawText(props.label.clone()));
     }
 
     fn layout_element(
         _layout_context: &mut LayoutContext,
         _state: &mut Self::State,
-        _props: &Self::Props,
+        props: &Self::Props,
     ) -> ViuiResult<LayoutConstraints> {
-        Ok(LayoutConstraints::FixedLayout {
-            width: 200.0,
-            height: 40.0,
-        })
+        let label = props.label.clone();
+        Ok(LayoutConstraints::Measured(Box::new(
+            move |measure_context, _max_width| {
+                let text_size = measure_context.measure_text(&label)?;
+                Ok(Size::new(
+                    text_size.width + 2.0 * PADDING_X,
+                    text_size.height + 2.0 * PADDING_Y,
+                ))
+            },
+        )))
     }
 }
 
@@ -0,0 +1,179 @@
+use crate::infrastructure::layout_context::LayoutContext;
+use crate::infrastructure::styling::Styling;
+use crate::nodes::data::LayoutInfo;
+use crate::nodes::elements::kind::{Element, EventTrigger, LayoutConstraints};
+use crate::nodes::events::{InputEvent, InputEventKind, KeyboardKey};
+use crate::nodes::types::{NodeEvents, NodeProps, NodeState};
+use crate::render::command::{DrawMode, RenderCommand};
+use crate::render::context::RenderContext;
+use crate::render::parameters::RenderParameters;
+use crate::result::ViuiResult;
+use crate::types::{Color, Float, Point, Rect, Size};
+use bevy_reflect::Reflect;
+
+/// Size of the box, switches are twice as wide
+pub(crate) const INDICATOR_SIZE: Float = 18.0;
+/// Space between the indicator and the label
+pub(crate) const LABEL_SPACING: Float = 8.0;
+
+/// Box that is checked or unchecked by clicking it or pressing Space
+pub struct CheckboxElement {}
+
+impl Element for CheckboxElement {
+    const NAME: &'static str = "checkbox";
+    type State = CheckboxElementState;
+    type Props = CheckboxElementProps;
+    type Events = CheckboxEvents;
+    const FOCUSABLE: bool = true;
+
+    fn handle_event(
+        event: &InputEvent,
+        state: &mut Self::State,
+        props: &Self::Props,
+        _layout: &LayoutInfo,
+        event_trigger: &mut EventTrigger<CheckboxEvents>,
+    ) {
+        match event.kind() {
+            InputEventKind::MouseEnter => {
+                state.is_hovering = true;
+            }
+            InputEventKind::MouseLeave => {
+                state.is_hovering = false;
+            }
+            InputEventKind::MousePress(..) => {
+                state.is_pressed = true;
+                event_trigger(CheckboxEvents::Change {
+                    new_value: !props.checked,
+                });
+            }
+            InputEventKind::MouseRelease(..) => {
+                state.is_pressed = false;
+            }
+            InputEventKind::KeyDown {
+                key: KeyboardKey::Space,
+                repeat: false,
+            } => {
+                event_trigger(CheckboxEvents::Change {
+                    new_value: !props.checked,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    fn render_element(
+        render_context: &mut RenderContext,
+        parameters: &RenderParameters,
+        state: &Self::State,
+        props: &Self::Props,
+    ) {
+        let styling = parameters.styling();
+        let top = (render_context.size().height - INDICATOR_SIZE) / 2.0;
+        let rect = Rect::new(
+            Point::new(1.0, top),
+            Size::new(INDICATOR_SIZE - 2.0, INDICATOR_SIZE - 2.0),
+        );
+        let fill_color = if props.checked {
+            styling.highlight_color
+        } else {
+            indicator_color(styling, state)
+        };
+        render_context.add_command(RenderCommand::SetFillColor(fill_color));
+        render_context.add_command(RenderCommand::SetStrokeColor(styling.text_color));
+        render_context.add_command(RenderCommand::SetStrokeWidth(2.0));
+        render_context.add_command(RenderCommand::DrawRoundRect {
+            rect,
+            radius: 3.0,
+            mode: DrawMode::FillAndStroke,
+        });
+        if props.checked {
+            // Check mark
+            let x = rect.min_x();
+            let y = rect.min_y();
+            render_context.add_command(RenderCommand::SetStrokeColor(styling.background_color));
+            render_context.add_command(RenderCommand::Line {
+                start: Point::new(x + 4.0, y + 8.0),
+                end: Point::new(x + 7.0, y + 11.0),
+            });
+            render_context.add_command(RenderCommand::Line {
+                start: Point::new(x + 7.0, y + 11.0),
+                end: Point::new(x + 12.0, y + 5.0),
+            });
+        }
+        draw_label(render_context, styling, INDICATOR_SIZE, &props.label);
+    }
+
+    fn layout_element(
+        _layout_context: &mut LayoutContext,
+        _state: &mut Self::State,
+        props: &Self::Props,
+    ) -> ViuiResult<LayoutConstraints> {
+        Ok(measure_labeled(INDICATOR_SIZE, &props.label))
+    }
+}
+
+/// Fill color of an unchecked indicator in its current interaction state
+pub(crate) fn indicator_color(styling: &Styling, state: &CheckboxElementState) -> Color {
+    if state.is_pressed {
+        styling.button_pressed_color
+    } else if state.is_hovering {
+        styling.button_hover_color
+    } else {
+        styling.button_color
+    }
+}
+
+/// Draws the label right of an indicator of the given width, centered vertically
+pub(crate) fn draw_label(
+    render_context: &mut RenderContext,
+    styling: &Styling,
+    indicator_width: Float,
+    label: &str,
+) {
+    if label.is_empty() {
+        return;
+    }
+    let text_size = render_context.measure_text(label).unwrap();
+    render_context.add_command(RenderCommand::SetStrokeColor(styling.text_color));
+    render_context.add_command(RenderCommand::Translate {
+        x: indicator_width + LABEL_SPACING,
+        y: (render_context.size().height - text_size.height) / 2.0 + render_context.text_ascent(),
+    });
+    render_context.add_command(RenderCommand::DrawText(label.to_string()));
+}
+
+/// Sizes an indicator of the given width followed by its label
+pub(crate) fn measure_labeled(indicator_width: Float, label: &str) -> LayoutConstraints {
+    let label = label.to_string();
+    LayoutConstraints::Measured(Box::new(move |measure_context, _max_width| {
+        if label.is_empty() {
+            return Ok(Size::new(indicator_width, INDICATOR_SIZE));
+        }
+        let text_size = measure_context.measure_text(&label)?;
+        Ok(Size::new(
+            indicator_width + LABEL_SPACING + text_size.width,
+            text_size.height.max(INDICATOR_SIZE),
+        ))
+    }))
+}
+
+#[derive(Default, Reflect, Debug)]
+pub struct CheckboxElementProps {
+    pub checked: bool,
+    pub label: String,
+}
+
+impl NodeProps for CheckboxElementProps {}
+
+#[derive(Reflect, Debug, Default)]
+pub struct CheckboxElementState {
+    pub is_hovering: bool,
+    pub is_pressed: bool,
+}
+impl NodeState for CheckboxElementState {}
+
+#[derive(Reflect, Debug)]
+pub enum CheckboxEvents {
+    Change { new_value: bool },
+}
+impl NodeEvents for CheckboxEvents {}
@@ -0,0 +1,241 @@
+use crate::infrastructure::layout_context::LayoutContext;
+use crate::nodes::data::LayoutInfo;
+use crate::nodes::elements::kind::{
+    BoxLayout, Element, EventTrigger, LayoutConstraints, LayoutUpdateContext, UpdateContext,
+};
+use crate::nodes::events::{EventPhase, InputEvent, InputEventKind, KeyboardKey};
+use crate::nodes::types::{NodeEvents, NodeProps, NodeState};
+use crate::render::command::RenderCommand;
+use crate::render::context::RenderContext;
+use crate::render::parameters::RenderParameters;
+use crate::result::ViuiResult;
+use crate::types::{Float, Point, Rect, SideOffsets, Size};
+use bevy_reflect::Reflect;
+use std::time::{Duration, Instant};
+
+pub(crate) const HEADER_HEIGHT: Float = 28.0;
+const PADDING_X: Float = 8.0;
+/// Width of the chevron at the left of the header
+const CHEVRON_WIDTH: Float = 12.0;
+const ANIMATION_DURATION: Duration = Duration::from_millis(200);
+
+/// Section with a header that expands and collapses its children, animating its height
+///
+/// Clicking the header or pressing Space or Enter toggles it. Without a bound `expanded` prop the
+/// node keeps its expanded state, starting with the prop value. Collapsed children are not
+/// evaluated.
+pub struct CollapsibleElement {}
+
+impl Element for CollapsibleElement {
+    const NAME: &'static str = "collapsible";
+    type State = CollapsibleElementState;
+    type Props = CollapsibleElementProps;
+    type Events = CollapsibleEvents;
+    const FOCUSABLE: bool = true;
+
+    fn shows_children(state: &Self::State) -> bool {
+        state.shows_content()
+    }
+
+    fn update_children(
+        state: &mut Self::State,
+        props: &Self::Props,
+        context: &mut UpdateContext,
+    ) -> ViuiResult<()> {
+        state.sync(props.expanded, context.is_prop_bound("expanded"));
+        Ok(())
+    }
+
+    fn handle_event(
+        event: &InputEvent,
+        state: &mut Self::State,
+        _props: &Self::Props,
+        layout: &LayoutInfo,
+        event_trigger: &mut EventTrigger<CollapsibleEvents>,
+    ) {
+        let mut toggle = |state: &mut CollapsibleElementState| {
+            state.set_expanded(!state.expanded);
+            event_trigger(CollapsibleEvents::Change {
+                new_value: state.expanded,
+            });
+        };
+        match event.kind() {
+            InputEventKind::MouseMove(position) => {
+                state.is_hovering = header(&layout.bounds).contains(*position);
+            }
+            InputEventKind::MouseLeave => {
+                state.is_hovering = false;
+            }
+            InputEventKind::MousePress(position) if header(&layout.bounds).contains(*position) => {
+                toggle(state);
+            }
+            InputEventKind::KeyDown {
+                key: KeyboardKey::Space | KeyboardKey::Enter,
+                repeat: false,
+            } if event.phase() == EventPhase::Target => {
+                toggle(state);
+            }
+            _ => {}
+        }
+    }
+
+    fn render_element(
+        render_context: &mut RenderContext,
+        parameters: &RenderParameters,
+        state: &Self::State,
+        props: &Self::Props,
+    ) {
+        let styling = parameters.styling();
+        let width = render_context.size().width;
+        let fill_color = if state.is_hovering {
+            styling.button_hover_color
+        } else {
+            styling.button_color
+        };
+        render_context.add_command(RenderCommand::SetFillColor(fill_color));
+        render_context.add_command(RenderCommand::FillRect {
+            rect: Rect::from_size(Size::new(width, HEADER_HEIGHT)),
+        });
+        // Chevron pointing right, turned down while expanding
+        render_context.add_command(RenderCommand::Save);
+        render_context.add_command(RenderCommand::SetStrokeColor(styling.text_color));
+        render_context.add_command(RenderCommand::SetStrokeWidth(2.0));
+        render_context.add_command(RenderCommand::Translate {
+            x: PADDING_X + CHEVRON_WIDTH / 2.0,
+            y: HEADER_HEIGHT / 2.0,
+        });
+        render_context.add_command(RenderCommand::Rotate {
+            angle: state.progress * std::f32::consts::FRAC_PI_2,
+        });
+        render_context.add_command(RenderCommand::Line {
+            start: Point::new(-2.0, -5.0),
+            end: Point::new(3.0, 0.0),
+        });
+        render_context.add_command(RenderCommand::Line {
+            start: Point::new(3.0, 0.0),
+            end: Point::new(-2.0, 5.0),
+        });
+        render_context.add_command(RenderCommand::Restore);
+
+        let text_size = render_context.measure_text(&props.title).unwrap();
+        render_context.add_command(RenderCommand::SetStrokeColor(styling.text_color));
+        render_context.add_command(RenderCommand::Translate {
+            x: 2.0 * PADDING_X + CHEVRON_WIDTH,
+            y: (HEADER_HEIGHT - text_size.height) / 2.0 + render_context.text_ascent(),
+        });
+        render_context.add_command(RenderCommand::DrawText(props.title.clone()));
+    }
+
+    fn layout_element(
+        layout_context: &mut LayoutContext,
+        state: &mut Self::State,
+        _props: &Self::Props,
+    ) -> ViuiResult<LayoutConstraints> {
+        state.advance();
+        let height = state.animation_start.map(|_| {
+            // Eased in and out
+            let progress = state.progress * state.progress * (3.0 - 2.0 * state.progress);
+            HEADER_HEIGHT + progress * state.content_height
+        });
+        if height.is_some() {
+            layout_context.set_animated();
+        }
+        Ok(LayoutConstraints::BoxLayout(BoxLayout {
+            height,
+            padding: SideOffsets::new(HEADER_HEIGHT, 0.0, 0.0, 0.0),
+            // The content is clipped while animating
+            clip: height.is_some(),
+            ..Default::default()
+        }))
+    }
+
+    fn layout_updated(
+        state: &mut Self::State,
+        _props: &Self::Props,
+        context: &mut LayoutUpdateContext,
+    ) {
+        let content_size = context.layout().content_size;
+        state.content_height = (content_size.height - HEADER_HEIGHT).max(0.0);
+    }
+}
+
+#[derive(Default, Reflect, Debug)]
+pub struct CollapsibleElementProps {
+    pub title: String,
+    /// Initially expanded, or expanded whenever it is bound to the app state
+    pub expanded: bool,
+}
+
+impl NodeProps for CollapsibleElementProps {}
+
+#[derive(Default, Reflect, Debug)]
+pub struct CollapsibleElementState {
+    pub expanded: bool,
+    pub is_hovering: bool,
+    /// Fraction of the content shown, animated towards 1 when expanded and 0 when collapsed
+    pub progress: Float,
+    #[reflect(ignore)]
+    is_initialized: bool,
+    #[reflect(ignore)]
+    animation_start: Option<Instant>,
+    /// Height of the children as of the last layout pass
+    #[reflect(ignore)]
+    content_height: Float,
+}
+
+impl CollapsibleElementState {
+    /// Whether the children are shown, which they are until collapsing finished
+    fn shows_content(&self) -> bool {
+        self.expanded || self.progress > 0.0
+    }
+
+    /// Takes the `expanded` prop, initially and whenever it is bound
+    fn sync(&mut self, expanded: bool, is_bound: bool) {
+        if !self.is_initialized {
+            self.is_initialized = true;
+            self.expanded = expanded;
+            self.progress = if expanded { 1.0 } else { 0.0 };
+        } else if is_bound {
+            self.set_expanded(expanded);
+        }
+    }
+
+    /// Starts animating towards the expanded state, continuing from the current progress
+    fn set_expanded(&mut self, expanded: bool) {
+        if expanded == self.expanded {
+            return;
+        }
+        self.expanded = expanded;
+        let done = if expanded {
+            self.progress
+        } else {
+            1.0 - self.progress
+        };
+        self.animation_start = Some(Instant::now() - ANIMATION_DURATION.mul_f32(done));
+    }
+
+    fn advance(&mut self) {
+        let Some(animation_start) = self.animation_start else {
+            return;
+        };
+        let done =
+            (animation_start.elapsed().as_secs_f32() / ANIMATION_DURATION.as_secs_f32()).min(1.0);
+        self.progress = if self.expanded { done } else { 1.0 - done };
+        if done >= 1.0 {
+            self.animation_start = None;
+        }
+    }
+}
+
+impl NodeState for CollapsibleElementState {}
+
+/// Header of the node with the window bounds
+fn header(bounds: &Rect) -> Rect {
+    Rect::new(bounds.origin, Size::new(bounds.width(), HEADER_HEIGHT))
+}
+
+#[derive(Reflect, Debug)]
+pub enum CollapsibleEvents {
+    Change { new_value: bool },
+}
+impl NodeEvents for CollapsibleEvents {}
@@ -0,0 +1,196 @@
+use crate::infrastructure::layout_context::LayoutContext;
+use crate::infrastructure::styling::Styling;
+use crate::nodes::data::LayoutInfo;
+use crate::nodes::elements::kind::{Element, EventTrigger, LayoutConstraints, LayoutUpdateContext};
+use crate::nodes::events::{InputEvent, InputEventKind, KeyboardKey};
+use crate::nodes::types::{NodeEvents, NodeProps, NodeState};
+use crate::render::command::{DrawMode, RenderCommand};
+use crate::render::context::RenderContext;
+use crate::render::parameters::RenderParameters;
+use crate::result::ViuiResult;
+use crate::types::{Float, Point, Rect, Size};
+use bevy_reflect::Reflect;
+
+/// Space between the edge of the panel and the children
+pub(crate) const PANEL_PADDING: Float = 12.0;
+
+/// Modal dialog centered in the window, shown above all other nodes while it is declared
+///
+/// Use an `if` to open and close it. While shown, it blocks the pointer for the nodes below and
+/// keeps the focus among its children. Escape triggers `close`.
+pub struct DialogElement {}
+
+impl Element for DialogElement {
+    const NAME: &'static str = "dialog";
+    type State = LayerElementState;
+    type Props = DialogElementProps;
+    type Events = LayerEvents;
+
+    fn handle_event(
+        event: &InputEvent,
+        _state: &mut Self::State,
+        _props: &Self::Props,
+        _layout: &LayoutInfo,
+        event_trigger: &mut EventTrigger<LayerEvents>,
+    ) {
+        close_on_escape(event, event_trigger);
+    }
+
+    fn render_element(
+        render_context: &mut RenderContext,
+        parameters: &RenderParameters,
+        state: &Self::State,
+        props: &Self::Props,
+    ) {
+        let styling = parameters.styling();
+        if props.backdrop {
+            render_context.add_command(RenderCommand::SetFillColor(styling.backdrop_color));
+            render_context.add_command(RenderCommand::FillRect {
+                rect: Rect::from_size(render_context.size()),
+            });
+        }
+        draw_panel(render_context, styling, state);
+    }
+
+    fn layout_element(
+        _layout_context: &mut LayoutContext,
+        _state: &mut Self::State,
+        _props: &Self::Props,
+    ) -> ViuiResult<LayoutConstraints> {
+        Ok(LayoutConstraints::LayerLayout { anchored: false })
+    }
+
+    fn layout_updated(
+        state: &mut Self::State,
+        _props: &Self::Props,
+        context: &mut LayoutUpdateContext,
+    ) {
+        state.set_panel(context);
+    }
+}
+
+/// Popup placed below the node it is declared in, shown above all other nodes
+///
+/// Like a dialog it blocks the pointer and keeps the focus, but it triggers `close` when pressed
+/// outside of its panel as well.
+pub struct PopupElement {}
+
+impl Element for PopupElement {
+    const NAME: &'static str = "popup";
+    type State = LayerElementState;
+    type Props = ();
+    type Events = LayerEvents;
+
+    fn handle_event(
+        event: &InputEvent,
+        state: &mut Self::State,
+        _props: &Self::Props,
+        _layout: &LayoutInfo,
+        event_trigger: &mut EventTrigger<LayerEvents>,
+    ) {
+        match event.kind() {
+            InputEventKind::MousePress(position) if !state.panel().contains(*position) => {
+                event_trigger(LayerEvents::Close);
+            }
+            _ => close_on_escape(event, event_trigger),
+        }
+    }
+
+    fn render_element(
+        render_context: &mut RenderContext,
+        parameters: &RenderParameters,
+        state: &Self::State,
+        _props: &Self::Props,
+    ) {
+        draw_panel(render_context, parameters.styling(), state);
+    }
+
+    fn layout_element(
+        _layout_context: &mut LayoutContext,
+        _state: &mut Self::State,
+        _props: &Self::Props,
+    ) -> ViuiResult<LayoutConstraints> {
+        Ok(LayoutConstraints::LayerLayout { anchored: true })
+    }
+
+    fn layout_updated(
+        state: &mut Self::State,
+        _props: &Self::Props,
+        context: &mut LayoutUpdateContext,
+    ) {
+        state.set_panel(context);
+    }
+}
+
+fn close_on_escape(event: &InputEvent, event_trigger: &mut EventTrigger<LayerEvents>) {
+    if let InputEventKind::KeyDown {
+        key: KeyboardKey::Escape,
+        ..
+    } = event.kind()
+    {
+        event_trigger(LayerEvents::Close);
+        event.stop_propagation();
+    }
+}
+
+/// Draws the panel behind the children, layers fill the window so it is in window coordinates
+fn draw_panel(render_context: &mut RenderContext, styling: &Styling, state: &LayerElementState) {
+    let stroke_width = 2.0f32;
+    render_context.add_command(RenderCommand::SetFillColor(styling.background_color));
+    render_context.add_command(RenderCommand::SetStrokeColor(styling.border_color));
+    render_context.add_command(RenderCommand::SetStrokeWidth(stroke_width));
+    render_context.add_command(RenderCommand::DrawRoundRect {
+        rect: state
+            .panel()
+            .inflate(-stroke_width / 2.0, -stroke_width / 2.0),
+        radius: 4.0,
+        mode: DrawMode::FillAndStroke,
+    });
+}
+
+#[derive(Default, Reflect, Debug)]
+pub struct DialogElementProps {
+    /// Dims the nodes below the dialog
+    pub backdrop: bool,
+}
+
+impl NodeProps for DialogElementProps {}
+
+/// State of dialogs and popups, which the UI lays out in a layer above the main tree
+#[derive(Default, Reflect, Debug)]
+pub struct LayerElementState {
+    /// Origin and size of the panel in window coordinates, set by the UI after each layout pass
+    panel: [Float; 4],
+}
+
+impl LayerElementState {
+    pub fn panel(&self) -> Rect {
+        let [x, y, width, height] = self.panel;
+        Rect::new(Point::new(x, y), Size::new(width, height))
+    }
+
+    /// Fits the panel around the window bounds of the children, as placed by the UI
+    fn set_panel(&mut self, context: &LayoutUpdateContext) {
+        let children_bounds = context
+            .children()
+            .iter()
+            .copied()
+            .reduce(|bounds, child_bounds| bounds.union(&child_bounds))
+            .unwrap_or(Rect::new(context.layout().bounds.center(), Size::zero()));
+        let panel = children_bounds.inflate(PANEL_PADDING, PANEL_PADDING);
+        self.panel = [
+            panel.origin.x,
+            panel.origin.y,
+            panel.size.width,
+            panel.size.height,
+        ];
+    }
+}
+
+impl NodeState for LayerElementState {}
+
+#[derive(Reflect, Debug)]
+pub enum LayerEvents {
+    Close,
+}
+impl NodeEvents for LayerEvents {}
@@ -0,0 +1,285 @@
+use crate::infrastructure::layout_context::LayoutContext;
+use crate::nodes::data::LayoutInfo;
+use crate::nodes::elements::kind::{Element, EventTrigger, LayoutConstraints};
+use crate::nodes::events::{InputEvent, InputEventKind, KeyboardKey};
+use crate::nodes::types::{NodeEvents, NodeProps, NodeState};
+use crate::render::command::{DrawMode, RenderCommand};
+use crate::render::context::RenderContext;
+use crate::render::parameters::RenderParameters;
+use crate::result::ViuiResult;
+use crate::types::{Float, Point, Rect, Size};
+use bevy_reflect::Reflect;
+use std::time::{Duration, Instant};
+
+const PADDING_X: Float = 10.0;
+const PADDING_Y: Float = 5.0;
+const MIN_WIDTH: Float = 120.0;
+/// Width of the arrow at the right edge
+const ARROW_WIDTH: Float = 20.0;
+const OPTION_HEIGHT: Float = 24.0;
+/// Typed characters are searched for together if typed within this time
+const TYPE_AHEAD_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Picks one of the `options`, which are shown in a popup above all other content
+///
+/// The popup opens on click or with the arrow keys, Enter and Space, and closes on Escape or when
+/// the dropdown loses focus. Typing selects the first option starting with the typed text, or
+/// highlights it while the popup is open.
+pub struct DropdownElement {}
+
+impl Element for DropdownElement {
+    const NAME: &'static str = "dropdown";
+    type State = DropdownElementState;
+    type Props = DropdownElementProps;
+    type Events = DropdownEvents;
+    const FOCUSABLE: bool = true;
+
+    fn handle_event(
+        event: &InputEvent,
+        state: &mut Self::State,
+        props: &Self::Props,
+        layout: &LayoutInfo,
+        event_trigger: &mut EventTrigger<DropdownEvents>,
+    ) {
+        let mut select = |index: usize| {
+            if let Some(option) = props.options.get(index) {
+                if *option != props.selected {
+                    event_trigger(DropdownEvents::Change {
+                        new_value: option.clone(),
+                    });
+                }
+            }
+        };
+        let last_index = props.options.len().saturating_sub(1);
+        match event.kind() {
+            InputEventKind::MouseEnter => {
+                state.is_hovering = true;
+            }
+            InputEventKind::MouseLeave => {
+                state.is_hovering = false;
+            }
+            InputEventKind::MousePress(position) => {
+                if state.is_open && position.y >= layout.bounds.max_y() {
+                    let index = ((position.y - layout.bounds.max_y()) / OPTION_HEIGHT) as usize;
+                    select(index);
+                    state.is_open = false;
+                } else if state.is_open {
+                    state.is_open = false;
+                } else {
+                    state.open(props);
+                }
+            }
+            InputEventKind::Blur => {
+                state.is_open = false;
+            }
+            InputEventKind::KeyDown { key, .. } => match key {
+                KeyboardKey::Escape if state.is_open => {
+                    state.is_open = false;
+                    event.stop_propagation();
+                }
+                KeyboardKey::ArrowDown | KeyboardKey::ArrowUp if !state.is_open => {
+                    state.open(props);
+                }
+                KeyboardKey::ArrowDown => {
+                    state.highlighted = (state.highlighted + 1).min(last_index);
+                }
+                KeyboardKey::ArrowUp => {
+                    state.highlighted = state.highlighted.saturating_sub(1);
+                }
+                KeyboardKey::Home | KeyboardKey::PageUp if state.is_open => {
+                    state.highlighted = 0;
+                }
+                KeyboardKey::End | KeyboardKey::PageDown if state.is_open => {
+                    state.highlighted = last_index;
+                }
+                KeyboardKey::Enter | KeyboardKey::Space if state.is_open => {
+                    select(state.highlighted);
+                    state.is_open = false;
+                }
+                KeyboardKey::Enter | KeyboardKey::Space => {
+                    state.open(props);
+                }
+                _ => {}
+            },
+            InputEventKind::Character(character) => {
+                let now = Instant::now();
+                if state
+                    .last_typed
+                    .is_none_or(|last_typed| now - last_typed > TYPE_AHEAD_TIMEOUT)
+                {
+                    state.type_ahead.clear();
+                }
+                // Space opens and closes the popup unless it is part of the typed text
+                if character.is_control() || (*character == ' ' && state.type_ahead.is_empty()) {
+                    return;
+                }
+                state.last_typed = Some(now);
+                state.type_ahead.extend(character.to_lowercase());
+                if let Some(index) = props
+                    .options
+                    .iter()
+                    .position(|option| option.to_lowercase().starts_with(&state.type_ahead))
+                {
+                    if state.is_open {
+                        state.highlighted = index;
+                    } else {
+                        select(index);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn render_element(
+        render_context: &mut RenderContext,
+        parameters: &RenderParameters,
+        state: &Self::State,
+        props: &Self::Props,
+    ) {
+        let styling = parameters.styling();
+        let stroke_width = 2.0f32;
+        let size = render_context.size();
+        if state.is_hovering {
+            render_context.add_command(RenderCommand::SetFillColor(styling.button_hover_color));
+        } else {
+            render_context.add_command(RenderCommand::SetFillColor(styling.button_color));
+        }
+        render_context.add_command(RenderCommand::SetStrokeColor(styling.border_color));
+        render_context.add_command(RenderCommand::SetStrokeWidth(stroke_width));
+        render_context.add_command(RenderCommand::DrawRoundRect {
+            rect: Rect::from_size(size).inflate(-stroke_width / 2.0, -stroke_width / 2.0),
+            radius: 3.0,
+            mode: DrawMode::FillAndStroke,
+        });
+        // Arrow pointing down
+        let center_x = size.width - ARROW_WIDTH / 2.0 - PADDING_X / 2.0;
+        let center_y = size.height / 2.0;
+        render_context.add_command(RenderCommand::SetStrokeColor(styling.text_color));
+        render_context.add_command(RenderCommand::Line {
+            start: Point::new(center_x - 5.0, center_y - 2.0),
+            end: Point::new(center_x, center_y + 3.0),
+        });
+        render_context.add_command(RenderCommand::Line {
+            start: Point::new(center_x, center_y + 3.0),
+            end: Point::new(center_x + 5.0, center_y - 2.0),
+        });
+
+        let (text, text_color) = if props.selected.is_empty() {
+            (&props.placeholder, styling.inactive_color)
+        } else {
+            (&props.selected, styling.text_color)
+        };
+        render_context.add_command(RenderCommand::Save);
+        render_context.add_command(RenderCommand::SetStrokeColor(text_color));
+        render_context.add_command(RenderCommand::Translate {
+            x: PADDING_X,
+            y: PADDING_Y + render_context.text_ascent(),
+        });
+        render_context.add_command(RenderCommand::DrawText(text.clone()));
+        render_context.add_command(RenderCommand::Restore);
+
+        if state.is_open {
+            let popup_size = Size::new(size.width, props.options.len() as Float * OPTION_HEIGHT);
+            render_context.begin_popup(Rect::new(Point::new(0.0, size.height), popup_size));
+            render_context.add_command(RenderCommand::SetFillColor(styling.background_color));
+            render_context.add_command(RenderCommand::SetStrokeColor(styling.border_color));
+            render_context.add_command(RenderCommand::SetStrokeWidth(stroke_width));
+            render_context.add_command(RenderCommand::DrawRect {
+                rect: Rect::from_size(popup_size).inflate(-stroke_width / 2.0, -stroke_width / 2.0),
+                mode: DrawMode::FillAndStroke,
+            });
+            for (index, option) in props.options.iter().enumerate() {
+                let top = index as Float * OPTION_HEIGHT;
+                if index == state.highlighted {
+                    render_context
+                        .add_command(RenderCommand::SetFillColor(styling.highlight_color));
+                    render_context.add_command(RenderCommand::FillRect {
+                        rect: Rect::new(
+                            Point::new(stroke_width, top),
+                            Size::new(size.width - 2.0 * stroke_width, OPTION_HEIGHT),
+                        ),
+                    });
+                }
+                let text_size = render_context.measure_text(option).unwrap();
+                render_context.add_command(RenderCommand::SetStrokeColor(styling.text_color));
+                render_context.add_command(RenderCommand::Save);
+                render_context.add_command(RenderCommand::Translate {
+                    x: PADDING_X,
+                    y: top
+                        + (OPTION_HEIGHT - text_size.height) / 2.0
+                        + render_context.text_ascent(),
+                });
+                render_context.add_command(RenderCommand::DrawText(option.clone()));
+                render_context.add_command(RenderCommand::Restore);
+            }
+            render_context.end_popup();
+        }
+    }
+
+    fn layout_element(
+        _layout_context: &mut LayoutContext,
+        _state: &mut Self::State,
+        props: &Self::Props,
+    ) -> ViuiResult<LayoutConstraints> {
+        let mut texts = props.options.clone();
+        texts.push(props.placeholder.clone());
+        Ok(LayoutConstraints::Measured(Box::new(
+            move |measure_context, _max_width| {
+                let mut width: Float = 0.0;
+                let mut height: Float = 0.0;
+                for text in &texts {
+                    let text_size = measure_context.measure_text(text)?;
+                    width = width.max(text_size.width);
+                    height = height.max(text_size.height);
+                }
+                Ok(Size::new(
+                    (width + 2.0 * PADDING_X + ARROW_WIDTH).max(MIN_WIDTH),
+                    height + 2.0 * PADDING_Y,
+                ))
+            },
+        )))
+    }
+}
+
+#[derive(Default, Reflect, Debug)]
+pub struct DropdownElementProps {
+    pub options: Vec<String>,
+    pub selected: String,
+    /// Shown while no option is selected
+    pub placeholder: String,
+}
+
+impl NodeProps for DropdownElementProps {}
+
+#[derive(Default, Reflect, Debug)]
+pub struct DropdownElementState {
+    pub is_hovering: bool,
+    pub is_open: bool,
+    /// Index of the option selected by Enter while the popup is open
+    pub highlighted: usize,
+    /// Lowercase text typed so far for type-ahead
+    pub type_ahead: String,
+    #[reflect(ignore)]
+    last_typed: Option<Instant>,
+}
+
+impl DropdownElementState {
+    /// Opens the popup with the selected option highlighted
+    fn open(&mut self, props: &DropdownElementProps) {
+        self.is_open = true;
+        self.highlighted = props
+            .options
+            .iter()
+            .position(|option| *option == props.selected)
+            .unwrap_or(0);
+    }
+}
+
+impl NodeState for DropdownElementState {}
+
+#[derive(Reflect, Debug)]
+pub enum DropdownEvents {
+    Change { new_value: String },
+}
+impl NodeEvents for DropdownEvents {}
@@ -0,0 +1,40 @@
+use crate::infrastructure::layout_context::LayoutContext;
+use crate::nodes::elements::kind::{Element, LayoutConstraints, NoEvents};
+use crate::nodes::types::NodeProps;
+use crate::render::context::RenderContext;
+use crate::render::parameters::RenderParameters;
+use crate::result::ViuiResult;
+use bevy_reflect::Reflect;
+
+pub struct GridElement {}
+
+impl Element for GridElement {
+    const NAME: &'static str = "grid";
+    type State = ();
+    type Props = GridElementProps;
+    type Events = NoEvents;
+    fn render_element(
+        _render_context: &mut RenderContext,
+        _parameters: &RenderParameters,
+        _state: &Self::State,
+        _props: &Self::Props,
+    ) {
+    }
+
+    fn layout_element(
+        _layout_context: &mut LayoutContext,
+        _state: &mut Self::State,
+        props: &Self::Props,
+    ) -> ViuiResult<LayoutConstraints> {
+        Ok(LayoutConstraints::GridLayout {
+            columns: props.columns.max(1) as usize,
+        })
+    }
+}
+
+#[derive(Default, Reflect, Debug)]
+pub struct GridElementProps {
+    pub columns: i32,
+}
+
+impl NodeProps for GridElementProps {}
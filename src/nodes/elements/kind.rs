@@ -1,11 +1,17 @@
-use crate::infrastructure::layout_context::LayoutContext;
+use crate::arenal::Arenal;
+use crate::ast::nodes::NodeAst;
+use crate::ast::value::ExpressionValue;
+use crate::bail;
+use crate::infrastructure::layout_context::{LayoutContext, MeasureContext};
+use crate::nodes::data::{LayoutInfo, NodeData, NodeIdx};
 use crate::nodes::events::InputEvent;
 use crate::nodes::types::{NodeEvents, NodeProps, NodeState};
 use crate::render::context::RenderContext;
 use crate::render::parameters::RenderParameters;
 use crate::result::ViuiResult;
-use crate::types::Float;
+use crate::types::{Float, Rect, SideOffsets, Size, Vector};
 use bevy_reflect::Reflect;
+use std::ops::Range;
 
 pub type EventTrigger<'a, E> = dyn FnMut(E) + 'a;
 
@@ -14,11 +20,20 @@ pub trait Element {
     type State: NodeState + Default;
     type Props: NodeProps + Default;
     type Events: NodeEvents;
+    /// Whether the element can receive keyboard focus, by clicking it or with Tab
+    const FOCUSABLE: bool = false;
+    /// Whether the element edits typed text while focused, so that keys pressed without Ctrl, Alt
+    /// or the logo key go to it instead of triggering shortcuts
+    const EDITS_TEXT: bool = false;
 
+    /// Called when an event reaches the node as its target or while bubbling up from a descendant
+    ///
+    /// `layout` is the layout of the node as of the last layout pass.
     fn handle_event(
         _event: &InputEvent,
         _state: &mut Self::State,
         _props: &Self::Props,
+        _layout: &LayoutInfo,
         _event_trigger: &mut EventTrigger<'_, Self::Events>,
     ) {
     }
@@ -34,12 +49,319 @@ pub trait Element {
         state: &mut Self::State,
         props: &Self::Props,
     ) -> ViuiResult<LayoutConstraints>;
+
+    /// Rewrites the node as written in the UI source before it is created, `None` to keep it
+    ///
+    /// Lets an element accept a more concise syntax than the nodes it is made of.
+    fn expand_template(_node: &NodeAst) -> ViuiResult<Option<NodeAst>> {
+        Ok(None)
+    }
+
+    /// Called with the computed layout of the node and its children after each layout pass
+    fn layout_updated(
+        _state: &mut Self::State,
+        _props: &Self::Props,
+        _context: &mut LayoutUpdateContext,
+    ) {
+    }
+
+    /// Whether the children of the node are shown, only shown children are evaluated, laid out
+    /// and rendered
+    fn shows_children(_state: &Self::State) -> bool {
+        true
+    }
+
+    /// Called after the expressions were evaluated, to update the element from the nodes below it
+    /// and the other way round
+    fn update_children(
+        _state: &mut Self::State,
+        _props: &Self::Props,
+        _context: &mut UpdateContext,
+    ) -> ViuiResult<()> {
+        Ok(())
+    }
+
+    /// Picks the values that `for` items among the children of the element are instantiated for
+    ///
+    /// `field` looks up a field of a value. All values are instantiated by default.
+    fn for_values(
+        _state: &mut Self::State,
+        _props: &Self::Props,
+        values: &dyn ForCollection,
+        _field: &ValueField,
+    ) -> ViuiResult<Vec<ForValue>> {
+        ForValue::indexed(values, 0..values.len())
+    }
+
+    /// Called when a node below the element triggered an event
+    ///
+    /// Events triggered in response are sent in place of the original event, which is then not
+    /// passed on to any further ancestors.
+    fn handle_descendant_event(
+        _event: &dyn NodeEvents,
+        _state: &mut Self::State,
+        _props: &Self::Props,
+        _event_trigger: &mut EventTrigger<'_, Self::Events>,
+    ) {
+    }
+}
+
+/// Looks up a field of a value bound in a `for` loop, `None` if the value has no such field
+pub type ValueField<'a> =
+    dyn Fn(&ExpressionValue, &str) -> ViuiResult<Option<ExpressionValue>> + 'a;
+
+/// Collection a `for` item iterates over, whose values are only converted once picked
+pub trait ForCollection {
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn value(&self, index: usize) -> ViuiResult<ExpressionValue>;
+
+    /// All values of the collection
+    fn values(&self) -> ViuiResult<Vec<ExpressionValue>> {
+        (0..self.len()).map(|index| self.value(index)).collect()
+    }
+}
+
+impl ForCollection for Vec<ExpressionValue> {
+    fn len(&self) -> usize {
+        <[ExpressionValue]>::len(self)
+    }
+
+    fn value(&self, index: usize) -> ViuiResult<ExpressionValue> {
+        Ok(self[index].clone())
+    }
+}
+
+/// Value a `for` item is instantiated for, picked by [`Element::for_values`]
+pub struct ForValue {
+    pub value: ExpressionValue,
+    /// Index in the collection, bound as `<name>#index`
+    pub index: usize,
+    /// Further bindings, e.g. `depth` bound as `<name>#depth`
+    pub bindings: Vec<(&'static str, ExpressionValue)>,
 }
 
+impl ForValue {
+    /// The values at the indices, converting only those
+    pub fn indexed(values: &dyn ForCollection, indices: Range<usize>) -> ViuiResult<Vec<ForValue>> {
+        indices
+            .map(|index| {
+                Ok(ForValue {
+                    value: values.value(index)?,
+                    index,
+                    bindings: vec![],
+                })
+            })
+            .collect()
+    }
+}
+
+/// Computed layout of a node, passed to [`Element::layout_updated`]
+pub struct LayoutUpdateContext<'a> {
+    layout: &'a LayoutInfo,
+    children: &'a [Rect],
+    is_changed: bool,
+    needs_layout_pass: bool,
+}
+
+impl<'a> LayoutUpdateContext<'a> {
+    pub fn new(layout: &'a LayoutInfo, children: &'a [Rect]) -> Self {
+        Self {
+            layout,
+            children,
+            is_changed: false,
+            needs_layout_pass: false,
+        }
+    }
+
+    pub fn layout(&self) -> &LayoutInfo {
+        self.layout
+    }
+
+    /// Window bounds of the children with a layout of their own, in document order
+    pub fn children(&self) -> &[Rect] {
+        self.children
+    }
+
+    /// Marks a change of state that is not reflected, so that the node is laid out again
+    pub fn mark_changed(&mut self) {
+        self.is_changed = true;
+    }
+
+    pub fn is_changed(&self) -> bool {
+        self.is_changed
+    }
+
+    /// Evaluates and lays out the UI again before rendering, e.g. to instantiate other children
+    pub fn request_layout_pass(&mut self) {
+        self.needs_layout_pass = true;
+    }
+
+    pub fn needs_layout_pass(&self) -> bool {
+        self.needs_layout_pass
+    }
+}
+
+/// Shown nodes below an element, passed to [`Element::update_children`]
+pub struct UpdateContext<'a> {
+    node_arena: &'a mut Arenal<NodeData>,
+    node_idx: NodeIdx,
+    /// Subtree of the element in document order, with the parent of each node
+    descendants: &'a [(NodeIdx, Option<NodeIdx>)],
+}
+
+impl<'a> UpdateContext<'a> {
+    pub fn new(
+        node_arena: &'a mut Arenal<NodeData>,
+        node_idx: NodeIdx,
+        descendants: &'a [(NodeIdx, Option<NodeIdx>)],
+    ) -> Self {
+        Self {
+            node_arena,
+            node_idx,
+            descendants,
+        }
+    }
+
+    /// Whether the prop of the element is set by an expression other than a literal
+    pub fn is_prop_bound(&self, field_name: &str) -> bool {
+        self.node_arena[&self.node_idx].is_prop_bound(field_name)
+    }
+
+    /// Calls `update` for the children that are `E` elements, in document order
+    pub fn update_children<E: Element>(
+        &mut self,
+        mut update: impl FnMut(&mut E::State, &E::Props) -> ViuiResult<()>,
+    ) -> ViuiResult<()> {
+        let node_idx = self.node_idx;
+        self.update_nodes::<E>(|parent| parent == Some(node_idx), &mut update)
+    }
+
+    /// Calls `update` for all nodes below the element that are `E` elements, in document order
+    pub fn update_descendants<E: Element>(
+        &mut self,
+        mut update: impl FnMut(&mut E::State, &E::Props) -> ViuiResult<()>,
+    ) -> ViuiResult<()> {
+        self.update_nodes::<E>(|_| true, &mut update)
+    }
+
+    fn update_nodes<E: Element>(
+        &mut self,
+        filter: impl Fn(Option<NodeIdx>) -> bool,
+        update: &mut impl FnMut(&mut E::State, &E::Props) -> ViuiResult<()>,
+    ) -> ViuiResult<()> {
+        for (node_idx, parent) in self.descendants {
+            let node = &mut self.node_arena[node_idx];
+            if filter(*parent)
+                && node.props.as_any().is::<E::Props>()
+                && node.state.as_any().is::<E::State>()
+            {
+                node.track_state(|node| {
+                    let (state, props) = node.cast_state_mut_and_props::<E::State, E::Props>()?;
+                    update(state, props)
+                })?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Computes the size of an element's content, wrapping it to the maximum width if given
+pub type MeasureFn = Box<dyn Fn(&MeasureContext, Option<Float>) -> ViuiResult<Size> + Send>;
+
 pub enum LayoutConstraints {
     Passthrough,
-    FixedLayout { width: Float, height: Float },
+    FixedLayout {
+        width: Float,
+        height: Float,
+    },
     HorizontalLayout {},
+    /// Sized to the content, measured when the available space is known
+    Measured(MeasureFn),
+    VerticalLayout {},
+    /// Children are placed in equally wide columns, wrapping to new rows
+    GridLayout {
+        columns: usize,
+    },
+    /// Takes up any free space along the main axis of the parent
+    FlexibleSpace,
+    /// Not laid out and takes up no space
+    Hidden,
+    /// Children are placed on top of each other, later children above earlier ones
+    OverlayLayout {},
+    /// Children are laid out at their intrinsic size and moved by the scroll offset
+    ScrollLayout {
+        offset: Vector,
+    },
+    /// Scrolls like `ScrollLayout`, with the space of rows that are not instantiated reserved
+    /// before and after the children
+    VirtualListLayout {
+        offset: Vector,
+        space_before: Float,
+        space_after: Float,
+        /// Height of each row, rows are sized to their content if `None`
+        row_height: Option<Float>,
+    },
+    /// Left out of the parent's layout and shown above all other nodes, filling the window
+    ///
+    /// Children are centered in the window, or placed below the enclosing node if `anchored`.
+    LayerLayout {
+        anchored: bool,
+    },
+    /// Children are stacked inside the padding, see [`BoxLayout`]
+    BoxLayout(BoxLayout),
+    /// Fills the parent and aligns its children inside
+    AlignedLayout {
+        horizontal: Alignment,
+        vertical: Alignment,
+    },
+}
+
+/// Box with its children stacked along one axis, for elements that draw around their children
+#[derive(Clone, Debug, Default)]
+pub struct BoxLayout {
+    /// Children are placed in a row instead of a column
+    pub horizontal: bool,
+    /// Fixed size, sized to the content if `None`
+    pub width: Option<Float>,
+    pub height: Option<Float>,
+    /// Space around the children, e.g. for a header drawn by the element
+    pub padding: SideOffsets,
+    /// Takes up the free space along the main axis of the parent, even if the content is larger
+    pub fill: bool,
+    /// Alignment of the children across the axis, stretched if `None`
+    pub align: Option<Alignment>,
+    /// Alignment of the children along the axis
+    pub justify: Alignment,
+    /// Children are clipped to the node
+    pub clip: bool,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Reflect)]
+pub enum Alignment {
+    #[default]
+    Start,
+    Center,
+    End,
+}
+
+impl Alignment {
+    /// Parses an alignment prop, where an unset (empty) prop means centered
+    pub fn parse(value: &str) -> ViuiResult<Self> {
+        Ok(match value {
+            "start" => Alignment::Start,
+            "" | "center" => Alignment::Center,
+            "end" => Alignment::End,
+            _ => bail!(
+                "Invalid alignment '{}', expected 'start', 'center' or 'end'",
+                value
+            ),
+        })
+    }
 }
 
 #[derive(Debug, Reflect)]
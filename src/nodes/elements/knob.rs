@@ -1,5 +1,7 @@
 use crate::infrastructure::layout_context::LayoutContext;
+use crate::nodes::data::LayoutInfo;
 use crate::nodes::elements::kind::{Element, EventTrigger, LayoutConstraints};
+use crate::nodes::elements::range::ValueRange;
 use crate::nodes::events::{InputEvent, InputEventKind};
 use crate::nodes::types::{NodeEvents, NodeProps, NodeState};
 use crate::render::command::RenderCommand;
@@ -12,23 +14,28 @@ use std::f32::consts::PI;
 
 pub struct KnobElement {}
 
+/// Dragging this far right or up turns the knob from minimum to maximum
+const DRAG_DISTANCE: Float = 200.0;
+
 impl Element for KnobElement {
     const NAME: &'static str = "knob";
     type State = KnobElementState;
     type Props = KnobElementProps;
     type Events = KnobEvents;
+    const FOCUSABLE: bool = true;
 
     fn handle_event(
         event: &InputEvent,
         state: &mut Self::State,
         props: &Self::Props,
+        _layout: &LayoutInfo,
         event_trigger: &mut EventTrigger<KnobEvents>,
     ) {
         match event.kind() {
-            InputEventKind::MouseOver => {
+            InputEventKind::MouseEnter => {
                 state.is_hovering = true;
             }
-            InputEventKind::MouseOut => {
+            InputEventKind::MouseLeave => {
                 state.is_hovering = false;
             }
             InputEventKind::MousePress(position) => {
@@ -46,9 +53,19 @@ impl Element for KnobElement {
                 if state.is_dragging {
                     let delta_x = position.x - state.drag_start_x;
                     let delta_y = position.y - state.drag_start_y;
-                    let delta = (delta_x - delta_y) / 10.0;
-                    let new_value = state.drag_start_value + delta;
-                    let new_value = new_value.clamp(props.min_value, props.max_value);
+                    let new_value = props.range().drag(
+                        state.drag_start_value,
+                        delta_x - delta_y,
+                        DRAG_DISTANCE,
+                        event.modifiers().shift,
+                    );
+                    event_trigger(KnobEvents::Change { new_value });
+                }
+            }
+            InputEventKind::KeyDown { key, .. } => {
+                let new_value = props.range().value_for_key(props.value, *key);
+                // Keys moving past the end of the range leave the value as it is
+                if let Some(new_value) = new_value.filter(|new_value| *new_value != props.value) {
                     event_trigger(KnobEvents::Change { new_value });
                 }
             }
@@ -77,18 +94,15 @@ impl Element for KnobElement {
             rect: Rect::new(Point::new(10.0, 10.0), Size::new(40.0, 40.0)),
             radius: 20.0,
         });
-        let center_x = 30.0;
-        let center_y = 30.0;
         let radius = 20.0;
-        let start_x = center_x + radius * angle.cos();
-        let start_y = center_y + radius * angle.sin();
-        let end_x = center_x + (radius - 10.0) * angle.cos();
-        let end_y = center_y + (radius - 10.0) * angle.sin();
-
+        render_context.add_command(RenderCommand::Save);
+        render_context.add_command(RenderCommand::Translate { x: 30.0, y: 30.0 });
+        render_context.add_command(RenderCommand::Rotate { angle });
         render_context.add_command(RenderCommand::Line {
-            start: Point::new(start_x, start_y),
-            end: Point::new(end_x, end_y),
+            start: Point::new(radius, 0.0),
+            end: Point::new(radius - 10.0, 0.0),
         });
+        render_context.add_command(RenderCommand::Restore);
         render_context.add_command(RenderCommand::SetStrokeColor(styling.inactive_color));
         render_context.add_command(RenderCommand::SetStrokeWidth(3.0));
         render_context.add_command(RenderCommand::Arc {
@@ -113,12 +127,16 @@ impl Element for KnobElement {
     fn layout_element(
         _layout_context: &mut LayoutContext,
         _state: &mut Self::State,
-        _props: &Self::Props,
+        props: &Self::Props,
     ) -> ViuiResult<LayoutConstraints> {
-        Ok(LayoutConstraints::FixedLayout {
-            width: 200.0,
-            height: 140.0,
-        })
+        let label = props.label.clone();
+        Ok(LayoutConstraints::Measured(Box::new(
+            move |measure_context, _max_width| {
+                // Label is drawn below the dial, starting 20 pixels from the left
+                let label_width = measure_context.measure_text(&label)?.width + 30.0;
+                Ok(Size::new(label_width.max(80.0), 140.0))
+            },
+        )))
     }
 }
 
@@ -130,6 +148,12 @@ pub struct KnobElementProps {
     pub label: String,
 }
 
+impl KnobElementProps {
+    fn range(&self) -> ValueRange {
+        ValueRange::new(self.min_value, self.max_value, 0.0, false)
+    }
+}
+
 impl NodeProps for KnobElementProps {}
 
 #[derive(Reflect, Debug, Default)]
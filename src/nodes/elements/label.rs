@@ -5,10 +5,14 @@ use crate::render::command::RenderCommand;
 use crate::render::context::RenderContext;
 use crate::render::parameters::RenderParameters;
 use crate::result::ViuiResult;
+use crate::types::{Float, Size};
 use bevy_reflect::Reflect;
 
 pub struct LabelElement {}
 
+const PADDING_X: Float = 10.0;
+const PADDING_Y: Float = 3.0;
+
 impl Element for LabelElement {
     const NAME: &'static str = "label";
     type State = ();
@@ -23,19 +27,46 @@ impl Element for LabelElement {
         render_context.add_command(RenderCommand::SetStrokeColor(
             parameters.styling().text_color,
         ));
-        render_context.add_command(RenderCommand::Translate { x: 10.0, y: 25.0 });
-        render_context.add_command(RenderCommand::DrawText(props.label.clone()));
+        let max_width = render_context.size().width - 2.0 * PADDING_X;
+        let lines = render_context
+            .wrap_text(&props.label, Some(max_width))
+            .unwrap();
+        let line_height = render_context.measure_text("").unwrap().height;
+        render_context.add_command(RenderCommand::Translate {
+            x: PADDING_X,
+            y: PADDING_Y + render_context.text_ascent(),
+        });
+        for (index, line) in lines.into_iter().enumerate() {
+            if index > 0 {
+                render_context.add_command(RenderCommand::Translate {
+                    x: 0.0,
+                    y: line_height,
+                });
+            }
+            render_context.add_command(RenderCommand::DrawText(line));
+        }
     }
 
     fn layout_element(
         _layout_context: &mut LayoutContext,
         _state: &mut Self::State,
-        _props: &Self::Props,
+        props: &Self::Props,
     ) -> ViuiResult<LayoutConstraints> {
-        Ok(LayoutConstraints::FixedLayout {
-            width: 400.0,
-            height: 40.0,
-        })
+        let label = props.label.clone();
+        Ok(LayoutConstraints::Measured(Box::new(
+            move |measure_context, max_width| {
+                let max_width = max_width.map(|max_width| max_width - 2.0 * PADDING_X);
+                let lines = measure_context.wrap_text(&label, max_width)?;
+                let mut width: Float = 0.0;
+                let mut height = 0.0;
+                for line in &lines {
+                    let measurement = measure_context.measure_text(line)?;
+                    width = width.max(measurement.width);
+                    height += measurement.height;
+                }
+                Ok(Size::new(width + 2.0 * PADDING_X, height + 2.0 * PADDING_Y))
+            },
+        )))
     }
 }
 
@@ -50,7 +81,7 @@ impl NodeProps for LabelElementProps {}
 mod tests {
     use crate::infrastructure::font_pool::FontPool;
     use crate::infrastructure::image_pool::ImagePool;
-    use crate::infrastructure::layout_context::LayoutContext;
+    use crate::infrastructure::layout_context::{LayoutContext, MeasureContext};
     use crate::infrastructure::styling::Styling;
     use crate::nodes::data::NodeData;
     use crate::nodes::elements::kind::LayoutConstraints;
@@ -86,21 +117,27 @@ mod tests {
             tag: "".to_string(),
             kind_index: 0,
             layout: Default::default(),
+            layout_props: Default::default(),
             state,
             props,
+            props_revision: 0,
+            state_revision: 0,
+            common_props: Default::default(),
             children: vec![],
             prop_expressions: vec![],
             event_mappings: Default::default(),
         };
-        let constraints =
-            (node_descriptor.layout_fn)(&mut LayoutContext::new(&mut image_pool), &mut node_data)
-                .unwrap();
+        let mut layout_context = LayoutContext::new(&mut image_pool, &font_pool);
+        let constraints = (node_descriptor.layout_fn)(&mut layout_context, &mut node_data).unwrap();
 
-        let mut render_context = RenderContext::new(&mut image_pool, &mut font_pool, 0.0).unwrap();
-        let LayoutConstraints::FixedLayout { width, height } = constraints else {
-            panic!("Expected fixed layout");
+        let LayoutConstraints::Measured(measure_fn) = constraints else {
+            panic!("Expected measured layout");
         };
-        let size = Size::new(width, height);
+        let size = measure_fn(&MeasureContext::new(&font_pool), None).unwrap();
+        assert_eq!(size, Size::new(78.475006, 37.25));
+
+        let mut render_context = RenderContext::new(&mut image_pool, &mut font_pool, 0.0).unwrap();
+        render_context.set_size(size);
         render_context.add_command(RenderCommand::SetFillColor(styling.background_color));
         render_context.add_command(RenderCommand::FillRect {
             rect: Rect::new(Point::new(0.0, 0.0), size),
@@ -118,7 +155,7 @@ mod tests {
         let parent_path = file_path.parent().unwrap();
         fs::create_dir_all(parent_path).unwrap();
         let mut buffer = Vec::new();
-        render_svg(size, &render_queue, &mut buffer, &file_path).unwrap();
+        render_svg(size, &render_queue, &mut buffer, file_path).unwrap();
         let svg = String::from_utf8(buffer).unwrap();
         let content = if file_path.exists() {
             fs::read_to_string(file_path).unwrap()
@@ -0,0 +1,281 @@
+use crate::infrastructure::layout_context::LayoutContext;
+use crate::nodes::data::LayoutInfo;
+use crate::nodes::elements::kind::{
+    Element, EventTrigger, ForCollection, ForValue, LayoutConstraints, LayoutUpdateContext,
+    NoEvents, ValueField,
+};
+use crate::nodes::elements::scroll::{ScrollElement, ScrollElementState};
+use crate::nodes::events::InputEvent;
+use crate::nodes::types::{NodeProps, NodeState};
+use crate::render::context::RenderContext;
+use crate::render::parameters::RenderParameters;
+use crate::result::ViuiResult;
+use crate::types::{Float, Vector};
+use bevy_reflect::Reflect;
+use std::ops::Range;
+
+/// Rows instantiated beyond each edge of the viewport
+pub const OVERSCAN: usize = 3;
+/// Assumed height of rows that have not been measured yet
+const ESTIMATED_ROW_HEIGHT: Float = 40.0;
+
+/// Scrollable list that only instantiates the rows of its `for` child intersecting the viewport
+///
+/// Row nodes are reused for other rows while scrolling. Rows have a fixed height if `row_height`
+/// is set, otherwise their heights are measured once they have been shown.
+pub struct ListElement {}
+
+impl Element for ListElement {
+    const NAME: &'static str = "list";
+    type State = ListElementState;
+    type Props = ListElementProps;
+    type Events = NoEvents;
+
+    fn for_values(
+        state: &mut Self::State,
+        props: &Self::Props,
+        values: &dyn ForCollection,
+        _field: &ValueField,
+    ) -> ViuiResult<Vec<ForValue>> {
+        // Only the rows that are instantiated are converted
+        let rows = state.update_rows(values.len(), props);
+        ForValue::indexed(values, rows)
+    }
+
+    fn handle_event(
+        event: &InputEvent,
+        state: &mut Self::State,
+        _props: &Self::Props,
+        layout: &LayoutInfo,
+        event_trigger: &mut EventTrigger<'_, Self::Events>,
+    ) {
+        ScrollElement::handle_event(event, &mut state.scroll, &(), layout, event_trigger);
+    }
+
+    fn render_element(
+        render_context: &mut RenderContext,
+        parameters: &RenderParameters,
+        state: &Self::State,
+        _props: &Self::Props,
+    ) {
+        ScrollElement::render_element(render_context, parameters, &state.scroll, &());
+    }
+
+    fn layout_element(
+        _layout_context: &mut LayoutContext,
+        state: &mut Self::State,
+        props: &Self::Props,
+    ) -> ViuiResult<LayoutConstraints> {
+        let rows = state.first_row..state.first_row + state.shown_row_count;
+        Ok(LayoutConstraints::VirtualListLayout {
+            offset: Vector::new(state.scroll.offset_x, state.scroll.offset_y),
+            space_before: state.rows_height(0..rows.start, props),
+            space_after: state.rows_height(rows.end..state.row_count, props),
+            row_height: props.fixed_row_height(),
+        })
+    }
+
+    fn layout_updated(
+        state: &mut Self::State,
+        props: &Self::Props,
+        context: &mut LayoutUpdateContext,
+    ) {
+        if props.fixed_row_height().is_none() {
+            let heights = context.children().iter().map(|row| row.size.height);
+            if state.set_row_heights(heights) {
+                context.mark_changed();
+            }
+        }
+        ScrollElement::layout_updated(&mut state.scroll, &(), context);
+        // Rows are only instantiated for the visible part of the list
+        if state.needs_update(props) {
+            context.request_layout_pass();
+        }
+    }
+}
+
+#[derive(Default, Reflect, Debug)]
+pub struct ListElementProps {
+    /// Height of every row, rows are measured if unset
+    pub row_height: f32,
+}
+
+impl ListElementProps {
+    fn fixed_row_height(&self) -> Option<Float> {
+        (self.row_height > 0.0).then_some(self.row_height)
+    }
+}
+
+impl NodeProps for ListElementProps {}
+
+#[derive(Default, Reflect, Debug)]
+pub struct ListElementState {
+    pub scroll: ScrollElementState,
+    pub row_count: usize,
+    /// Index of the first instantiated row
+    pub first_row: usize,
+    pub shown_row_count: usize,
+    /// Measured heights of rows that have been shown
+    #[reflect(ignore)]
+    row_heights: Vec<Option<Float>>,
+}
+
+impl ListElementState {
+    /// Sets the number of rows and returns the rows to instantiate for the current scroll offset
+    fn update_rows(&mut self, row_count: usize, props: &ListElementProps) -> Range<usize> {
+        self.row_count = row_count;
+        self.row_heights.resize(row_count, None);
+        let rows = self.visible_rows(props);
+        self.first_row = rows.start;
+        self.shown_row_count = rows.len();
+        rows
+    }
+
+    /// Whether the instantiated rows no longer match the visible rows after a layout pass
+    pub fn needs_update(&self, props: &ListElementProps) -> bool {
+        self.visible_rows(props) != (self.first_row..self.first_row + self.shown_row_count)
+    }
+
+    /// Records the heights of the instantiated rows, returning whether any of them changed
+    pub fn set_row_heights(&mut self, heights: impl IntoIterator<Item = Float>) -> bool {
+        let rows = &mut self.row_heights[self.first_row..];
+        let mut changed = false;
+        for (row_height, height) in rows.iter_mut().zip(heights) {
+            changed |= *row_height != Some(height);
+            *row_height = Some(height);
+        }
+        changed
+    }
+
+    /// Scrolls vertically by the least amount that shows the whole row
+    pub fn scroll_to_row(&mut self, row: usize, props: &ListElementProps) {
+        let row = row.min(self.row_count.saturating_sub(1));
+        let top = self.rows_height(0..row, props);
+        let bottom = top + self.rows_height(row..(row + 1).min(self.row_count), props);
+        if top < self.scroll.offset_y {
+            self.scroll.offset_y = top;
+        } else if bottom > self.scroll.offset_y + self.scroll.height {
+            self.scroll.offset_y = bottom - self.scroll.height;
+        }
+    }
+
+    /// Number of rows fitting in the viewport, at least one
+    pub fn page_rows(&self, props: &ListElementProps) -> usize {
+        let row_height = props
+            .fixed_row_height()
+            .unwrap_or_else(|| self.estimated_row_height());
+        ((self.scroll.height / row_height).floor() as usize).max(1)
+    }
+
+    /// Rows intersecting the viewport, extended by the overscan
+    fn visible_rows(&self, props: &ListElementProps) -> Range<usize> {
+        let top = self.scroll.offset_y;
+        let bottom = top + self.scroll.height;
+        let (first, end) = match props.fixed_row_height() {
+            Some(row_height) => (
+                (top / row_height).floor() as usize,
+                (bottom / row_height).ceil() as usize,
+            ),
+            None => {
+                let estimate = self.estimated_row_height();
+                let mut first = self.row_count;
+                let mut end = self.row_count;
+                let mut y = 0.0;
+                for (index, height) in self.row_heights.iter().enumerate() {
+                    y += height.unwrap_or(estimate);
+                    if y > top && first == self.row_count {
+                        first = index;
+                    }
+                    if y >= bottom {
+                        end = index + 1;
+                        break;
+                    }
+                }
+                (first, end)
+            }
+        };
+        let first = first.min(self.row_count).saturating_sub(OVERSCAN);
+        let end = (end + OVERSCAN).min(self.row_count);
+        first..end.max(first)
+    }
+
+    fn rows_height(&self, rows: Range<usize>, props: &ListElementProps) -> Float {
+        match props.fixed_row_height() {
+            Some(row_height) => rows.len() as Float * row_height,
+            None => {
+                let estimate = self.estimated_row_height();
+                self.row_heights[rows]
+                    .iter()
+                    .map(|height| height.unwrap_or(estimate))
+                    .sum()
+            }
+        }
+    }
+
+    /// Average of the measured row heights
+    fn estimated_row_height(&self) -> Float {
+        let (count, total) = self
+            .row_heights
+            .iter()
+            .flatten()
+            .fold((0, 0.0), |(count, total), height| {
+                (count + 1, total + height)
+            });
+        if count == 0 {
+            ESTIMATED_ROW_HEIGHT
+        } else {
+            total / count as Float
+        }
+    }
+}
+
+impl NodeState for ListElementState {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(row_count: usize, offset: Float, height: Float) -> ListElementState {
+        let mut state = ListElementState::default();
+        state.scroll.offset_y = offset;
+        state.scroll.height = height;
+        state.row_count = row_count;
+        state.row_heights.resize(row_count, None);
+        state
+    }
+
+    #[test]
+    fn test_visible_rows_with_fixed_height() {
+        let props = ListElementProps { row_height: 20.0 };
+        assert_eq!(state(1000, 0.0, 100.0).visible_rows(&props), 0..8);
+        assert_eq!(state(1000, 210.0, 100.0).visible_rows(&props), 7..19);
+        assert_eq!(state(12, 210.0, 100.0).visible_rows(&props), 7..12);
+        assert_eq!(state(0, 0.0, 100.0).visible_rows(&props), 0..0);
+    }
+
+    #[test]
+    fn test_scroll_to_row() {
+        let props = ListElementProps { row_height: 20.0 };
+        let mut state = state(1000, 200.0, 100.0);
+        state.scroll_to_row(12, &props);
+        assert_eq!(state.scroll.offset_y, 200.0);
+        state.scroll_to_row(20, &props);
+        assert_eq!(state.scroll.offset_y, 320.0);
+        state.scroll_to_row(3, &props);
+        assert_eq!(state.scroll.offset_y, 60.0);
+        assert_eq!(state.page_rows(&props), 5);
+    }
+
+    #[test]
+    fn test_visible_rows_with_measured_height() {
+        let props = ListElementProps::default();
+        let mut state = state(1000, 100.0, 100.0);
+        // Unmeasured rows are estimated
+        assert_eq!(state.visible_rows(&props), 0..8);
+        state.first_row = 0;
+        state.set_row_heights([30.0, 10.0, 20.0]);
+        assert_eq!(state.estimated_row_height(), 20.0);
+        assert_eq!(state.visible_rows(&props), 2..13);
+        assert_eq!(state.rows_height(0..4, &props), 80.0);
+    }
+}
@@ -1,8 +1,30 @@
+pub mod aligned_box;
 pub mod button;
+pub mod checkbox;
+pub mod collapsible;
+pub mod component;
+pub mod dialog;
+pub mod dropdown;
+pub mod grid;
 pub mod hstack;
 pub mod image;
 pub mod kind;
 pub mod knob;
 pub mod label;
+pub mod list;
+pub mod number_input;
+pub mod overlay;
+pub mod progress;
+pub mod radio;
+pub mod range;
+pub mod scroll;
+pub mod shortcut;
+pub mod slider;
+pub mod spacer;
 pub mod spinner;
+pub mod switch;
+pub mod table;
+pub mod tabs;
 pub mod textinput;
+pub mod tree;
+pub mod vstack;
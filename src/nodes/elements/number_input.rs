@@ -0,0 +1,302 @@
+use crate::infrastructure::layout_context::LayoutContext;
+use crate::infrastructure::text_edit_state::TextEditState;
+use crate::nodes::data::LayoutInfo;
+use crate::nodes::elements::kind::{Element, EventTrigger, LayoutConstraints};
+use crate::nodes::elements::range::ValueRange;
+use crate::nodes::events::{InputEvent, InputEventKind, KeyboardKey};
+use crate::nodes::types::{NodeEvents, NodeProps, NodeState};
+use crate::render::command::{DrawMode, RenderCommand};
+use crate::render::context::RenderContext;
+use crate::render::parameters::RenderParameters;
+use crate::result::ViuiResult;
+use crate::types::{Float, Point, Rect, Size};
+use bevy_reflect::Reflect;
+
+const PADDING_X: Float = 10.0;
+const PADDING_Y: Float = 4.0;
+const MIN_WIDTH: Float = 100.0;
+/// Width of the up/down buttons at the right edge
+const STEPPER_WIDTH: Float = 20.0;
+
+/// Text field for numbers, stepped with the up/down buttons, arrow keys and Page Up/Down
+///
+/// Typed text is applied on Enter or when the field loses focus. Text that is not a number in
+/// the range is marked as invalid and discarded on blur or Escape. The range is 0 to 100 unless
+/// `min` and `max` are set.
+pub struct NumberInputElement {}
+
+impl Element for NumberInputElement {
+    const NAME: &'static str = "number_input";
+    type State = NumberInputElementState;
+    type Props = NumberInputElementProps;
+    type Events = NumberInputEvents;
+    const FOCUSABLE: bool = true;
+    const EDITS_TEXT: bool = true;
+
+    fn handle_event(
+        event: &InputEvent,
+        state: &mut Self::State,
+        props: &Self::Props,
+        layout: &LayoutInfo,
+        event_trigger: &mut EventTrigger<'_, Self::Events>,
+    ) {
+        let range = props.range();
+        let mut change = |new_value: Float| {
+            if new_value != props.value {
+                event_trigger(NumberInputEvents::Change { new_value });
+            }
+        };
+        match event.kind() {
+            InputEventKind::MouseEnter => {
+                state.is_hovering = true;
+            }
+            InputEventKind::MouseLeave => {
+                state.is_hovering = false;
+            }
+            InputEventKind::MousePress(position) => {
+                // Upper half of the stepper increments, lower half decrements
+                let stepper = Rect::new(
+                    Point::new(layout.bounds.max_x() - STEPPER_WIDTH, layout.bounds.min_y()),
+                    Size::new(STEPPER_WIDTH, layout.bounds.height()),
+                );
+                if stepper.contains(*position) {
+                    let steps = if position.y < stepper.center().y {
+                        1.0
+                    } else {
+                        -1.0
+                    };
+                    change(range.step_by(state.current_value(props), steps));
+                    state.reset();
+                }
+            }
+            InputEventKind::KeyDown {
+                key:
+                    key @ (KeyboardKey::ArrowUp
+                    | KeyboardKey::ArrowDown
+                    | KeyboardKey::PageUp
+                    | KeyboardKey::PageDown),
+                ..
+            } => {
+                if let Some(new_value) = range.value_for_key(state.current_value(props), *key) {
+                    change(new_value);
+                }
+                state.reset();
+            }
+            InputEventKind::KeyDown {
+                key: KeyboardKey::Enter,
+                ..
+            } => {
+                if let Some(new_value) = state.parsed_value(props) {
+                    change(new_value);
+                    state.reset();
+                }
+            }
+            InputEventKind::KeyDown {
+                key: KeyboardKey::Escape,
+                ..
+            } => {
+                state.reset();
+            }
+            InputEventKind::Blur => {
+                if let Some(new_value) = state.parsed_value(props) {
+                    change(new_value);
+                }
+                state.reset();
+            }
+            InputEventKind::Character(..) | InputEventKind::KeyDown { .. } => {
+                let text = state.text(props);
+                let edit_position = state.edit_position.unwrap_or(text.len()).min(text.len());
+                let mut text_edit_state = TextEditState::new(&text, edit_position);
+                text_edit_state.handle_event(event);
+                state.edit_position = Some(text_edit_state.cursor_position);
+                if let Some(new_text) = text_edit_state.new_text {
+                    state.is_invalid = new_text
+                        .trim()
+                        .parse::<Float>()
+                        .map_or(true, |value| !range.contains(value));
+                    state.edited_text = Some(new_text);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn render_element(
+        render_context: &mut RenderContext,
+        parameters: &RenderParameters,
+        state: &Self::State,
+        props: &Self::Props,
+    ) {
+        let styling = parameters.styling();
+        let stroke_width = 2.0f32;
+        let size = render_context.size();
+        let border_color = if state.is_invalid {
+            styling.error_color
+        } else {
+            styling.border_color
+        };
+        render_context.add_command(RenderCommand::SetStrokeColor(border_color));
+        render_context.add_command(RenderCommand::SetFillColor(styling.background_color));
+        render_context.add_command(RenderCommand::SetStrokeWidth(2.0));
+        render_context.add_command(RenderCommand::DrawRoundRect {
+            rect: Rect::new(
+                Point::new(stroke_width, stroke_width),
+                Size::new(
+                    size.width - stroke_width * 2.0,
+                    size.height - stroke_width * 2.0,
+                ),
+            ),
+            radius: 2.0,
+            mode: DrawMode::FillAndStroke,
+        });
+
+        // Stepper with an up and a down chevron
+        let stepper_x = size.width - STEPPER_WIDTH;
+        let center_x = stepper_x + STEPPER_WIDTH / 2.0;
+        let center_y = size.height / 2.0;
+        if state.is_hovering {
+            render_context.add_command(RenderCommand::SetFillColor(styling.button_hover_color));
+        } else {
+            render_context.add_command(RenderCommand::SetFillColor(styling.button_color));
+        }
+        render_context.add_command(RenderCommand::FillRect {
+            rect: Rect::new(
+                Point::new(stepper_x, stroke_width),
+                Size::new(
+                    STEPPER_WIDTH - stroke_width,
+                    size.height - stroke_width * 2.0,
+                ),
+            ),
+        });
+        render_context.add_command(RenderCommand::SetStrokeColor(styling.text_color));
+        for direction in [-1.0, 1.0] {
+            let tip = Point::new(center_x, center_y + direction * 7.0);
+            let base_y = center_y + direction * 3.0;
+            render_context.add_command(RenderCommand::Line {
+                start: Point::new(center_x - 4.0, base_y),
+                end: tip,
+            });
+            render_context.add_command(RenderCommand::Line {
+                start: tip,
+                end: Point::new(center_x + 4.0, base_y),
+            });
+        }
+
+        let text = state.text(props);
+        if render_context.is_focused() {
+            // Cursor blinks
+            render_context.set_animated();
+            if render_context.time() % 1.0 < 0.5 {
+                let edit_position = state.edit_position.unwrap_or(text.len()).min(text.len());
+                let text_size = render_context
+                    .measure_text(&text[0..edit_position])
+                    .unwrap();
+                render_context.add_command(RenderCommand::SetFillColor(styling.text_color));
+                render_context.add_command(RenderCommand::FillRect {
+                    rect: Rect::new(
+                        Point::new(PADDING_X + 1.0 + text_size.width, PADDING_Y),
+                        Size::new(2.0, text_size.height),
+                    ),
+                });
+            }
+        }
+        render_context.add_command(RenderCommand::Translate {
+            x: PADDING_X,
+            y: PADDING_Y + render_context.text_ascent(),
+        });
+        render_context.add_command(RenderCommand::DrawText(text));
+    }
+
+    fn layout_element(
+        _layout_context: &mut LayoutContext,
+        _state: &mut Self::State,
+        props: &Self::Props,
+    ) -> ViuiResult<LayoutConstraints> {
+        let text = props.range().format(props.value);
+        Ok(LayoutConstraints::Measured(Box::new(
+            move |measure_context, _max_width| {
+                let text_size = measure_context.measure_text(&text)?;
+                let width = text_size.width + 2.0 * PADDING_X + 2.0 + STEPPER_WIDTH;
+                Ok(Size::new(
+                    width.max(MIN_WIDTH),
+                    text_size.height + 2.0 * PADDING_Y,
+                ))
+            },
+        )))
+    }
+}
+
+#[derive(Reflect, Debug)]
+pub struct NumberInputElementProps {
+    pub value: f32,
+    pub min: f32,
+    pub max: f32,
+    /// Amount added by each step, values are rounded to multiples of it if set
+    pub step: f32,
+    pub logarithmic: bool,
+}
+
+impl Default for NumberInputElementProps {
+    fn default() -> Self {
+        Self {
+            value: 0.0,
+            min: 0.0,
+            max: 100.0,
+            step: 0.0,
+            logarithmic: false,
+        }
+    }
+}
+
+impl NumberInputElementProps {
+    fn range(&self) -> ValueRange {
+        ValueRange::new(self.min, self.max, self.step, self.logarithmic)
+    }
+}
+
+impl NodeProps for NumberInputElementProps {}
+
+#[derive(Default, Reflect, Debug)]
+pub struct NumberInputElementState {
+    pub is_hovering: bool,
+    /// Text typed since the value was last applied
+    pub edited_text: Option<String>,
+    pub edit_position: Option<usize>,
+    pub is_invalid: bool,
+}
+
+impl NumberInputElementState {
+    fn text(&self, props: &NumberInputElementProps) -> String {
+        match &self.edited_text {
+            Some(text) => text.clone(),
+            None => props.range().format(props.value),
+        }
+    }
+
+    /// Snapped value of the edited text, or `None` if there is no valid edit
+    fn parsed_value(&self, props: &NumberInputElementProps) -> Option<Float> {
+        let range = props.range();
+        let value = self.edited_text.as_ref()?.trim().parse::<Float>().ok()?;
+        range.contains(value).then(|| range.snap(value))
+    }
+
+    /// Value that steps start from, including valid edits
+    fn current_value(&self, props: &NumberInputElementProps) -> Float {
+        self.parsed_value(props).unwrap_or(props.value)
+    }
+
+    /// Discards the edited text
+    fn reset(&mut self) {
+        self.edited_text = None;
+        self.edit_position = None;
+        self.is_invalid = false;
+    }
+}
+
+impl NodeState for NumberInputElementState {}
+
+#[derive(Reflect, Debug)]
+pub enum NumberInputEvents {
+    Change { new_value: Float },
+}
+impl NodeEvents for NumberInputEvents {}
@@ -0,0 +1,99 @@
+use crate::infrastructure::layout_context::LayoutContext;
+use crate::nodes::elements::kind::{Element, LayoutConstraints, NoEvents};
+use crate::nodes::elements::range::ValueRange;
+use crate::nodes::types::NodeProps;
+use crate::render::command::RenderCommand;
+use crate::render::context::RenderContext;
+use crate::render::parameters::RenderParameters;
+use crate::result::ViuiResult;
+use crate::types::{Float, Point, Rect, Size};
+use bevy_reflect::Reflect;
+
+/// Length of the bar, unless set by the layout props
+const LENGTH: Float = 160.0;
+const THICKNESS: Float = 6.0;
+/// Fraction of the bar covered by the stripe of an indeterminate bar
+const STRIPE_FRACTION: Float = 0.3;
+/// Seconds the stripe takes to cross the bar
+const STRIPE_PERIOD: Float = 1.5;
+
+/// Bar filled according to the value, or crossed by a moving stripe while the progress is unknown
+///
+/// Indeterminate bars are animated while they are visible.
+pub struct ProgressElement {}
+
+impl Element for ProgressElement {
+    const NAME: &'static str = "progress";
+    type State = ();
+    type Props = ProgressElementProps;
+    type Events = NoEvents;
+
+    fn render_element(
+        render_context: &mut RenderContext,
+        parameters: &RenderParameters,
+        _state: &Self::State,
+        props: &Self::Props,
+    ) {
+        let styling = parameters.styling();
+        let size = render_context.size();
+        let radius = size.height / 2.0;
+        render_context.add_command(RenderCommand::SetFillColor(styling.button_color));
+        render_context.add_command(RenderCommand::FillRoundRect {
+            rect: Rect::from_size(size),
+            radius,
+        });
+        let (start, length) = if props.indeterminate {
+            if render_context.is_visible() {
+                render_context.set_animated();
+            }
+            // Enters on the left and leaves on the right
+            let cycle = (render_context.time() / STRIPE_PERIOD).fract();
+            let length = size.width * STRIPE_FRACTION;
+            (cycle * (size.width + length) - length, length)
+        } else {
+            let range = ValueRange::new(props.min, props.max, 0.0, false);
+            (0.0, range.normalize(props.value) * size.width)
+        };
+        if length <= 0.0 {
+            return;
+        }
+        render_context.add_command(RenderCommand::SetFillColor(styling.highlight_color));
+        render_context.add_command(RenderCommand::FillRoundRect {
+            rect: Rect::new(Point::new(start, 0.0), Size::new(length, size.height)),
+            radius,
+        });
+    }
+
+    fn layout_element(
+        _layout_context: &mut LayoutContext,
+        _state: &mut Self::State,
+        _props: &Self::Props,
+    ) -> ViuiResult<LayoutConstraints> {
+        Ok(LayoutConstraints::FixedLayout {
+            width: LENGTH,
+            height: THICKNESS,
+        })
+    }
+}
+
+#[derive(Reflect, Debug)]
+pub struct ProgressElementProps {
+    pub value: f32,
+    pub min: f32,
+    pub max: f32,
+    /// Shows activity without a value, for work of unknown length
+    pub indeterminate: bool,
+}
+
+impl Default for ProgressElementProps {
+    fn default() -> Self {
+        Self {
+            value: 0.0,
+            min: 0.0,
+            max: 1.0,
+            indeterminate: false,
+        }
+    }
+}
+
+impl NodeProps for ProgressElementProps {}
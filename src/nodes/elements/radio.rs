@@ -0,0 +1,191 @@
+use crate::infrastructure::layout_context::LayoutContext;
+use crate::nodes::data::LayoutInfo;
+use crate::nodes::elements::checkbox::{draw_label, measure_labeled, INDICATOR_SIZE};
+use crate::nodes::elements::kind::{Element, EventTrigger, LayoutConstraints, UpdateContext};
+use crate::nodes::events::{InputEvent, InputEventKind, KeyboardKey};
+use crate::nodes::types::{NodeEvents, NodeProps, NodeState};
+use crate::render::command::{DrawMode, RenderCommand};
+use crate::render::context::RenderContext;
+use crate::render::parameters::RenderParameters;
+use crate::result::ViuiResult;
+use crate::types::Point;
+use bevy_reflect::Reflect;
+
+/// Stacks its `radio` descendants vertically, the one whose value is `selected` is shown as selected
+///
+/// Selecting a radio triggers the `change` event of the group.
+pub struct RadioGroupElement {}
+
+impl Element for RadioGroupElement {
+    const NAME: &'static str = "radio_group";
+    type State = ();
+    type Props = RadioGroupElementProps;
+    type Events = RadioGroupEvents;
+
+    fn update_children(
+        _state: &mut Self::State,
+        props: &Self::Props,
+        context: &mut UpdateContext,
+    ) -> ViuiResult<()> {
+        // Radios of nested groups are updated again by their own group
+        context.update_descendants::<RadioElement>(|state, radio| {
+            state.is_selected = radio.value == props.selected;
+            Ok(())
+        })
+    }
+
+    fn handle_descendant_event(
+        event: &dyn NodeEvents,
+        _state: &mut Self::State,
+        _props: &Self::Props,
+        event_trigger: &mut EventTrigger<RadioGroupEvents>,
+    ) {
+        if let Some(RadioEvents::Change { new_value }) = event.as_any().downcast_ref() {
+            event_trigger(RadioGroupEvents::Change {
+                new_value: new_value.clone(),
+            });
+        }
+    }
+
+    fn render_element(
+        _render_context: &mut RenderContext,
+        _parameters: &RenderParameters,
+        _state: &Self::State,
+        _props: &Self::Props,
+    ) {
+    }
+
+    fn layout_element(
+        _layout_context: &mut LayoutContext,
+        _state: &mut Self::State,
+        _props: &Self::Props,
+    ) -> ViuiResult<LayoutConstraints> {
+        Ok(LayoutConstraints::VerticalLayout {})
+    }
+}
+
+#[derive(Default, Reflect, Debug)]
+pub struct RadioGroupElementProps {
+    pub selected: String,
+}
+
+impl NodeProps for RadioGroupElementProps {}
+
+#[derive(Reflect, Debug)]
+pub enum RadioGroupEvents {
+    Change { new_value: String },
+}
+impl NodeEvents for RadioGroupEvents {}
+
+/// Option of a `radio_group`, selected by clicking it or pressing Space
+pub struct RadioElement {}
+
+impl Element for RadioElement {
+    const NAME: &'static str = "radio";
+    type State = RadioElementState;
+    type Props = RadioElementProps;
+    type Events = RadioEvents;
+    const FOCUSABLE: bool = true;
+
+    fn handle_event(
+        event: &InputEvent,
+        state: &mut Self::State,
+        props: &Self::Props,
+        _layout: &LayoutInfo,
+        event_trigger: &mut EventTrigger<RadioEvents>,
+    ) {
+        match event.kind() {
+            InputEventKind::MouseEnter => {
+                state.is_hovering = true;
+            }
+            InputEventKind::MouseLeave => {
+                state.is_hovering = false;
+            }
+            InputEventKind::MousePress(..) => {
+                state.is_pressed = true;
+                if !state.is_selected {
+                    event_trigger(RadioEvents::Change {
+                        new_value: props.value.clone(),
+                    });
+                }
+            }
+            InputEventKind::MouseRelease(..) => {
+                state.is_pressed = false;
+            }
+            InputEventKind::KeyDown {
+                key: KeyboardKey::Space,
+                repeat: false,
+            } if !state.is_selected => {
+                event_trigger(RadioEvents::Change {
+                    new_value: props.value.clone(),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    fn render_element(
+        render_context: &mut RenderContext,
+        parameters: &RenderParameters,
+        state: &Self::State,
+        props: &Self::Props,
+    ) {
+        let styling = parameters.styling();
+        let radius = INDICATOR_SIZE / 2.0;
+        let center = Point::new(radius, render_context.size().height / 2.0);
+        if state.is_pressed {
+            render_context.add_command(RenderCommand::SetFillColor(styling.button_pressed_color));
+        } else if state.is_hovering {
+            render_context.add_command(RenderCommand::SetFillColor(styling.button_hover_color));
+        } else {
+            render_context.add_command(RenderCommand::SetFillColor(styling.button_color));
+        }
+        render_context.add_command(RenderCommand::SetStrokeColor(styling.text_color));
+        render_context.add_command(RenderCommand::SetStrokeWidth(2.0));
+        render_context.add_command(RenderCommand::DrawCircle {
+            center,
+            radius: radius - 1.0,
+            mode: DrawMode::FillAndStroke,
+        });
+        if state.is_selected {
+            render_context.add_command(RenderCommand::SetFillColor(styling.highlight_color));
+            render_context.add_command(RenderCommand::DrawCircle {
+                center,
+                radius: radius - 5.0,
+                mode: DrawMode::Fill,
+            });
+        }
+        draw_label(render_context, styling, INDICATOR_SIZE, &props.label);
+    }
+
+    fn layout_element(
+        _layout_context: &mut LayoutContext,
+        _state: &mut Self::State,
+        props: &Self::Props,
+    ) -> ViuiResult<LayoutConstraints> {
+        Ok(measure_labeled(INDICATOR_SIZE, &props.label))
+    }
+}
+
+#[derive(Default, Reflect, Debug)]
+pub struct RadioElementProps {
+    pub value: String,
+    pub label: String,
+}
+
+impl NodeProps for RadioElementProps {}
+
+#[derive(Reflect, Debug, Default)]
+pub struct RadioElementState {
+    pub is_hovering: bool,
+    pub is_pressed: bool,
+    /// Whether the value is the `selected` value of the enclosing group, set by the group
+    pub is_selected: bool,
+}
+impl NodeState for RadioElementState {}
+
+#[derive(Reflect, Debug)]
+pub enum RadioEvents {
+    Change { new_value: String },
+}
+impl NodeEvents for RadioEvents {}
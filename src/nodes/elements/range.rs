@@ -0,0 +1,167 @@
+use crate::nodes::events::KeyboardKey;
+use crate::types::Float;
+
+/// Fraction of the pointer movement applied while dragging with Shift held
+pub const FINE_DRAG_FACTOR: Float = 0.1;
+/// Steps moved by Page Up and Page Down
+const PAGE_STEPS: Float = 10.0;
+/// Fraction of the range moved per step if the range has no step
+const DEFAULT_STEP_FRACTION: Float = 0.01;
+
+/// Range of values edited by sliders, number inputs and knobs
+///
+/// Values are snapped to multiples of `step` from `min`, unless `step` is zero. Logarithmic ranges
+/// map positions to values exponentially, they are treated as linear unless `min` and `max` are
+/// positive.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ValueRange {
+    pub min: Float,
+    pub max: Float,
+    pub step: Float,
+    pub logarithmic: bool,
+}
+
+impl ValueRange {
+    pub fn new(min: Float, max: Float, step: Float, logarithmic: bool) -> Self {
+        Self {
+            min,
+            max,
+            step,
+            logarithmic,
+        }
+    }
+
+    fn is_logarithmic(&self) -> bool {
+        self.logarithmic && self.min > 0.0 && self.max > 0.0
+    }
+
+    pub fn contains(&self, value: Float) -> bool {
+        value >= self.min.min(self.max) && value <= self.max.max(self.min)
+    }
+
+    pub fn clamp(&self, value: Float) -> Float {
+        value.clamp(self.min.min(self.max), self.max.max(self.min))
+    }
+
+    /// Clamps the value and rounds it to the nearest step
+    pub fn snap(&self, value: Float) -> Float {
+        let value = self.clamp(value);
+        if self.step > 0.0 {
+            self.clamp(self.min + ((value - self.min) / self.step).round() * self.step)
+        } else {
+            value
+        }
+    }
+
+    /// Position of the value in the range, from 0 at `min` to 1 at `max`
+    pub fn normalize(&self, value: Float) -> Float {
+        if self.max <= self.min {
+            return 0.0;
+        }
+        let value = self.clamp(value);
+        let position = if self.is_logarithmic() {
+            (value.ln() - self.min.ln()) / (self.max.ln() - self.min.ln())
+        } else {
+            (value - self.min) / (self.max - self.min)
+        };
+        position.clamp(0.0, 1.0)
+    }
+
+    /// Snapped value at the position in the range, the inverse of `normalize`
+    pub fn denormalize(&self, position: Float) -> Float {
+        let position = position.clamp(0.0, 1.0);
+        let value = if self.is_logarithmic() {
+            (self.min.ln() + position * (self.max.ln() - self.min.ln())).exp()
+        } else {
+            self.min + position * (self.max - self.min)
+        };
+        self.snap(value)
+    }
+
+    /// Moves the value by a number of steps, or by a percentage of the range if there is no step
+    pub fn step_by(&self, value: Float, steps: Float) -> Float {
+        if self.step > 0.0 {
+            self.snap(value + steps * self.step)
+        } else {
+            self.denormalize(self.normalize(value) + steps * DEFAULT_STEP_FRACTION)
+        }
+    }
+
+    /// Value after dragging over `distance` pixels, where `length` pixels cover the whole range
+    pub fn drag(&self, start_value: Float, distance: Float, length: Float, fine: bool) -> Float {
+        if length <= 0.0 {
+            return self.snap(start_value);
+        }
+        let factor = if fine { FINE_DRAG_FACTOR } else { 1.0 };
+        self.denormalize(self.normalize(start_value) + distance / length * factor)
+    }
+
+    /// New value for a key increment, or `None` if the key does not change values
+    pub fn value_for_key(&self, value: Float, key: KeyboardKey) -> Option<Float> {
+        Some(match key {
+            KeyboardKey::ArrowUp | KeyboardKey::ArrowRight => self.step_by(value, 1.0),
+            KeyboardKey::ArrowDown | KeyboardKey::ArrowLeft => self.step_by(value, -1.0),
+            KeyboardKey::PageUp => self.step_by(value, PAGE_STEPS),
+            KeyboardKey::PageDown => self.step_by(value, -PAGE_STEPS),
+            KeyboardKey::Home => self.min,
+            KeyboardKey::End => self.max,
+            _ => return None,
+        })
+    }
+
+    /// Formats the value with as many decimals as the step has
+    pub fn format(&self, value: Float) -> String {
+        if self.step > 0.0 {
+            let step = self.step.to_string();
+            let decimals = step
+                .split_once('.')
+                .map_or(0, |(_, decimals)| decimals.len());
+            format!("{:.*}", decimals, value)
+        } else {
+            value.to_string()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snap() {
+        let range = ValueRange::new(1.0, 10.0, 0.5, false);
+        assert_eq!(range.snap(3.3), 3.5);
+        assert_eq!(range.snap(-3.0), 1.0);
+        assert_eq!(range.snap(12.0), 10.0);
+        assert_eq!(ValueRange::new(0.0, 1.0, 0.0, false).snap(0.123), 0.123);
+    }
+
+    #[test]
+    fn test_logarithmic() {
+        let range = ValueRange::new(10.0, 1000.0, 0.0, true);
+        assert_eq!(range.normalize(100.0), 0.5);
+        assert!((range.denormalize(0.5) - 100.0).abs() < 0.001);
+        // Ranges including zero cannot be logarithmic
+        let range = ValueRange::new(0.0, 100.0, 0.0, true);
+        assert_eq!(range.normalize(25.0), 0.25);
+    }
+
+    #[test]
+    fn test_drag() {
+        let range = ValueRange::new(0.0, 100.0, 1.0, false);
+        assert_eq!(range.drag(50.0, 20.0, 100.0, false), 70.0);
+        assert_eq!(range.drag(50.0, 20.0, 100.0, true), 52.0);
+        assert_eq!(range.drag(50.0, 200.0, 100.0, false), 100.0);
+    }
+
+    #[test]
+    fn test_keys() {
+        let range = ValueRange::new(0.0, 1.0, 0.1, false);
+        assert_eq!(range.value_for_key(0.5, KeyboardKey::ArrowUp), Some(0.6));
+        assert_eq!(range.value_for_key(0.5, KeyboardKey::PageDown), Some(0.0));
+        assert_eq!(range.value_for_key(0.5, KeyboardKey::End), Some(1.0));
+        assert_eq!(range.value_for_key(0.5, KeyboardKey::A), None);
+        assert_eq!(range.format(0.6), "0.6");
+        assert_eq!(ValueRange::new(0.0, 10.0, 0.0, false).format(2.5), "2.5");
+    }
+}
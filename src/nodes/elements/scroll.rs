@@ -0,0 +1,259 @@
+use crate::infrastructure::layout_context::LayoutContext;
+use crate::nodes::data::LayoutInfo;
+use crate::nodes::elements::kind::{
+    Element, EventTrigger, LayoutConstraints, LayoutUpdateContext, NoEvents,
+};
+use crate::nodes::events::{InputEvent, InputEventKind};
+use crate::nodes::types::NodeState;
+use crate::render::command::{DrawMode, RenderCommand};
+use crate::render::context::RenderContext;
+use crate::render::parameters::RenderParameters;
+use crate::result::ViuiResult;
+use crate::types::{Float, Point, Rect, Size, Vector};
+use bevy_reflect::Reflect;
+
+/// Width of the scrollbars, reserved at the right and bottom edge of the scroll container
+pub const SCROLLBAR_WIDTH: Float = 10.0;
+const MINIMUM_THUMB_LENGTH: Float = 20.0;
+
+pub struct ScrollElement {}
+
+impl Element for ScrollElement {
+    const NAME: &'static str = "scroll";
+    type State = ScrollElementState;
+    type Props = ();
+    type Events = NoEvents;
+
+    fn handle_event(
+        event: &InputEvent,
+        state: &mut Self::State,
+        _props: &Self::Props,
+        _layout: &LayoutInfo,
+        _event_trigger: &mut EventTrigger<'_, Self::Events>,
+    ) {
+        match event.kind() {
+            InputEventKind::MouseWheel(delta) => {
+                let offset = state.offset();
+                state.set_offset(offset + *delta);
+                // Enclosing scroll containers only scroll once this one reached its end
+                if state.offset() != offset {
+                    event.stop_propagation();
+                }
+            }
+            InputEventKind::MousePress(position) => {
+                let local_position = *position - state.origin().to_vector();
+                state.dragged_axis = state
+                    .scrollbars()
+                    .find(|scrollbar| scrollbar.thumb.contains(local_position))
+                    .map(|scrollbar| scrollbar.axis);
+                state.drag_start_x = position.x;
+                state.drag_start_y = position.y;
+                state.drag_start_offset_x = state.offset_x;
+                state.drag_start_offset_y = state.offset_y;
+            }
+            InputEventKind::MouseRelease(..) => {
+                state.dragged_axis = None;
+            }
+            InputEventKind::MouseMove(position) => {
+                let Some(scrollbar) = state
+                    .scrollbars()
+                    .find(|scrollbar| Some(scrollbar.axis) == state.dragged_axis)
+                else {
+                    return;
+                };
+                let mut offset = Vector::new(state.drag_start_offset_x, state.drag_start_offset_y);
+                match scrollbar.axis {
+                    ScrollAxis::Horizontal => {
+                        offset.x += (position.x - state.drag_start_x) * scrollbar.offset_per_pixel
+                    }
+                    ScrollAxis::Vertical => {
+                        offset.y += (position.y - state.drag_start_y) * scrollbar.offset_per_pixel
+                    }
+                }
+                state.set_offset(offset);
+            }
+            _ => {}
+        }
+    }
+
+    fn render_element(
+        render_context: &mut RenderContext,
+        parameters: &RenderParameters,
+        state: &Self::State,
+        _props: &Self::Props,
+    ) {
+        let styling = parameters.styling();
+        for scrollbar in state.scrollbars() {
+            render_context.add_command(RenderCommand::SetFillColor(styling.inactive_color));
+            render_context.add_command(RenderCommand::FillRect {
+                rect: scrollbar.track,
+            });
+            let thumb_color = if state.dragged_axis == Some(scrollbar.axis) {
+                styling.highlight_color
+            } else {
+                styling.button_color
+            };
+            render_context.add_command(RenderCommand::SetFillColor(thumb_color));
+            render_context.add_command(RenderCommand::DrawRoundRect {
+                rect: scrollbar.thumb,
+                radius: SCROLLBAR_WIDTH / 2.0,
+                mode: DrawMode::Fill,
+            });
+        }
+    }
+
+    fn layout_element(
+        _layout_context: &mut LayoutContext,
+        state: &mut Self::State,
+        _props: &Self::Props,
+    ) -> ViuiResult<LayoutConstraints> {
+        Ok(LayoutConstraints::ScrollLayout {
+            offset: state.offset(),
+        })
+    }
+
+    fn layout_updated(
+        state: &mut Self::State,
+        _props: &Self::Props,
+        context: &mut LayoutUpdateContext,
+    ) {
+        let layout = context.layout();
+        state.x = layout.bounds.origin.x;
+        state.y = layout.bounds.origin.y;
+        state.width = layout.bounds.size.width;
+        state.height = layout.bounds.size.height;
+        state.content_width = layout.content_size.width;
+        state.content_height = layout.content_size.height;
+        state.set_offset(state.offset());
+    }
+}
+
+/// Keeps the scroll offset within the scrollable range
+pub fn clamp_scroll_offset(offset: Vector, content_size: Size, visible_size: Size) -> Vector {
+    let maximum_offset = (content_size - visible_size).max(Size::zero());
+    Vector::new(
+        offset.x.clamp(0.0, maximum_offset.width),
+        offset.y.clamp(0.0, maximum_offset.height),
+    )
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Reflect)]
+pub enum ScrollAxis {
+    Horizontal,
+    Vertical,
+}
+
+/// Scrollbar geometry in local coordinates of the scroll container
+struct Scrollbar {
+    axis: ScrollAxis,
+    track: Rect,
+    thumb: Rect,
+    /// Scroll distance per pixel the thumb is dragged
+    offset_per_pixel: Float,
+}
+
+impl Scrollbar {
+    /// Returns `None` if the content fits, so there is nothing to scroll
+    fn new(axis: ScrollAxis, track: Rect, content_length: Float, offset: Float) -> Option<Self> {
+        let track_length = match axis {
+            ScrollAxis::Horizontal => track.width(),
+            ScrollAxis::Vertical => track.height(),
+        };
+        if content_length <= track_length {
+            return None;
+        }
+        let thumb_length = (track_length * track_length / content_length)
+            .max(MINIMUM_THUMB_LENGTH)
+            .min(track_length);
+        let maximum_offset = content_length - track_length;
+        let thumb_travel = track_length - thumb_length;
+        let thumb_position = thumb_travel * offset / maximum_offset;
+        let mut thumb = track;
+        match axis {
+            ScrollAxis::Horizontal => {
+                thumb.origin.x += thumb_position;
+                thumb.size.width = thumb_length;
+            }
+            ScrollAxis::Vertical => {
+                thumb.origin.y += thumb_position;
+                thumb.size.height = thumb_length;
+            }
+        }
+        let offset_per_pixel = if thumb_travel > 0.0 {
+            maximum_offset / thumb_travel
+        } else {
+            0.0
+        };
+        Some(Self {
+            axis,
+            track,
+            thumb,
+            offset_per_pixel,
+        })
+    }
+}
+
+#[derive(Default, Reflect, Debug)]
+pub struct ScrollElementState {
+    pub offset_x: Float,
+    pub offset_y: Float,
+    pub x: Float,
+    pub y: Float,
+    pub width: Float,
+    pub height: Float,
+    pub content_width: Float,
+    pub content_height: Float,
+    pub dragged_axis: Option<ScrollAxis>,
+    pub drag_start_x: Float,
+    pub drag_start_y: Float,
+    pub drag_start_offset_x: Float,
+    pub drag_start_offset_y: Float,
+}
+
+impl ScrollElementState {
+    fn origin(&self) -> Point {
+        Point::new(self.x, self.y)
+    }
+
+    fn offset(&self) -> Vector {
+        Vector::new(self.offset_x, self.offset_y)
+    }
+
+    fn set_offset(&mut self, offset: Vector) {
+        let content_size = Size::new(self.content_width, self.content_height);
+        let visible_size = Size::new(self.width - SCROLLBAR_WIDTH, self.height - SCROLLBAR_WIDTH);
+        let offset = clamp_scroll_offset(offset, content_size, visible_size);
+        self.offset_x = offset.x;
+        self.offset_y = offset.y;
+    }
+
+    /// Scrollbars along the axes the content overflows
+    fn scrollbars(&self) -> impl Iterator<Item = Scrollbar> {
+        let visible_width = self.width - SCROLLBAR_WIDTH;
+        let visible_height = self.height - SCROLLBAR_WIDTH;
+        [
+            Scrollbar::new(
+                ScrollAxis::Horizontal,
+                Rect::new(
+                    Point::new(0.0, visible_height),
+                    Size::new(visible_width, SCROLLBAR_WIDTH),
+                ),
+                self.content_width,
+                self.offset_x,
+            ),
+            Scrollbar::new(
+                ScrollAxis::Vertical,
+                Rect::new(
+                    Point::new(visible_width, 0.0),
+                    Size::new(SCROLLBAR_WIDTH, visible_height),
+                ),
+                self.content_height,
+                self.offset_y,
+            ),
+        ]
+        .into_iter()
+        .flatten()
+    }
+}
+
+impl NodeState for ScrollElementState {}
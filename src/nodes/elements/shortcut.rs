@@ -0,0 +1,68 @@
+use crate::infrastructure::layout_context::LayoutContext;
+use crate::nodes::data::LayoutInfo;
+use crate::nodes::elements::kind::{Element, EventTrigger, LayoutConstraints};
+use crate::nodes::events::{InputEvent, InputEventKind};
+use crate::nodes::types::{NodeEvents, NodeProps};
+use crate::render::context::RenderContext;
+use crate::render::parameters::RenderParameters;
+use crate::result::ViuiResult;
+use bevy_reflect::Reflect;
+
+/// Keyboard shortcut sending a message, e.g. `shortcut(keys="Ctrl+S" @trigger=Save)`
+///
+/// Shortcuts apply while the focus is inside their parent node, or always if they are placed in
+/// the root component or `global` is set. The UI only sends them key events matching their keys.
+pub struct ShortcutElement {}
+
+impl Element for ShortcutElement {
+    const NAME: &'static str = "shortcut";
+    type State = ();
+    type Props = ShortcutElementProps;
+    type Events = ShortcutEvents;
+
+    fn handle_event(
+        event: &InputEvent,
+        _state: &mut Self::State,
+        _props: &Self::Props,
+        _layout: &LayoutInfo,
+        event_trigger: &mut EventTrigger<ShortcutEvents>,
+    ) {
+        if let InputEventKind::KeyDown { .. } = event.kind() {
+            event_trigger(ShortcutEvents::Trigger);
+        }
+    }
+
+    fn render_element(
+        _render_context: &mut RenderContext,
+        _parameters: &RenderParameters,
+        _state: &Self::State,
+        _props: &Self::Props,
+    ) {
+    }
+
+    fn layout_element(
+        _layout_context: &mut LayoutContext,
+        _state: &mut Self::State,
+        _props: &Self::Props,
+    ) -> ViuiResult<LayoutConstraints> {
+        Ok(LayoutConstraints::Hidden)
+    }
+}
+
+#[derive(Default, Reflect, Debug)]
+pub struct ShortcutElementProps {
+    /// Key combination like `Ctrl+Shift+Z`
+    pub keys: String,
+    /// Shown in lists of the active shortcuts
+    pub description: String,
+    /// Applies regardless of the focus
+    pub global: bool,
+}
+
+impl NodeProps for ShortcutElementProps {}
+
+#[derive(Reflect, Debug)]
+pub enum ShortcutEvents {
+    Trigger,
+}
+impl NodeEvents for ShortcutEvents {}
@@ -0,0 +1,226 @@
+use crate::infrastructure::layout_context::LayoutContext;
+use crate::nodes::data::LayoutInfo;
+use crate::nodes::elements::kind::{Element, EventTrigger, LayoutConstraints};
+use crate::nodes::elements::range::ValueRange;
+use crate::nodes::events::{InputEvent, InputEventKind};
+use crate::nodes::types::{NodeEvents, NodeProps, NodeState};
+use crate::render::command::{DrawMode, RenderCommand};
+use crate::render::context::RenderContext;
+use crate::render::parameters::RenderParameters;
+use crate::result::ViuiResult;
+use crate::types::{Float, Point, Rect, Size};
+use bevy_reflect::Reflect;
+
+/// Length of the track
+const LENGTH: Float = 160.0;
+/// Width across the track
+const THICKNESS: Float = 24.0;
+const TRACK_WIDTH: Float = 4.0;
+const THUMB_RADIUS: Float = 8.0;
+
+/// Horizontal or vertical slider, dragged or moved with the arrow keys
+///
+/// Pressing the track moves the thumb to the pointer, unless Shift is held for fine dragging.
+/// The range is 0 to 1 unless `min` and `max` are set.
+pub struct SliderElement {}
+
+impl Element for SliderElement {
+    const NAME: &'static str = "slider";
+    type State = SliderElementState;
+    type Props = SliderElementProps;
+    type Events = SliderEvents;
+    const FOCUSABLE: bool = true;
+
+    fn handle_event(
+        event: &InputEvent,
+        state: &mut Self::State,
+        props: &Self::Props,
+        layout: &LayoutInfo,
+        event_trigger: &mut EventTrigger<SliderEvents>,
+    ) {
+        let range = props.range();
+        let bounds = &layout.bounds;
+        let mut change = |new_value: Float| {
+            if new_value != props.value {
+                event_trigger(SliderEvents::Change { new_value });
+            }
+        };
+        match event.kind() {
+            InputEventKind::MouseEnter => {
+                state.is_hovering = true;
+            }
+            InputEventKind::MouseLeave => {
+                state.is_hovering = false;
+            }
+            InputEventKind::MousePress(position) => {
+                state.is_pressed = true;
+                state.drag_start = track_position(bounds, position, props.vertical);
+                state.drag_start_value = props.value;
+                if !event.modifiers().shift {
+                    let position = state.drag_start / track_length(bounds, props.vertical);
+                    state.drag_start_value = range.denormalize(position);
+                    change(state.drag_start_value);
+                }
+            }
+            InputEventKind::MouseRelease(..) => {
+                state.is_pressed = false;
+            }
+            InputEventKind::MouseMove(position) if state.is_pressed => {
+                let distance = track_position(bounds, position, props.vertical) - state.drag_start;
+                change(range.drag(
+                    state.drag_start_value,
+                    distance,
+                    track_length(bounds, props.vertical),
+                    event.modifiers().shift,
+                ));
+            }
+            InputEventKind::KeyDown { key, .. } => {
+                if let Some(new_value) = range.value_for_key(props.value, *key) {
+                    change(new_value);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn render_element(
+        render_context: &mut RenderContext,
+        parameters: &RenderParameters,
+        state: &Self::State,
+        props: &Self::Props,
+    ) {
+        let styling = parameters.styling();
+        let size = render_context.size();
+        let position = props.range().normalize(props.value);
+        // Track and thumb are laid out horizontally, and rotated into place if vertical
+        let (length, thickness) = if props.vertical {
+            (size.height, size.width)
+        } else {
+            (size.width, size.height)
+        };
+        if props.vertical {
+            render_context.add_command(RenderCommand::Translate { x: 0.0, y: length });
+            render_context.add_command(RenderCommand::Rotate {
+                angle: -std::f32::consts::FRAC_PI_2,
+            });
+        }
+        let track_length = length - 2.0 * THUMB_RADIUS;
+        let track_top = (thickness - TRACK_WIDTH) / 2.0;
+        let thumb_x = THUMB_RADIUS + position * track_length;
+        render_context.add_command(RenderCommand::SetFillColor(styling.inactive_color));
+        render_context.add_command(RenderCommand::FillRoundRect {
+            rect: Rect::new(
+                Point::new(THUMB_RADIUS, track_top),
+                Size::new(track_length, TRACK_WIDTH),
+            ),
+            radius: TRACK_WIDTH / 2.0,
+        });
+        render_context.add_command(RenderCommand::SetFillColor(styling.highlight_color));
+        render_context.add_command(RenderCommand::FillRoundRect {
+            rect: Rect::new(
+                Point::new(THUMB_RADIUS, track_top),
+                Size::new(thumb_x - THUMB_RADIUS, TRACK_WIDTH),
+            ),
+            radius: TRACK_WIDTH / 2.0,
+        });
+        if state.is_pressed {
+            render_context.add_command(RenderCommand::SetFillColor(styling.button_pressed_color));
+        } else if state.is_hovering {
+            render_context.add_command(RenderCommand::SetFillColor(styling.button_hover_color));
+        } else {
+            render_context.add_command(RenderCommand::SetFillColor(styling.button_color));
+        }
+        render_context.add_command(RenderCommand::SetStrokeColor(styling.text_color));
+        render_context.add_command(RenderCommand::SetStrokeWidth(2.0));
+        render_context.add_command(RenderCommand::DrawCircle {
+            center: Point::new(thumb_x, thickness / 2.0),
+            radius: THUMB_RADIUS - 1.0,
+            mode: DrawMode::FillAndStroke,
+        });
+    }
+
+    fn layout_element(
+        _layout_context: &mut LayoutContext,
+        _state: &mut Self::State,
+        props: &Self::Props,
+    ) -> ViuiResult<LayoutConstraints> {
+        Ok(if props.vertical {
+            LayoutConstraints::FixedLayout {
+                width: THICKNESS,
+                height: LENGTH,
+            }
+        } else {
+            LayoutConstraints::FixedLayout {
+                width: LENGTH,
+                height: THICKNESS,
+            }
+        })
+    }
+}
+
+#[derive(Reflect, Debug)]
+pub struct SliderElementProps {
+    pub value: f32,
+    pub min: f32,
+    pub max: f32,
+    /// Values are rounded to multiples of the step, if set
+    pub step: f32,
+    pub logarithmic: bool,
+    pub vertical: bool,
+}
+
+impl Default for SliderElementProps {
+    fn default() -> Self {
+        Self {
+            value: 0.0,
+            min: 0.0,
+            max: 1.0,
+            step: 0.0,
+            logarithmic: false,
+            vertical: false,
+        }
+    }
+}
+
+impl SliderElementProps {
+    fn range(&self) -> ValueRange {
+        ValueRange::new(self.min, self.max, self.step, self.logarithmic)
+    }
+}
+
+impl NodeProps for SliderElementProps {}
+
+#[derive(Reflect, Debug, Default)]
+pub struct SliderElementState {
+    pub is_hovering: bool,
+    pub is_pressed: bool,
+    /// Track position where the drag started
+    pub drag_start: Float,
+    pub drag_start_value: Float,
+}
+
+impl NodeState for SliderElementState {}
+
+/// Distance of the window position from the start of the track, along the track
+fn track_position(bounds: &Rect, position: &Point, vertical: bool) -> Float {
+    if vertical {
+        bounds.max_y() - THUMB_RADIUS - position.y
+    } else {
+        position.x - bounds.min_x() - THUMB_RADIUS
+    }
+}
+
+fn track_length(bounds: &Rect, vertical: bool) -> Float {
+    let length = if vertical {
+        bounds.height()
+    } else {
+        bounds.width()
+    };
+    length - 2.0 * THUMB_RADIUS
+}
+
+#[derive(Reflect, Debug)]
+pub enum SliderEvents {
+    Change { new_value: Float },
+}
+impl NodeEvents for SliderEvents {}
@@ -1,38 +1,46 @@
 use crate::infrastructure::layout_context::LayoutContext;
 use crate::nodes::elements::kind::{Element, LayoutConstraints, NoEvents};
+use crate::nodes::types::NodeProps;
 use crate::render::command::RenderCommand;
 use crate::render::context::RenderContext;
 use crate::render::parameters::RenderParameters;
 use crate::result::ViuiResult;
-use crate::types::{Point, Rect, Size};
+use crate::types::{Color, Float, Point, Rect, Size};
+use bevy_reflect::Reflect;
 use std::f32::consts::PI;
 
+/// Arc spinning around the center, animated while it is visible
 pub struct SpinnerElement {}
 
 impl Element for SpinnerElement {
     const NAME: &'static str = "spinner";
     type State = ();
-    type Props = ();
+    type Props = SpinnerElementProps;
     type Events = NoEvents;
     fn render_element(
         render_context: &mut RenderContext,
         parameters: &RenderParameters,
         _state: &Self::State,
-        _props: &Self::Props,
+        props: &Self::Props,
     ) {
         let styling = parameters.styling();
-        let t = render_context.time() * 3.0;
-        render_context.set_animated();
+        let color = props.color().ok().flatten();
+        let color = color.unwrap_or(styling.highlight_color);
+        let t = render_context.time() * props.speed * 2.0 * PI;
+        if render_context.is_visible() {
+            render_context.set_animated();
+        }
+        let size = props.size;
         render_context.add_command(RenderCommand::SetStrokeWidth(0.0));
         render_context.add_command(RenderCommand::SetFillColor(styling.background_color));
         render_context.add_command(RenderCommand::FillRect {
-            rect: Rect::new(Point::new(0.0, 0.0), Size::new(60.0, 60.0)),
+            rect: Rect::new(Point::new(0.0, 0.0), Size::new(size, size)),
         });
-        render_context.add_command(RenderCommand::SetStrokeWidth(3.0));
-        render_context.add_command(RenderCommand::SetStrokeColor(styling.highlight_color));
+        render_context.add_command(RenderCommand::SetStrokeWidth(size / 20.0));
+        render_context.add_command(RenderCommand::SetStrokeColor(color));
         render_context.add_command(RenderCommand::Arc {
-            center: Point::new(30.0, 30.0),
-            radius: 20.0,
+            center: Point::new(size / 2.0, size / 2.0),
+            radius: size / 3.0,
             start_angle: 0.0 + t,
             end_angle: 1.0 * PI + t,
         });
@@ -41,11 +49,45 @@ impl Element for SpinnerElement {
     fn layout_element(
         _layout_context: &mut LayoutContext,
         _state: &mut Self::State,
-        _props: &Self::Props,
+        props: &Self::Props,
     ) -> ViuiResult<LayoutConstraints> {
+        // Invalid colors are reported here, as rendering cannot fail
+        props.color()?;
         Ok(LayoutConstraints::FixedLayout {
-            width: 60.0,
-            height: 60.0,
+            width: props.size,
+            height: props.size,
         })
     }
 }
+
+#[derive(Reflect, Debug)]
+pub struct SpinnerElementProps {
+    /// Width and height
+    pub size: Float,
+    /// Hex color of the arc, the highlight color if empty
+    pub color: String,
+    /// Turns per second
+    pub speed: Float,
+}
+
+impl Default for SpinnerElementProps {
+    fn default() -> Self {
+        Self {
+            size: 60.0,
+            color: String::new(),
+            speed: 0.5,
+        }
+    }
+}
+
+impl SpinnerElementProps {
+    fn color(&self) -> ViuiResult<Option<Color>> {
+        if self.color.is_empty() {
+            Ok(None)
+        } else {
+            Color::parse(&self.color).map(Some)
+        }
+    }
+}
+
+impl NodeProps for SpinnerElementProps {}
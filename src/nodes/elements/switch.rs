@@ -0,0 +1,79 @@
+use crate::infrastructure::layout_context::LayoutContext;
+use crate::nodes::data::LayoutInfo;
+use crate::nodes::elements::checkbox::{
+    draw_label, indicator_color, measure_labeled, CheckboxElement, CheckboxElementProps,
+    CheckboxElementState, CheckboxEvents, INDICATOR_SIZE,
+};
+use crate::nodes::elements::kind::{Element, EventTrigger, LayoutConstraints};
+use crate::nodes::events::InputEvent;
+use crate::render::command::{DrawMode, RenderCommand};
+use crate::render::context::RenderContext;
+use crate::render::parameters::RenderParameters;
+use crate::result::ViuiResult;
+use crate::types::{Float, Point, Rect, Size};
+
+/// Toggle switch, behaving like a checkbox with a sliding knob
+pub struct SwitchElement {}
+
+const TRACK_WIDTH: Float = INDICATOR_SIZE * 2.0;
+
+impl Element for SwitchElement {
+    const NAME: &'static str = "switch";
+    type State = CheckboxElementState;
+    type Props = CheckboxElementProps;
+    type Events = CheckboxEvents;
+    const FOCUSABLE: bool = true;
+
+    fn handle_event(
+        event: &InputEvent,
+        state: &mut Self::State,
+        props: &Self::Props,
+        layout: &LayoutInfo,
+        event_trigger: &mut EventTrigger<CheckboxEvents>,
+    ) {
+        CheckboxElement::handle_event(event, state, props, layout, event_trigger);
+    }
+
+    fn render_element(
+        render_context: &mut RenderContext,
+        parameters: &RenderParameters,
+        state: &Self::State,
+        props: &Self::Props,
+    ) {
+        let styling = parameters.styling();
+        let top = (render_context.size().height - INDICATOR_SIZE) / 2.0;
+        let radius = INDICATOR_SIZE / 2.0;
+        let track_color = if props.checked {
+            styling.highlight_color
+        } else {
+            styling.inactive_color
+        };
+        render_context.add_command(RenderCommand::SetFillColor(track_color));
+        render_context.add_command(RenderCommand::FillRoundRect {
+            rect: Rect::new(Point::new(0.0, top), Size::new(TRACK_WIDTH, INDICATOR_SIZE)),
+            radius,
+        });
+        let knob_x = if props.checked {
+            TRACK_WIDTH - radius
+        } else {
+            radius
+        };
+        render_context.add_command(RenderCommand::SetFillColor(indicator_color(styling, state)));
+        render_context.add_command(RenderCommand::SetStrokeColor(styling.text_color));
+        render_context.add_command(RenderCommand::SetStrokeWidth(2.0));
+        render_context.add_command(RenderCommand::DrawCircle {
+            center: Point::new(knob_x, top + radius),
+            radius: radius - 1.0,
+            mode: DrawMode::FillAndStroke,
+        });
+        draw_label(render_context, styling, TRACK_WIDTH, &props.label);
+    }
+
+    fn layout_element(
+        _layout_context: &mut LayoutContext,
+        _state: &mut Self::State,
+        props: &Self::Props,
+    ) -> ViuiResult<LayoutConstraints> {
+        Ok(measure_labeled(TRACK_WIDTH, &props.label))
+    }
+}
@@ -0,0 +1,640 @@
+use crate::ast::nodes::{
+    ExpressionAst, ExpressionKind, ForItemDefinition, ItemAst, ItemDefinition, NodeAst,
+    NodeDefinition, PropAst, PropDefinition,
+};
+use crate::ast::value::ExpressionValue;
+use crate::bail;
+use crate::infrastructure::layout_context::LayoutContext;
+use crate::nodes::data::LayoutInfo;
+use crate::nodes::elements::kind::{
+    Alignment, BoxLayout, Element, EventTrigger, LayoutConstraints, NoEvents, UpdateContext,
+};
+use crate::nodes::elements::list::ListElement;
+use crate::nodes::events::{EventPhase, InputEvent, InputEventKind, KeyboardKey};
+use crate::nodes::types::{NodeEvents, NodeProps, NodeState};
+use crate::render::command::RenderCommand;
+use crate::render::context::RenderContext;
+use crate::render::parameters::RenderParameters;
+use crate::result::ViuiResult;
+use crate::types::{Float, Point, Rect, SideOffsets, Size};
+use bevy_reflect::Reflect;
+use std::mem::take;
+
+/// Name the rows are bound to in the cell templates of the columns
+pub const ROW_BINDING: &str = "row";
+const HEADER_HEIGHT: Float = 28.0;
+const DEFAULT_ROW_HEIGHT: Float = 24.0;
+const DEFAULT_COLUMN_WIDTH: Float = 120.0;
+const MIN_COLUMN_WIDTH: Float = 30.0;
+/// Distance from a column edge within which the header is dragged to resize the column
+const RESIZE_HANDLE_WIDTH: Float = 4.0;
+const CELL_PADDING: Float = 6.0;
+/// Width reserved for the sort arrow next to the header of the sorted column
+const SORT_ARROW_WIDTH: Float = 12.0;
+
+/// Table of the `rows` collection, with a cell per row for each `column` child
+///
+/// The children of a column are the template of its cells, with the row bound to `row`. Rows are
+/// virtualised like the rows of a `list`, below a header that stays in place. Columns are resized
+/// by dragging the edges in the header, clicking the header of a column with a `key` triggers
+/// `sort`, and clicking a row or pressing the arrow and page keys triggers `select`. The selected
+/// row is scrolled into view whenever the selection changes.
+pub struct TableElement {}
+
+impl Element for TableElement {
+    const NAME: &'static str = "table";
+    type State = TableElementState;
+    type Props = TableElementProps;
+    type Events = TableEvents;
+    const FOCUSABLE: bool = true;
+
+    fn expand_template(node: &NodeAst) -> ViuiResult<Option<NodeAst>> {
+        expand_table(node).map(Some)
+    }
+
+    fn update_children(
+        state: &mut Self::State,
+        props: &Self::Props,
+        context: &mut UpdateContext,
+    ) -> ViuiResult<()> {
+        let mut columns = vec![];
+        context.update_children::<TableColumnElement>(|_, column| {
+            columns.push((column.column()?, column.initial_width()));
+            Ok(())
+        })?;
+        // Columns keep their width once resized
+        state.column_widths.truncate(columns.len());
+        for (_, width) in &columns[state.column_widths.len()..] {
+            state.column_widths.push(*width);
+        }
+        state.columns = columns.into_iter().map(|(column, _)| column).collect();
+        // Rows and cells of nested tables are updated again by their own table
+        context.update_descendants::<TableRowElement>(|row_state, row| {
+            row_state.is_selected = row.index == props.selected;
+            Ok(())
+        })?;
+        context.update_descendants::<TableCellElement>(|cell_state, cell| {
+            let column = cell.column as usize;
+            if let (Some(width), Some(column)) =
+                (state.column_widths.get(column), state.columns.get(column))
+            {
+                cell_state.width = *width;
+                cell_state.align = column.align;
+            }
+            Ok(())
+        })?;
+        // The header scrolls horizontally with the rows
+        context.update_children::<ListElement>(|list, list_props| {
+            // A newly selected row is scrolled into view once the rows have been laid out
+            if state.scrolled_selection != Some(props.selected) && list.scroll.height > 0.0 {
+                if props.selected >= 0 {
+                    list.scroll_to_row(props.selected as usize, list_props);
+                }
+                state.scrolled_selection = Some(props.selected);
+            }
+            state.scroll_x = list.scroll.offset_x;
+            state.row_count = list.row_count;
+            state.page_rows = list.page_rows(list_props);
+            Ok(())
+        })
+    }
+
+    fn handle_event(
+        event: &InputEvent,
+        state: &mut Self::State,
+        props: &Self::Props,
+        layout: &LayoutInfo,
+        event_trigger: &mut EventTrigger<TableEvents>,
+    ) {
+        match event.kind() {
+            InputEventKind::MousePress(position) => {
+                let header = Rect::new(
+                    layout.bounds.origin,
+                    Size::new(layout.bounds.width(), HEADER_HEIGHT),
+                );
+                if !header.contains(*position) {
+                    return;
+                }
+                let x = position.x - header.min_x() + state.scroll_x;
+                if let Some(index) = state.resize_handle_at(x) {
+                    state.resizing = Some(index);
+                    state.drag_start_x = position.x;
+                    state.drag_start_width = state.column_widths[index];
+                } else if let Some(column) = state.column_at(x).map(|index| &state.columns[index]) {
+                    if !column.key.is_empty() {
+                        event_trigger(TableEvents::Sort {
+                            column: column.key.clone(),
+                            descending: column.key == props.sort_column && !props.sort_descending,
+                        });
+                    }
+                }
+            }
+            InputEventKind::MouseMove(position) => {
+                if let Some(index) = state.resizing {
+                    let width = state.drag_start_width + position.x - state.drag_start_x;
+                    state.column_widths[index] = width.max(MIN_COLUMN_WIDTH);
+                }
+            }
+            InputEventKind::MouseRelease(..) => {
+                state.resizing = None;
+            }
+            InputEventKind::KeyDown { key, .. } if event.phase() == EventPhase::Target => {
+                let last_index = state.row_count as i32 - 1;
+                let index = match key {
+                    KeyboardKey::ArrowUp => (props.selected - 1).max(0),
+                    KeyboardKey::ArrowDown => (props.selected + 1).min(last_index),
+                    KeyboardKey::PageUp => (props.selected - state.page_rows as i32).max(0),
+                    KeyboardKey::PageDown => {
+                        (props.selected + state.page_rows as i32).min(last_index)
+                    }
+                    KeyboardKey::Home => 0,
+                    KeyboardKey::End => last_index,
+                    _ => return,
+                };
+                if index >= 0 && index != props.selected {
+                    event_trigger(TableEvents::Select {
+                        index: index as Float,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn render_element(
+        render_context: &mut RenderContext,
+        parameters: &RenderParameters,
+        state: &Self::State,
+        props: &Self::Props,
+    ) {
+        let styling = parameters.styling();
+        let width = render_context.size().width;
+        render_context.add_command(RenderCommand::SetFillColor(styling.button_color));
+        render_context.add_command(RenderCommand::FillRect {
+            rect: Rect::from_size(Size::new(width, HEADER_HEIGHT)),
+        });
+        render_context.add_command(RenderCommand::SetStrokeWidth(1.0));
+        render_context.add_command(RenderCommand::SetStrokeColor(styling.border_color));
+        render_context.add_command(RenderCommand::Line {
+            start: Point::new(0.0, HEADER_HEIGHT - 0.5),
+            end: Point::new(width, HEADER_HEIGHT - 0.5),
+        });
+        // The header follows the rows when they are scrolled horizontally
+        let mut left = -state.scroll_x;
+        for (index, (column, column_width)) in
+            state.columns.iter().zip(&state.column_widths).enumerate()
+        {
+            let is_sorted = !column.key.is_empty() && column.key == props.sort_column;
+            let text_size = state.header_sizes.get(index).copied().unwrap_or_default();
+            let arrow_width = if is_sorted { SORT_ARROW_WIDTH } else { 0.0 };
+            let text_x = match column.align {
+                Alignment::Start => CELL_PADDING,
+                Alignment::Center => (column_width - text_size.width - arrow_width) / 2.0,
+                Alignment::End => column_width - CELL_PADDING - text_size.width - arrow_width,
+            };
+            render_context.add_command(RenderCommand::Save);
+            render_context.add_command(RenderCommand::Translate { x: left, y: 0.0 });
+            render_context.add_command(RenderCommand::ClipRect(Rect::from_size(Size::new(
+                *column_width,
+                HEADER_HEIGHT,
+            ))));
+            render_context.add_command(RenderCommand::SetStrokeColor(styling.border_color));
+            render_context.add_command(RenderCommand::Line {
+                start: Point::new(column_width - 0.5, 4.0),
+                end: Point::new(column_width - 0.5, HEADER_HEIGHT - 4.0),
+            });
+            render_context.add_command(RenderCommand::SetStrokeColor(styling.text_color));
+            if is_sorted {
+                // Arrow pointing up when ascending, down when descending
+                let center_x = text_x + text_size.width + SORT_ARROW_WIDTH / 2.0 + 2.0;
+                let center_y = HEADER_HEIGHT / 2.0;
+                let tip = if props.sort_descending { 3.0 } else { -3.0 };
+                render_context.add_command(RenderCommand::Line {
+                    start: Point::new(center_x - 4.0, center_y - tip),
+                    end: Point::new(center_x, center_y + tip),
+                });
+                render_context.add_command(RenderCommand::Line {
+                    start: Point::new(center_x, center_y + tip),
+                    end: Point::new(center_x + 4.0, center_y - tip),
+                });
+            }
+            render_context.add_command(RenderCommand::Translate {
+                x: text_x,
+                y: (HEADER_HEIGHT - text_size.height) / 2.0 + render_context.text_ascent(),
+            });
+            render_context.add_command(RenderCommand::DrawText(column.header.clone()));
+            render_context.add_command(RenderCommand::Restore);
+            left += column_width;
+        }
+    }
+
+    fn layout_element(
+        layout_context: &mut LayoutContext,
+        state: &mut Self::State,
+        _props: &Self::Props,
+    ) -> ViuiResult<LayoutConstraints> {
+        state.header_sizes = state
+            .columns
+            .iter()
+            .map(|column| {
+                let text_size = layout_context.measure_text(&column.header)?;
+                Ok(Size::new(text_size.width, text_size.height))
+            })
+            .collect::<ViuiResult<_>>()?;
+        Ok(LayoutConstraints::BoxLayout(BoxLayout {
+            padding: SideOffsets::new(HEADER_HEIGHT, 0.0, 0.0, 0.0),
+            fill: true,
+            clip: true,
+            ..Default::default()
+        }))
+    }
+
+    fn handle_descendant_event(
+        event: &dyn NodeEvents,
+        _state: &mut Self::State,
+        _props: &Self::Props,
+        event_trigger: &mut EventTrigger<TableEvents>,
+    ) {
+        if let Some(TableRowEvents::Select { index }) = event.as_any().downcast_ref() {
+            event_trigger(TableEvents::Select { index: *index });
+        }
+    }
+}
+
+#[derive(Reflect, Debug)]
+pub struct TableElementProps {
+    /// Index of the selected row, no row is selected if negative
+    pub selected: i32,
+    /// Key of the column the rows are sorted by
+    pub sort_column: String,
+    pub sort_descending: bool,
+}
+
+impl Default for TableElementProps {
+    fn default() -> Self {
+        Self {
+            selected: -1,
+            sort_column: String::new(),
+            sort_descending: false,
+        }
+    }
+}
+
+impl NodeProps for TableElementProps {}
+
+#[derive(Clone, Debug, PartialEq, Reflect)]
+pub struct TableColumn {
+    pub header: String,
+    pub key: String,
+    pub align: Alignment,
+}
+
+#[derive(Default, Reflect, Debug)]
+pub struct TableElementState {
+    /// Columns in document order
+    pub columns: Vec<TableColumn>,
+    /// Widths of the columns, starting with the `width` prop and changed by resizing
+    pub column_widths: Vec<Float>,
+    pub row_count: usize,
+    /// Horizontal scroll offset of the rows
+    pub scroll_x: Float,
+    /// Column whose right edge is dragged
+    pub resizing: Option<usize>,
+    pub drag_start_x: Float,
+    pub drag_start_width: Float,
+    /// Number of rows Page Up and Page Down move the selection by
+    pub page_rows: usize,
+    /// Selection last scrolled into view
+    pub scrolled_selection: Option<i32>,
+    /// Sizes of the header texts, measured during layout
+    #[reflect(ignore)]
+    header_sizes: Vec<Size>,
+}
+
+impl TableElementState {
+    /// Column whose right edge is near the header position
+    fn resize_handle_at(&self, x: Float) -> Option<usize> {
+        let mut right = 0.0;
+        self.column_widths.iter().position(|width| {
+            right += width;
+            (x - right).abs() <= RESIZE_HANDLE_WIDTH
+        })
+    }
+
+    fn column_at(&self, x: Float) -> Option<usize> {
+        let mut right = 0.0;
+        self.column_widths.iter().position(|width| {
+            right += width;
+            x < right
+        })
+    }
+}
+
+impl NodeState for TableElementState {}
+
+#[derive(Reflect, Debug)]
+pub enum TableEvents {
+    /// The header of the column was clicked, `descending` is toggled if the rows are sorted by it
+    Sort {
+        column: String,
+        descending: bool,
+    },
+    Select {
+        index: Float,
+    },
+}
+impl NodeEvents for TableEvents {}
+
+/// Column of a `table`, its children are the template of its cells
+pub struct TableColumnElement {}
+
+impl Element for TableColumnElement {
+    const NAME: &'static str = "column";
+    type State = ();
+    type Props = TableColumnElementProps;
+    type Events = NoEvents;
+
+    fn render_element(
+        _render_context: &mut RenderContext,
+        _parameters: &RenderParameters,
+        _state: &Self::State,
+        _props: &Self::Props,
+    ) {
+    }
+
+    fn layout_element(
+        _layout_context: &mut LayoutContext,
+        _state: &mut Self::State,
+        _props: &Self::Props,
+    ) -> ViuiResult<LayoutConstraints> {
+        Ok(LayoutConstraints::Hidden)
+    }
+}
+
+#[derive(Default, Reflect, Debug)]
+pub struct TableColumnElementProps {
+    pub header: String,
+    /// Sent with `sort` when the header is clicked, the column is not sortable if empty
+    pub key: String,
+    /// Initial width, the column can be resized
+    pub width: f32,
+    /// Horizontal alignment of the header and the cells
+    pub align: String,
+}
+
+impl TableColumnElementProps {
+    pub fn column(&self) -> ViuiResult<TableColumn> {
+        Ok(TableColumn {
+            header: self.header.clone(),
+            key: self.key.clone(),
+            align: match self.align.as_str() {
+                "" => Alignment::Start,
+                align => Alignment::parse(align)?,
+            },
+        })
+    }
+
+    pub fn initial_width(&self) -> Float {
+        if self.width > 0.0 {
+            self.width
+        } else {
+            DEFAULT_COLUMN_WIDTH
+        }
+    }
+}
+
+impl NodeProps for TableColumnElementProps {}
+
+/// Row of a `table`, created for each row when the table is expanded
+pub struct TableRowElement {}
+
+impl Element for TableRowElement {
+    const NAME: &'static str = "table_row";
+    type State = TableRowElementState;
+    type Props = TableRowElementProps;
+    type Events = TableRowEvents;
+
+    fn handle_event(
+        event: &InputEvent,
+        state: &mut Self::State,
+        props: &Self::Props,
+        _layout: &LayoutInfo,
+        event_trigger: &mut EventTrigger<TableRowEvents>,
+    ) {
+        match event.kind() {
+            InputEventKind::MouseEnter => {
+                state.is_hovering = true;
+            }
+            InputEventKind::MouseLeave => {
+                state.is_hovering = false;
+            }
+            InputEventKind::MousePress(..) if !state.is_selected => {
+                event_trigger(TableRowEvents::Select {
+                    index: props.index as Float,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    fn render_element(
+        render_context: &mut RenderContext,
+        parameters: &RenderParameters,
+        state: &Self::State,
+        _props: &Self::Props,
+    ) {
+        let styling = parameters.styling();
+        let size = render_context.size();
+        let fill_color = if state.is_selected {
+            Some(styling.highlight_color)
+        } else if state.is_hovering {
+            Some(styling.button_hover_color)
+        } else {
+            None
+        };
+        if let Some(fill_color) = fill_color {
+            render_context.add_command(RenderCommand::SetFillColor(fill_color));
+            render_context.add_command(RenderCommand::FillRect {
+                rect: Rect::from_size(size),
+            });
+        }
+        render_context.add_command(RenderCommand::SetStrokeColor(styling.border_color));
+        render_context.add_command(RenderCommand::SetStrokeWidth(1.0));
+        render_context.add_command(RenderCommand::Line {
+            start: Point::new(0.0, size.height - 0.5),
+            end: Point::new(size.width, size.height - 0.5),
+        });
+    }
+
+    fn layout_element(
+        _layout_context: &mut LayoutContext,
+        _state: &mut Self::State,
+        _props: &Self::Props,
+    ) -> ViuiResult<LayoutConstraints> {
+        Ok(LayoutConstraints::HorizontalLayout {})
+    }
+}
+
+#[derive(Default, Reflect, Debug)]
+pub struct TableRowElementProps {
+    pub index: i32,
+}
+
+impl NodeProps for TableRowElementProps {}
+
+#[derive(Default, Reflect, Debug)]
+pub struct TableRowElementState {
+    pub is_hovering: bool,
+    /// Set by the table from its `selected` prop
+    pub is_selected: bool,
+}
+
+impl NodeState for TableRowElementState {}
+
+#[derive(Reflect, Debug)]
+pub enum TableRowEvents {
+    Select { index: Float },
+}
+impl NodeEvents for TableRowEvents {}
+
+/// Cell of a `table_row`, sized and aligned like its column
+pub struct TableCellElement {}
+
+impl Element for TableCellElement {
+    const NAME: &'static str = "table_cell";
+    type State = TableCellElementState;
+    type Props = TableCellElementProps;
+    type Events = NoEvents;
+
+    fn render_element(
+        _render_context: &mut RenderContext,
+        _parameters: &RenderParameters,
+        _state: &Self::State,
+        _props: &Self::Props,
+    ) {
+    }
+
+    fn layout_element(
+        _layout_context: &mut LayoutContext,
+        state: &mut Self::State,
+        _props: &Self::Props,
+    ) -> ViuiResult<LayoutConstraints> {
+        Ok(LayoutConstraints::BoxLayout(BoxLayout {
+            width: Some(state.width),
+            padding: SideOffsets::new(0.0, CELL_PADDING, 0.0, CELL_PADDING),
+            align: Some(state.align),
+            justify: Alignment::Center,
+            clip: true,
+            ..Default::default()
+        }))
+    }
+}
+
+#[derive(Default, Reflect, Debug)]
+pub struct TableCellElementProps {
+    /// Index of the column in the table
+    pub column: i32,
+}
+
+impl NodeProps for TableCellElementProps {}
+
+/// Set by the table from the column of the cell
+#[derive(Default, Reflect, Debug)]
+pub struct TableCellElementState {
+    pub width: Float,
+    pub align: Alignment,
+}
+
+impl NodeState for TableCellElementState {}
+
+/// Moves the cell templates of the columns into a `list` of rows of the `rows` collection
+///
+/// Each `column` child of the table keeps its props, while its children become the content of
+/// a `table_cell` in a `table_row` per row.
+fn expand_table(node: &NodeAst) -> ViuiResult<NodeAst> {
+    let span = node.span();
+    let mut table = node.data().clone();
+    let mut take_prop = |name: &str| {
+        let index = table.props.iter().position(|prop| prop.name == name)?;
+        Some(table.props.remove(index).into_data().expression)
+    };
+    let Some(rows) = take_prop("rows") else {
+        bail!("Table has no 'rows' prop");
+    };
+    let expression = |kind| ExpressionAst::new(span, kind);
+    let row_height = take_prop("row_height").unwrap_or_else(|| {
+        expression(ExpressionKind::Literal(ExpressionValue::Float(
+            DEFAULT_ROW_HEIGHT,
+        )))
+    });
+    let node_item = |tag: &str, props: Vec<(&str, ExpressionAst)>, children: Vec<ItemAst>| {
+        let props = props
+            .into_iter()
+            .map(|(name, expression)| {
+                PropAst::new(
+                    span,
+                    PropDefinition {
+                        name: name.to_string(),
+                        expression,
+                    },
+                )
+            })
+            .collect();
+        ItemAst::new(
+            span,
+            ItemDefinition::Node {
+                node: NodeAst::new(
+                    span,
+                    NodeDefinition {
+                        tag: tag.to_string(),
+                        props,
+                        children,
+                        events: vec![],
+                    },
+                ),
+            },
+        )
+    };
+    let mut cells = vec![];
+    for (index, child) in table.children.iter_mut().enumerate() {
+        let ItemDefinition::Node { node: column } = &mut **child else {
+            bail!("Table children must be columns");
+        };
+        if column.tag != TableColumnElement::NAME {
+            bail!("Table children must be columns, but found '{}'", column.tag);
+        }
+        cells.push(node_item(
+            TableCellElement::NAME,
+            vec![(
+                "column",
+                expression(ExpressionKind::Literal(ExpressionValue::Float(
+                    index as Float,
+                ))),
+            )],
+            take(&mut column.children),
+        ));
+    }
+    let row = node_item(
+        TableRowElement::NAME,
+        vec![(
+            "index",
+            expression(ExpressionKind::VarUse(format!("{}#index", ROW_BINDING))),
+        )],
+        cells,
+    );
+    let for_item = ItemAst::new(
+        span,
+        ItemDefinition::For(Box::new(ForItemDefinition {
+            expression: rows,
+            binding_name: ROW_BINDING.to_string(),
+            each_item: row,
+        })),
+    );
+    let list = node_item(
+        ListElement::NAME,
+        vec![("row_height", row_height)],
+        vec![for_item],
+    );
+    table.children.push(list);
+    Ok(NodeAst::new(span, table))
+}
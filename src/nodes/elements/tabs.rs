@@ -0,0 +1,289 @@
+use crate::infrastructure::layout_context::LayoutContext;
+use crate::nodes::data::LayoutInfo;
+use crate::nodes::elements::kind::{
+    BoxLayout, Element, EventTrigger, LayoutConstraints, NoEvents, UpdateContext,
+};
+use crate::nodes::events::{EventPhase, InputEvent, InputEventKind, KeyboardKey};
+use crate::nodes::types::{NodeEvents, NodeProps, NodeState};
+use crate::render::command::RenderCommand;
+use crate::render::context::RenderContext;
+use crate::render::parameters::RenderParameters;
+use crate::result::ViuiResult;
+use crate::types::{Float, Point, Rect, SideOffsets, Size};
+use bevy_reflect::Reflect;
+
+pub(crate) const TAB_BAR_HEIGHT: Float = 28.0;
+/// Height of the line under the selected tab
+const INDICATOR_HEIGHT: Float = 2.0;
+
+/// Shows one of its `tab` children at a time, picked in the tab bar or with the arrow keys
+///
+/// Without a bound `selected` prop the selection is kept by the node, starting with the first tab.
+/// Only the content of the selected tab is evaluated, laid out and rendered.
+pub struct TabsElement {}
+
+impl Element for TabsElement {
+    const NAME: &'static str = "tabs";
+    type State = TabsElementState;
+    type Props = TabsElementProps;
+    type Events = TabsEvents;
+    const FOCUSABLE: bool = true;
+
+    fn update_children(
+        state: &mut Self::State,
+        props: &Self::Props,
+        context: &mut UpdateContext,
+    ) -> ViuiResult<()> {
+        let mut titles = vec![];
+        let mut values = vec![];
+        context.update_children::<TabElement>(|tab_state, tab| {
+            tab_state.has_tabs = true;
+            titles.push(tab.title.clone());
+            values.push(tab.value().to_string());
+            Ok(())
+        })?;
+        if context.is_prop_bound("selected") || state.selected.is_empty() {
+            state.selected = props.selected.clone();
+        }
+        // The first tab is selected unless another one is
+        if !values.contains(&state.selected) {
+            state.selected = values.first().cloned().unwrap_or_default();
+        }
+        state.titles = titles;
+        state.values = values;
+        context.update_children::<TabElement>(|tab_state, tab| {
+            tab_state.is_selected = tab.value() == state.selected;
+            Ok(())
+        })
+    }
+
+    fn handle_event(
+        event: &InputEvent,
+        state: &mut Self::State,
+        _props: &Self::Props,
+        layout: &LayoutInfo,
+        event_trigger: &mut EventTrigger<TabsEvents>,
+    ) {
+        let mut select = |state: &mut TabsElementState, index: usize| {
+            if let Some(value) = state.values.get(index) {
+                if *value != state.selected {
+                    state.selected = value.clone();
+                    event_trigger(TabsEvents::Change {
+                        new_value: value.clone(),
+                    });
+                }
+            }
+        };
+        let selected_index = state.selected_index();
+        let last_index = state.values.len().saturating_sub(1);
+        match event.kind() {
+            InputEventKind::MouseMove(position) => {
+                state.hovered = state.tab_at(&layout.bounds, position);
+            }
+            InputEventKind::MouseLeave => {
+                state.hovered = None;
+            }
+            InputEventKind::MousePress(position) => {
+                if let Some(index) = state.tab_at(&layout.bounds, position) {
+                    select(state, index);
+                }
+            }
+            // Keys typed into the content are left to it
+            InputEventKind::KeyDown { key, .. } if event.phase() == EventPhase::Target => {
+                let index = match key {
+                    KeyboardKey::ArrowLeft => selected_index.saturating_sub(1),
+                    KeyboardKey::ArrowRight => (selected_index + 1).min(last_index),
+                    KeyboardKey::Home => 0,
+                    KeyboardKey::End => last_index,
+                    _ => return,
+                };
+                select(state, index);
+            }
+            _ => {}
+        }
+    }
+
+    fn render_element(
+        render_context: &mut RenderContext,
+        parameters: &RenderParameters,
+        state: &Self::State,
+        _props: &Self::Props,
+    ) {
+        let styling = parameters.styling();
+        let size = render_context.size();
+        let tab_width = state.tab_width(size.width);
+        render_context.add_command(RenderCommand::SetStrokeColor(styling.border_color));
+        render_context.add_command(RenderCommand::SetStrokeWidth(1.0));
+        render_context.add_command(RenderCommand::Line {
+            start: Point::new(0.0, TAB_BAR_HEIGHT - 0.5),
+            end: Point::new(size.width, TAB_BAR_HEIGHT - 0.5),
+        });
+        let selected_index = state.selected_index();
+        for (index, title) in state.titles.iter().enumerate() {
+            let left = index as Float * tab_width;
+            if state.hovered == Some(index) {
+                render_context.add_command(RenderCommand::SetFillColor(styling.button_hover_color));
+                render_context.add_command(RenderCommand::FillRect {
+                    rect: Rect::new(Point::new(left, 0.0), Size::new(tab_width, TAB_BAR_HEIGHT)),
+                });
+            }
+            let text_color = if index == selected_index {
+                render_context.add_command(RenderCommand::SetFillColor(styling.highlight_color));
+                render_context.add_command(RenderCommand::FillRect {
+                    rect: Rect::new(
+                        Point::new(left, TAB_BAR_HEIGHT - INDICATOR_HEIGHT),
+                        Size::new(tab_width, INDICATOR_HEIGHT),
+                    ),
+                });
+                styling.text_color
+            } else {
+                styling.inactive_color
+            };
+            let text_size = render_context.measure_text(title).unwrap();
+            render_context.add_command(RenderCommand::Save);
+            render_context.add_command(RenderCommand::SetStrokeColor(text_color));
+            render_context.add_command(RenderCommand::Translate {
+                x: left + ((tab_width - text_size.width) / 2.0).max(0.0),
+                y: (TAB_BAR_HEIGHT - text_size.height) / 2.0 + render_context.text_ascent(),
+            });
+            render_context.add_command(RenderCommand::DrawText(title.clone()));
+            render_context.add_command(RenderCommand::Restore);
+        }
+    }
+
+    fn layout_element(
+        _layout_context: &mut LayoutContext,
+        _state: &mut Self::State,
+        _props: &Self::Props,
+    ) -> ViuiResult<LayoutConstraints> {
+        Ok(LayoutConstraints::BoxLayout(BoxLayout {
+            padding: SideOffsets::new(TAB_BAR_HEIGHT, 0.0, 0.0, 0.0),
+            ..Default::default()
+        }))
+    }
+}
+
+#[derive(Default, Reflect, Debug)]
+pub struct TabsElementProps {
+    /// Value of the selected tab, the selection is kept by the node unless this is bound
+    pub selected: String,
+}
+
+impl NodeProps for TabsElementProps {}
+
+#[derive(Default, Reflect, Debug)]
+pub struct TabsElementState {
+    /// Value of the selected tab
+    pub selected: String,
+    /// Titles and values of the tabs in document order, set by the UI after evaluation
+    pub titles: Vec<String>,
+    pub values: Vec<String>,
+    pub hovered: Option<usize>,
+}
+
+impl TabsElementState {
+    fn selected_index(&self) -> usize {
+        self.values
+            .iter()
+            .position(|value| *value == self.selected)
+            .unwrap_or(0)
+    }
+
+    /// Tabs share the width of the bar equally
+    fn tab_width(&self, width: Float) -> Float {
+        width / self.titles.len().max(1) as Float
+    }
+
+    /// Index of the tab in the bar at the window position, given the window bounds of the node
+    fn tab_at(&self, bounds: &Rect, position: &Point) -> Option<usize> {
+        let bar = Rect::new(bounds.origin, Size::new(bounds.width(), TAB_BAR_HEIGHT));
+        if !bar.contains(*position) {
+            return None;
+        }
+        let index = (position.x - bar.min_x()) / self.tab_width(bar.width());
+        Some((index as usize).min(self.titles.len().checked_sub(1)?))
+    }
+}
+
+impl NodeState for TabsElementState {}
+
+#[derive(Reflect, Debug)]
+pub enum TabsEvents {
+    Change { new_value: String },
+}
+impl NodeEvents for TabsEvents {}
+
+/// Page of a `tabs` element, its children are only shown while it is selected
+pub struct TabElement {}
+
+impl Element for TabElement {
+    const NAME: &'static str = "tab";
+    type State = TabElementState;
+    type Props = TabElementProps;
+    type Events = NoEvents;
+
+    fn shows_children(state: &Self::State) -> bool {
+        state.is_selected
+    }
+
+    fn update_children(
+        state: &mut Self::State,
+        _props: &Self::Props,
+        _context: &mut UpdateContext,
+    ) -> ViuiResult<()> {
+        // Tabs outside of a `tabs` element are always shown
+        if !state.has_tabs {
+            state.is_selected = true;
+        }
+        Ok(())
+    }
+
+    fn render_element(
+        _render_context: &mut RenderContext,
+        _parameters: &RenderParameters,
+        _state: &Self::State,
+        _props: &Self::Props,
+    ) {
+    }
+
+    fn layout_element(
+        _layout_context: &mut LayoutContext,
+        state: &mut Self::State,
+        _props: &Self::Props,
+    ) -> ViuiResult<LayoutConstraints> {
+        Ok(if state.is_selected {
+            LayoutConstraints::VerticalLayout {}
+        } else {
+            LayoutConstraints::Hidden
+        })
+    }
+}
+
+#[derive(Default, Reflect, Debug)]
+pub struct TabElementProps {
+    pub title: String,
+    /// Identifies the tab in the `selected` prop of `tabs`, the title if empty
+    pub value: String,
+}
+
+impl TabElementProps {
+    pub fn value(&self) -> &str {
+        if self.value.is_empty() {
+            &self.title
+        } else {
+            &self.value
+        }
+    }
+}
+
+impl NodeProps for TabElementProps {}
+
+#[derive(Default, Reflect, Debug)]
+pub struct TabElementState {
+    /// Set by the `tabs` element the tab belongs to
+    pub is_selected: bool,
+    /// Whether the tab belongs to a `tabs` element
+    pub has_tabs: bool,
+}
+
+impl NodeState for TabElementState {}
@@ -1,26 +1,34 @@
 use crate::infrastructure::layout_context::LayoutContext;
 use crate::infrastructure::text_edit_state::TextEditState;
+use crate::nodes::data::LayoutInfo;
 use crate::nodes::elements::kind::{Element, EventTrigger, LayoutConstraints};
 use crate::nodes::events::InputEvent;
 use crate::nodes::types::{NodeEvents, NodeProps, NodeState};
-use crate::render::command::RenderCommand;
+use crate::render::command::{DrawMode, RenderCommand};
 use crate::render::context::RenderContext;
 use crate::render::parameters::RenderParameters;
 use crate::result::ViuiResult;
-use crate::types::{Point, Rect, Size};
+use crate::types::{Float, Point, Rect, Size};
 use bevy_reflect::Reflect;
 
 pub struct TextInputElement {}
 
+const PADDING_X: Float = 10.0;
+const PADDING_Y: Float = 4.0;
+const MIN_WIDTH: Float = 200.0;
+
 impl Element for TextInputElement {
     const NAME: &'static str = "textinput";
     type State = TextInputElementState;
     type Props = TextInputElementProps;
     type Events = TextInputEvents;
+    const FOCUSABLE: bool = true;
+    const EDITS_TEXT: bool = true;
     fn handle_event(
         event: &InputEvent,
         state: &mut Self::State,
         props: &Self::Props,
+        _layout: &LayoutInfo,
         event_trigger: &mut EventTrigger<'_, Self::Events>,
     ) {
         let mut edit_position = state.edit_position.unwrap_or(props.text.len());
@@ -46,46 +54,64 @@ impl Element for TextInputElement {
         render_context.add_command(RenderCommand::SetStrokeColor(styling.border_color));
         render_context.add_command(RenderCommand::SetFillColor(styling.background_color));
         render_context.add_command(RenderCommand::SetStrokeWidth(2.0));
-        render_context.add_command(RenderCommand::FillRoundRect {
+        let size = render_context.size();
+        render_context.add_command(RenderCommand::DrawRoundRect {
             rect: Rect::new(
                 Point::new(stroke_width, stroke_width),
-                Size::new(1000.0 - stroke_width * 2.0, 40.0 - stroke_width * 2.0),
+                Size::new(
+                    size.width - stroke_width * 2.0,
+                    size.height - stroke_width * 2.0,
+                ),
             ),
             radius: 2.0,
+            mode: DrawMode::FillAndStroke,
         });
 
         render_context.add_command(RenderCommand::SetStrokeColor(styling.text_color));
-        if render_context.is_active() {
+        if render_context.is_focused() {
             if let Some(mut edit_position) = state.edit_position {
+                // Cursor blinks
+                render_context.set_animated();
                 edit_position = edit_position.clamp(0, props.text.len());
                 if render_context.time() % 1.0 < 0.5 {
-                    let size = render_context
+                    let text_size = render_context
                         .measure_text(&props.text[0..edit_position])
                         .unwrap();
                     render_context.add_command(RenderCommand::SetFillColor(styling.text_color));
                     render_context.add_command(RenderCommand::FillRect {
                         rect: Rect::new(
-                            Point::new(11.0 + size.width, stroke_width + 2.0),
-                            Size::new(2.0, 30.0),
+                            Point::new(PADDING_X + 1.0 + text_size.width, PADDING_Y),
+                            Size::new(2.0, text_size.height),
                         ),
                     });
                 }
             }
         }
 
-        render_context.add_command(RenderCommand::Translate { x: 10.0, y: 25.0 });
+        render_context.add_command(RenderCommand::Translate {
+            x: PADDING_X,
+            y: PADDING_Y + render_context.text_ascent(),
+        });
         render_context.add_command(RenderCommand::DrawText(props.text.clone()));
     }
 
     fn layout_element(
         _layout_context: &mut LayoutContext,
         _state: &mut Self::State,
-        _props: &Self::Props,
+        props: &Self::Props,
     ) -> ViuiResult<LayoutConstraints> {
-        Ok(LayoutConstraints::FixedLayout {
-            width: 1000.0,
-            height: 40.0,
-        })
+        let text = props.text.clone();
+        Ok(LayoutConstraints::Measured(Box::new(
+            move |measure_context, _max_width| {
+                let text_size = measure_context.measure_text(&text)?;
+                // Leave room for the cursor behind the text
+                let width = text_size.width + 2.0 * PADDING_X + 2.0;
+                Ok(Size::new(
+                    width.max(MIN_WIDTH),
+                    text_size.height + 2.0 * PADDING_Y,
+                ))
+            },
+        )))
     }
 }
 
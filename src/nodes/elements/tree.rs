@@ -0,0 +1,443 @@
+use crate::ast::nodes::{
+    ExpressionAst, ExpressionKind, ItemAst, ItemDefinition, NodeAst, NodeDefinition, PropAst,
+    PropDefinition,
+};
+use crate::ast::value::ExpressionValue;
+use crate::bail;
+use crate::infrastructure::layout_context::LayoutContext;
+use crate::nodes::data::LayoutInfo;
+use crate::nodes::elements::kind::{
+    Alignment, BoxLayout, Element, EventTrigger, ForCollection, ForValue, LayoutConstraints,
+    NoEvents, UpdateContext, ValueField,
+};
+use crate::nodes::events::{EventPhase, InputEvent, InputEventKind, KeyboardKey};
+use crate::nodes::types::{NodeEvents, NodeProps, NodeState};
+use crate::render::command::RenderCommand;
+use crate::render::context::RenderContext;
+use crate::render::parameters::RenderParameters;
+use crate::result::ViuiResult;
+use crate::types::{Float, Point, Rect, SideOffsets, Size};
+use bevy_reflect::Reflect;
+use std::collections::HashSet;
+
+const ROW_HEIGHT: Float = 24.0;
+/// Indentation of each level of the tree
+const INDENT: Float = 16.0;
+/// Width of the chevron in front of entries with children
+const CHEVRON_WIDTH: Float = 16.0;
+
+/// Expandable tree of the entries of its `for` child, recursing into the `children` of each entry
+///
+/// The template of the `for` item is shown once per visible entry, indented by its depth, with
+/// the entry bound to the loop variable. Without a bound `selected` prop the selection is kept by
+/// the node, starting with the prop value.
+pub struct TreeElement {}
+
+impl Element for TreeElement {
+    const NAME: &'static str = "tree";
+    type State = TreeElementState;
+    type Props = TreeElementProps;
+    type Events = TreeEvents;
+    const FOCUSABLE: bool = true;
+
+    fn expand_template(node: &NodeAst) -> ViuiResult<Option<NodeAst>> {
+        expand_tree(node).map(Some)
+    }
+
+    fn update_children(
+        state: &mut Self::State,
+        props: &Self::Props,
+        context: &mut UpdateContext,
+    ) -> ViuiResult<()> {
+        state.sync(&props.selected, context.is_prop_bound("selected"));
+        Ok(())
+    }
+
+    fn for_values(
+        state: &mut Self::State,
+        props: &Self::Props,
+        values: &dyn ForCollection,
+        field: &ValueField,
+    ) -> ViuiResult<Vec<ForValue>> {
+        state.rows.clear();
+        let mut shown = vec![];
+        state.add_rows(values.values()?, 0, "", props, field, &mut shown)?;
+        // The depth of each shown entry is bound as `<name>#depth`
+        Ok(shown
+            .into_iter()
+            .enumerate()
+            .map(|(index, (value, depth))| ForValue {
+                value,
+                index,
+                bindings: vec![("depth", ExpressionValue::Float(depth as Float))],
+            })
+            .collect())
+    }
+
+    fn handle_event(
+        event: &InputEvent,
+        state: &mut Self::State,
+        _props: &Self::Props,
+        layout: &LayoutInfo,
+        event_trigger: &mut EventTrigger<TreeEvents>,
+    ) {
+        match event.kind() {
+            InputEventKind::MouseMove(position) => {
+                state.hovered = state.row_at(&layout.bounds, position);
+            }
+            InputEventKind::MouseLeave => {
+                state.hovered = None;
+            }
+            InputEventKind::MousePress(position) => {
+                let Some(index) = state.row_at(&layout.bounds, position) else {
+                    return;
+                };
+                let row = &state.rows[index];
+                let chevron_x = layout.bounds.min_x() + row.depth as Float * INDENT;
+                let on_chevron = (chevron_x..chevron_x + CHEVRON_WIDTH).contains(&position.x);
+                if row.has_children && on_chevron {
+                    state.toggle(index, event_trigger);
+                } else {
+                    state.select(index, event_trigger);
+                }
+            }
+            // Keys typed into the content are left to it
+            InputEventKind::KeyDown { key, .. } if event.phase() == EventPhase::Target => {
+                let Some(last_index) = state.rows.len().checked_sub(1) else {
+                    return;
+                };
+                let selected_index = state.selected_index();
+                let current = selected_index.unwrap_or(0);
+                match key {
+                    KeyboardKey::ArrowUp => {
+                        let index =
+                            selected_index.map_or(last_index, |index| index.saturating_sub(1));
+                        state.select(index, event_trigger);
+                    }
+                    KeyboardKey::ArrowDown => {
+                        let index = selected_index.map_or(0, |index| (index + 1).min(last_index));
+                        state.select(index, event_trigger);
+                    }
+                    KeyboardKey::Home => state.select(0, event_trigger),
+                    KeyboardKey::End => state.select(last_index, event_trigger),
+                    // Expands the entry, or moves to its first child if already expanded
+                    KeyboardKey::ArrowRight => {
+                        let row = &state.rows[current];
+                        if row.has_children && !row.expanded {
+                            state.toggle(current, event_trigger);
+                        } else if row.expanded {
+                            state.select(current + 1, event_trigger);
+                        }
+                    }
+                    // Collapses the entry, or moves to its parent if already collapsed
+                    KeyboardKey::ArrowLeft => {
+                        if state.rows[current].expanded {
+                            state.toggle(current, event_trigger);
+                        } else if let Some(parent) = state.parent_index(current) {
+                            state.select(parent, event_trigger);
+                        }
+                    }
+                    KeyboardKey::Enter | KeyboardKey::Space if state.rows[current].has_children => {
+                        state.toggle(current, event_trigger);
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn render_element(
+        render_context: &mut RenderContext,
+        parameters: &RenderParameters,
+        state: &Self::State,
+        _props: &Self::Props,
+    ) {
+        let styling = parameters.styling();
+        let width = render_context.size().width;
+        for (index, row) in state.rows.iter().enumerate() {
+            let top = index as Float * ROW_HEIGHT;
+            let fill_color = if row.key == state.selected {
+                Some(styling.highlight_color)
+            } else if state.hovered == Some(index) {
+                Some(styling.button_hover_color)
+            } else {
+                None
+            };
+            if let Some(fill_color) = fill_color {
+                render_context.add_command(RenderCommand::SetFillColor(fill_color));
+                render_context.add_command(RenderCommand::FillRect {
+                    rect: Rect::new(Point::new(0.0, top), Size::new(width, ROW_HEIGHT)),
+                });
+            }
+            if !row.has_children {
+                continue;
+            }
+            // Chevron pointing right, turned down when expanded
+            render_context.add_command(RenderCommand::Save);
+            render_context.add_command(RenderCommand::SetStrokeColor(styling.text_color));
+            render_context.add_command(RenderCommand::SetStrokeWidth(2.0));
+            render_context.add_command(RenderCommand::Translate {
+                x: row.depth as Float * INDENT + CHEVRON_WIDTH / 2.0,
+                y: top + ROW_HEIGHT / 2.0,
+            });
+            if row.expanded {
+                render_context.add_command(RenderCommand::Rotate {
+                    angle: std::f32::consts::FRAC_PI_2,
+                });
+            }
+            render_context.add_command(RenderCommand::Line {
+                start: Point::new(-2.0, -4.0),
+                end: Point::new(2.0, 0.0),
+            });
+            render_context.add_command(RenderCommand::Line {
+                start: Point::new(2.0, 0.0),
+                end: Point::new(-2.0, 4.0),
+            });
+            render_context.add_command(RenderCommand::Restore);
+        }
+    }
+
+    fn layout_element(
+        _layout_context: &mut LayoutContext,
+        _state: &mut Self::State,
+        _props: &Self::Props,
+    ) -> ViuiResult<LayoutConstraints> {
+        Ok(LayoutConstraints::VerticalLayout {})
+    }
+}
+
+#[derive(Reflect, Debug)]
+pub struct TreeElementProps {
+    /// Field of the entries holding their child entries
+    pub children: String,
+    /// Field of the entries identifying them, the path of indices (like "0/2") if empty
+    pub key: String,
+    /// Key of the selected entry, the selection is kept by the node unless this is bound
+    pub selected: String,
+}
+
+impl Default for TreeElementProps {
+    fn default() -> Self {
+        Self {
+            children: "children".to_string(),
+            key: String::new(),
+            selected: String::new(),
+        }
+    }
+}
+
+impl NodeProps for TreeElementProps {}
+
+/// Visible entry of the tree
+#[derive(Clone, Debug, PartialEq, Reflect)]
+pub struct TreeRow {
+    pub key: String,
+    pub depth: usize,
+    pub has_children: bool,
+    pub expanded: bool,
+}
+
+#[derive(Default, Reflect, Debug)]
+pub struct TreeElementState {
+    /// Entries shown in order, set when evaluating the `for` child
+    pub rows: Vec<TreeRow>,
+    pub selected: String,
+    pub hovered: Option<usize>,
+    /// Keys of the expanded entries
+    #[reflect(ignore)]
+    expanded: HashSet<String>,
+    #[reflect(ignore)]
+    is_initialized: bool,
+}
+
+impl TreeElementState {
+    /// Takes the `selected` prop, initially and whenever it is bound
+    fn sync(&mut self, selected: &str, is_bound: bool) {
+        if !self.is_initialized || is_bound {
+            self.is_initialized = true;
+            self.selected = selected.to_string();
+        }
+    }
+
+    /// Flattens the expanded entries into rows, adding the visible entries with their depth
+    fn add_rows(
+        &mut self,
+        entries: Vec<ExpressionValue>,
+        depth: usize,
+        parent_path: &str,
+        props: &TreeElementProps,
+        field: &ValueField,
+        shown: &mut Vec<(ExpressionValue, usize)>,
+    ) -> ViuiResult<()> {
+        for (index, entry) in entries.into_iter().enumerate() {
+            let path = if depth == 0 {
+                index.to_string()
+            } else {
+                format!("{parent_path}/{index}")
+            };
+            let key = if props.key.is_empty() {
+                path.clone()
+            } else {
+                let Some(key) = field(&entry, &props.key)? else {
+                    bail!("Tree entry has no field '{}'", props.key);
+                };
+                key.to_string()
+            };
+            let children = match field(&entry, &props.children)? {
+                Some(ExpressionValue::Vec(children)) => children,
+                Some(value) => bail!(
+                    "Expected list for the children of tree entries, but was: {}",
+                    value
+                ),
+                None => vec![],
+            };
+            let expanded = !children.is_empty() && self.expanded.contains(&key);
+            self.rows.push(TreeRow {
+                key,
+                depth,
+                has_children: !children.is_empty(),
+                expanded,
+            });
+            shown.push((entry, depth));
+            if expanded {
+                self.add_rows(children, depth + 1, &path, props, field, shown)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn selected_index(&self) -> Option<usize> {
+        self.rows.iter().position(|row| row.key == self.selected)
+    }
+
+    /// Closest row above with a smaller depth
+    fn parent_index(&self, index: usize) -> Option<usize> {
+        let depth = self.rows[index].depth;
+        self.rows[..index].iter().rposition(|row| row.depth < depth)
+    }
+
+    fn select(&mut self, index: usize, event_trigger: &mut EventTrigger<TreeEvents>) {
+        let Some(row) = self.rows.get(index) else {
+            return;
+        };
+        if row.key != self.selected {
+            self.selected = row.key.clone();
+            event_trigger(TreeEvents::Select {
+                key: row.key.clone(),
+            });
+        }
+    }
+
+    fn toggle(&mut self, index: usize, event_trigger: &mut EventTrigger<TreeEvents>) {
+        let row = &mut self.rows[index];
+        row.expanded = !row.expanded;
+        if row.expanded {
+            self.expanded.insert(row.key.clone());
+        } else {
+            self.expanded.remove(&row.key);
+        }
+        event_trigger(TreeEvents::Toggle {
+            key: row.key.clone(),
+            expanded: row.expanded,
+        });
+    }
+
+    /// Index of the row at the window position, given the window bounds of the node
+    fn row_at(&self, bounds: &Rect, position: &Point) -> Option<usize> {
+        if !bounds.contains(*position) {
+            return None;
+        }
+        let index = ((position.y - bounds.min_y()) / ROW_HEIGHT) as usize;
+        (index < self.rows.len()).then_some(index)
+    }
+}
+
+impl NodeState for TreeElementState {}
+
+#[derive(Reflect, Debug)]
+pub enum TreeEvents {
+    Select { key: String },
+    Toggle { key: String, expanded: bool },
+}
+impl NodeEvents for TreeEvents {}
+
+/// Row of a `tree`, indenting the entry template by its depth
+pub struct TreeRowElement {}
+
+impl Element for TreeRowElement {
+    const NAME: &'static str = "tree_row";
+    type State = ();
+    type Props = TreeRowElementProps;
+    type Events = NoEvents;
+
+    fn render_element(
+        _render_context: &mut RenderContext,
+        _parameters: &RenderParameters,
+        _state: &Self::State,
+        _props: &Self::Props,
+    ) {
+    }
+
+    fn layout_element(
+        _layout_context: &mut LayoutContext,
+        _state: &mut Self::State,
+        props: &Self::Props,
+    ) -> ViuiResult<LayoutConstraints> {
+        Ok(LayoutConstraints::BoxLayout(BoxLayout {
+            horizontal: true,
+            height: Some(ROW_HEIGHT),
+            padding: SideOffsets::new(0.0, 0.0, 0.0, props.depth as Float * INDENT + CHEVRON_WIDTH),
+            align: Some(Alignment::Center),
+            clip: true,
+            ..Default::default()
+        }))
+    }
+}
+
+#[derive(Default, Reflect, Debug)]
+pub struct TreeRowElementProps {
+    pub depth: i32,
+}
+
+impl NodeProps for TreeRowElementProps {}
+
+/// Wraps the template of the `for` child of the tree in a `tree_row`
+///
+/// The depth of each entry is bound as `<name>#depth` next to `<name>#index`.
+fn expand_tree(node: &NodeAst) -> ViuiResult<NodeAst> {
+    let span = node.span();
+    let mut tree = node.data().clone();
+    let [child] = &mut tree.children[..] else {
+        bail!("Tree must have a single 'for' child");
+    };
+    let ItemDefinition::For(for_item) = &mut **child else {
+        bail!("Tree must have a single 'for' child");
+    };
+    let depth = PropAst::new(
+        span,
+        PropDefinition {
+            name: "depth".to_string(),
+            expression: ExpressionAst::new(
+                span,
+                ExpressionKind::VarUse(format!("{}#depth", for_item.binding_name)),
+            ),
+        },
+    );
+    let template = for_item.each_item.clone();
+    for_item.each_item = ItemAst::new(
+        span,
+        ItemDefinition::Node {
+            node: NodeAst::new(
+                span,
+                NodeDefinition {
+                    tag: TreeRowElement::NAME.to_string(),
+                    props: vec![depth],
+                    children: vec![template],
+                    events: vec![],
+                },
+            ),
+        },
+    );
+    Ok(NodeAst::new(span, tree))
+}
@@ -1,63 +1,238 @@
-use crate::types::{Point, Size};
+use crate::bail;
+use crate::result::ViuiResult;
+use crate::types::{Point, Size, Vector};
+use std::cell::Cell;
+use std::fmt::{Display, Formatter};
 
+/// Input event dispatched to a node
+///
+/// Events with a target in the node tree are first dispatched down the ancestry of the target
+/// (capture), then to the target and back up to the root (bubble), see [`EventPhase`].
 #[derive(Debug)]
 pub struct InputEvent {
     kind: InputEventKind,
+    phase: EventPhase,
+    modifiers: Modifiers,
+    propagation_stopped: Cell<bool>,
+    default_prevented: Cell<bool>,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum EventPhase {
+    /// Dispatched to the ancestors of the target, from the root down
+    Capture,
+    Target,
+    /// Dispatched to the ancestors of the target, from the parent up
+    Bubble,
 }
 
 #[derive(Debug)]
 #[non_exhaustive]
 pub enum InputEventKind {
-    MouseOver,
-    MouseOut,
+    /// The pointer entered the node or one of its descendants
+    MouseEnter,
+    /// The pointer left the node and its descendants
+    MouseLeave,
+    /// The node received keyboard focus
+    Focus,
+    /// The node lost keyboard focus
+    Blur,
     MouseMove(Point),
     MousePress(Point),
     MouseRelease(Point),
+    /// Distance to scroll in pixels, positive values scroll right and down
+    MouseWheel(Vector),
     Character(char),
-    KeyInput(KeyboardKey),
+    KeyDown {
+        key: KeyboardKey,
+        /// Sent again because the key is held down
+        repeat: bool,
+    },
+    KeyUp(KeyboardKey),
 }
 
 impl InputEvent {
-    pub fn mouse_over() -> Self {
+    fn new(kind: InputEventKind) -> Self {
         Self {
-            kind: InputEventKind::MouseOver,
+            kind,
+            phase: EventPhase::Target,
+            modifiers: Modifiers::NONE,
+            propagation_stopped: Cell::new(false),
+            default_prevented: Cell::new(false),
         }
     }
-    pub fn mouse_out() -> Self {
-        Self {
-            kind: InputEventKind::MouseOut,
-        }
+
+    pub fn mouse_enter() -> Self {
+        Self::new(InputEventKind::MouseEnter)
+    }
+    pub fn mouse_leave() -> Self {
+        Self::new(InputEventKind::MouseLeave)
+    }
+    pub fn focus() -> Self {
+        Self::new(InputEventKind::Focus)
+    }
+    pub fn blur() -> Self {
+        Self::new(InputEventKind::Blur)
     }
     pub fn mouse_press(position: Point) -> Self {
-        Self {
-            kind: InputEventKind::MousePress(position),
-        }
+        Self::new(InputEventKind::MousePress(position))
     }
     pub fn mouse_release(position: Point) -> Self {
-        Self {
-            kind: InputEventKind::MouseRelease(position),
-        }
+        Self::new(InputEventKind::MouseRelease(position))
     }
 
     pub fn mouse_move(position: Point) -> Self {
-        Self {
-            kind: InputEventKind::MouseMove(position),
-        }
+        Self::new(InputEventKind::MouseMove(position))
+    }
+
+    pub fn mouse_wheel(delta: Vector) -> Self {
+        Self::new(InputEventKind::MouseWheel(delta))
     }
 
     pub fn kind(&self) -> &InputEventKind {
         &self.kind
     }
 
-    pub fn character(character: char) -> Self {
-        Self {
-            kind: InputEventKind::Character(character),
+    /// Name used to map the event in the UI, e.g. `@press=Pressed`
+    pub fn name(&self) -> &'static str {
+        match self.kind {
+            InputEventKind::MouseEnter => "enter",
+            InputEventKind::MouseLeave => "leave",
+            InputEventKind::Focus => "focus",
+            InputEventKind::Blur => "blur",
+            InputEventKind::MouseMove(..) => "move",
+            InputEventKind::MousePress(..) => "press",
+            InputEventKind::MouseRelease(..) => "release",
+            InputEventKind::MouseWheel(..) => "wheel",
+            InputEventKind::Character(..) => "character",
+            InputEventKind::KeyDown { .. } => "keydown",
+            InputEventKind::KeyUp(..) => "keyup",
         }
     }
 
+    /// Whether the event is dispatched through the ancestors of its target
+    pub fn propagates(&self) -> bool {
+        !matches!(
+            self.kind,
+            InputEventKind::MouseEnter
+                | InputEventKind::MouseLeave
+                | InputEventKind::MouseMove(..)
+                | InputEventKind::Focus
+                | InputEventKind::Blur
+        )
+    }
+
+    /// Modifier keys held when the event occurred
+    pub fn modifiers(&self) -> Modifiers {
+        self.modifiers
+    }
+
+    pub fn with_modifiers(mut self, modifiers: Modifiers) -> Self {
+        self.modifiers = modifiers;
+        self
+    }
+
+    pub fn phase(&self) -> EventPhase {
+        self.phase
+    }
+
+    pub fn set_phase(&mut self, phase: EventPhase) {
+        self.phase = phase;
+    }
+
+    /// Stops the event from being dispatched to any further nodes
+    pub fn stop_propagation(&self) {
+        self.propagation_stopped.set(true);
+    }
+
+    pub fn is_propagation_stopped(&self) -> bool {
+        self.propagation_stopped.get()
+    }
+
+    /// Keeps the elements it has not reached yet from handling the event
+    ///
+    /// The dispatcher skips `Element::handle_event` from then on, while event mappings still get it.
+    pub fn prevent_default(&self) {
+        self.default_prevented.set(true);
+    }
+
+    pub fn is_default_prevented(&self) -> bool {
+        self.default_prevented.get()
+    }
+
+    pub fn character(character: char) -> Self {
+        Self::new(InputEventKind::Character(character))
+    }
+
     pub fn key_input(keyboard_key: KeyboardKey) -> Self {
+        Self::key_down(keyboard_key, false)
+    }
+
+    pub fn key_down(keyboard_key: KeyboardKey, repeat: bool) -> Self {
+        Self::new(InputEventKind::KeyDown {
+            key: keyboard_key,
+            repeat,
+        })
+    }
+
+    pub fn key_up(keyboard_key: KeyboardKey) -> Self {
+        Self::new(InputEventKind::KeyUp(keyboard_key))
+    }
+}
+
+/// Modifier keys held during an input event
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub control: bool,
+    pub alt: bool,
+    /// The Windows, Command or Super key
+    pub logo: bool,
+}
+
+impl Modifiers {
+    pub const NONE: Self = Self {
+        shift: false,
+        control: false,
+        alt: false,
+        logo: false,
+    };
+    pub const SHIFT: Self = Self {
+        shift: true,
+        ..Self::NONE
+    };
+    pub const CONTROL: Self = Self {
+        control: true,
+        ..Self::NONE
+    };
+    pub const ALT: Self = Self {
+        alt: true,
+        ..Self::NONE
+    };
+    pub const LOGO: Self = Self {
+        logo: true,
+        ..Self::NONE
+    };
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::NONE
+    }
+
+    /// Whether keys pressed with the modifiers type text, as only Shift is held if any
+    pub fn types_text(&self) -> bool {
+        !(self.control || self.alt || self.logo)
+    }
+}
+
+impl std::ops::BitOr for Modifiers {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
         Self {
-            kind: InputEventKind::KeyInput(keyboard_key),
+            shift: self.shift || other.shift,
+            control: self.control || other.control,
+            alt: self.alt || other.alt,
+            logo: self.logo || other.logo,
         }
     }
 }
@@ -69,16 +244,31 @@ pub struct UiEvent {
 
 #[derive(Debug)]
 pub enum UiEventKind {
-    MouseMoved(Point),
+    MouseMoved(MouseMoveInput),
     MouseInput(MouseInput),
+    MouseWheel(WheelInput),
     CharInput(CharInput),
     KeyInput(KeyInput),
     WindowResized { size: Size, backend_index: usize },
 }
 
+#[derive(Debug)]
+pub struct MouseMoveInput {
+    pub position: Point,
+    pub modifiers: Modifiers,
+}
+
 #[derive(Debug)]
 pub struct MouseInput {
     pub mouse_event_kind: MouseEventKind,
+    pub modifiers: Modifiers,
+}
+
+#[derive(Debug)]
+pub struct WheelInput {
+    /// Distance to scroll in pixels, positive values scroll right and down
+    pub delta: Vector,
+    pub modifiers: Modifiers,
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -92,34 +282,127 @@ pub struct CharInput {
     pub character: char,
 }
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 #[non_exhaustive]
 pub enum KeyboardKey {
     ArrowLeft,
     ArrowRight,
+    ArrowUp,
+    ArrowDown,
     Home,
     End,
+    PageUp,
+    PageDown,
+    Insert,
     Delete,
     Backspace,
     Enter,
     Escape,
     Tab,
+    Space,
+    // Letter and digit keys, independent of the keyboard layout's characters
+    A,
+    B,
+    C,
+    D,
+    E,
+    F,
+    G,
+    H,
+    I,
+    J,
+    K,
+    L,
+    M,
+    N,
+    O,
+    P,
+    Q,
+    R,
+    S,
+    T,
+    U,
+    V,
+    W,
+    X,
+    Y,
+    Z,
+    Digit0,
+    Digit1,
+    Digit2,
+    Digit3,
+    Digit4,
+    Digit5,
+    Digit6,
+    Digit7,
+    Digit8,
+    Digit9,
+    Minus,
+    Equals,
+    /// Plus key of the numeric keypad or of keyboards with a dedicated one
+    Plus,
+    Comma,
+    Period,
+    Slash,
+    Backslash,
+    Semicolon,
+    Apostrophe,
+    BracketLeft,
+    BracketRight,
+    Grave,
+    F1,
+    F2,
+    F3,
+    F4,
+    F5,
+    F6,
+    F7,
+    F8,
+    F9,
+    F10,
+    F11,
+    F12,
 }
 
 #[derive(Debug)]
 pub struct KeyInput {
     pub key: KeyboardKey,
+    pub key_event_kind: KeyEventKind,
+    /// Sent again because the key is held down
+    pub repeat: bool,
+    pub modifiers: Modifiers,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum KeyEventKind {
+    Pressed,
+    Released,
 }
 
 impl UiEvent {
     pub fn mouse_move(position: Point) -> Self {
         Self {
-            kind: UiEventKind::MouseMoved(position),
+            kind: UiEventKind::MouseMoved(MouseMoveInput {
+                position,
+                modifiers: Modifiers::NONE,
+            }),
         }
     }
     pub fn mouse_input(mouse_event_kind: MouseEventKind) -> Self {
         Self {
-            kind: UiEventKind::MouseInput(MouseInput { mouse_event_kind }),
+            kind: UiEventKind::MouseInput(MouseInput {
+                mouse_event_kind,
+                modifiers: Modifiers::NONE,
+            }),
+        }
+    }
+
+    pub fn mouse_wheel(delta: Vector) -> Self {
+        Self {
+            kind: UiEventKind::MouseWheel(WheelInput {
+                delta,
+                modifiers: Modifiers::NONE,
+            }),
         }
     }
 
@@ -130,9 +413,34 @@ impl UiEvent {
     }
 
     pub fn key_input(keyboard_key: KeyboardKey) -> Self {
+        Self::key_event(keyboard_key, KeyEventKind::Pressed, false)
+    }
+
+    pub fn key_event(
+        keyboard_key: KeyboardKey,
+        key_event_kind: KeyEventKind,
+        repeat: bool,
+    ) -> Self {
         Self {
-            kind: UiEventKind::KeyInput(KeyInput { key: keyboard_key }),
+            kind: UiEventKind::KeyInput(KeyInput {
+                key: keyboard_key,
+                key_event_kind,
+                repeat,
+                modifiers: Modifiers::NONE,
+            }),
+        }
+    }
+
+    /// Sets the modifiers of key, mouse and wheel events
+    pub fn with_modifiers(mut self, modifiers: Modifiers) -> Self {
+        match &mut self.kind {
+            UiEventKind::MouseMoved(input) => input.modifiers = modifiers,
+            UiEventKind::MouseInput(input) => input.modifiers = modifiers,
+            UiEventKind::MouseWheel(input) => input.modifiers = modifiers,
+            UiEventKind::KeyInput(input) => input.modifiers = modifiers,
+            _ => {}
         }
+        self
     }
     pub fn window_resized(size: Size, backend_index: usize) -> Self {
         Self {
@@ -143,3 +451,194 @@ impl UiEvent {
         }
     }
 }
+
+/// Names of the keys in shortcuts, e.g. `Ctrl+PageUp`
+const KEY_NAMES: &[(KeyboardKey, &str)] = &[
+    (KeyboardKey::ArrowLeft, "Left"),
+    (KeyboardKey::ArrowRight, "Right"),
+    (KeyboardKey::ArrowUp, "Up"),
+    (KeyboardKey::ArrowDown, "Down"),
+    (KeyboardKey::Home, "Home"),
+    (KeyboardKey::End, "End"),
+    (KeyboardKey::PageUp, "PageUp"),
+    (KeyboardKey::PageDown, "PageDown"),
+    (KeyboardKey::Insert, "Insert"),
+    (KeyboardKey::Delete, "Delete"),
+    (KeyboardKey::Backspace, "Backspace"),
+    (KeyboardKey::Enter, "Enter"),
+    (KeyboardKey::Escape, "Escape"),
+    (KeyboardKey::Tab, "Tab"),
+    (KeyboardKey::Space, "Space"),
+    (KeyboardKey::A, "A"),
+    (KeyboardKey::B, "B"),
+    (KeyboardKey::C, "C"),
+    (KeyboardKey::D, "D"),
+    (KeyboardKey::E, "E"),
+    (KeyboardKey::F, "F"),
+    (KeyboardKey::G, "G"),
+    (KeyboardKey::H, "H"),
+    (KeyboardKey::I, "I"),
+    (KeyboardKey::J, "J"),
+    (KeyboardKey::K, "K"),
+    (KeyboardKey::L, "L"),
+    (KeyboardKey::M, "M"),
+    (KeyboardKey::N, "N"),
+    (KeyboardKey::O, "O"),
+    (KeyboardKey::P, "P"),
+    (KeyboardKey::Q, "Q"),
+    (KeyboardKey::R, "R"),
+    (KeyboardKey::S, "S"),
+    (KeyboardKey::T, "T"),
+    (KeyboardKey::U, "U"),
+    (KeyboardKey::V, "V"),
+    (KeyboardKey::W, "W"),
+    (KeyboardKey::X, "X"),
+    (KeyboardKey::Y, "Y"),
+    (KeyboardKey::Z, "Z"),
+    (KeyboardKey::Digit0, "0"),
+    (KeyboardKey::Digit1, "1"),
+    (KeyboardKey::Digit2, "2"),
+    (KeyboardKey::Digit3, "3"),
+    (KeyboardKey::Digit4, "4"),
+    (KeyboardKey::Digit5, "5"),
+    (KeyboardKey::Digit6, "6"),
+    (KeyboardKey::Digit7, "7"),
+    (KeyboardKey::Digit8, "8"),
+    (KeyboardKey::Digit9, "9"),
+    (KeyboardKey::Minus, "-"),
+    (KeyboardKey::Equals, "="),
+    (KeyboardKey::Plus, "Plus"),
+    (KeyboardKey::Comma, ","),
+    (KeyboardKey::Period, "."),
+    (KeyboardKey::Slash, "/"),
+    (KeyboardKey::Backslash, "\\"),
+    (KeyboardKey::Semicolon, ";"),
+    (KeyboardKey::Apostrophe, "'"),
+    (KeyboardKey::BracketLeft, "["),
+    (KeyboardKey::BracketRight, "]"),
+    (KeyboardKey::Grave, "`"),
+    (KeyboardKey::F1, "F1"),
+    (KeyboardKey::F2, "F2"),
+    (KeyboardKey::F3, "F3"),
+    (KeyboardKey::F4, "F4"),
+    (KeyboardKey::F5, "F5"),
+    (KeyboardKey::F6, "F6"),
+    (KeyboardKey::F7, "F7"),
+    (KeyboardKey::F8, "F8"),
+    (KeyboardKey::F9, "F9"),
+    (KeyboardKey::F10, "F10"),
+    (KeyboardKey::F11, "F11"),
+    (KeyboardKey::F12, "F12"),
+];
+
+impl KeyboardKey {
+    /// Looks up a key by its name in shortcuts, ignoring case
+    pub fn from_name(name: &str) -> Option<Self> {
+        KEY_NAMES
+            .iter()
+            .find(|(_, key_name)| key_name.eq_ignore_ascii_case(name))
+            .map(|(key, _)| *key)
+    }
+
+    pub fn name(&self) -> &'static str {
+        KEY_NAMES
+            .iter()
+            .find(|(key, _)| key == self)
+            .map(|(_, name)| *name)
+            .unwrap_or("?")
+    }
+}
+
+/// Key pressed together with modifiers, written like `Ctrl+Shift+Z`
+///
+/// The plus key is written `Plus` or as a trailing `+`, like `Ctrl++`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct KeyCombination {
+    pub key: KeyboardKey,
+    pub modifiers: Modifiers,
+}
+
+impl KeyCombination {
+    pub fn parse(text: &str) -> ViuiResult<Self> {
+        let mut modifiers = Modifiers::NONE;
+        let (modifier_text, key) = match text.trim().rsplit_once('+') {
+            Some(("", "")) => ("", "Plus"),
+            Some((rest, "")) => match rest.trim_end().strip_suffix('+') {
+                Some(modifier_text) => (modifier_text, "Plus"),
+                None => bail!("Missing key in key combination '{}'", text),
+            },
+            Some((modifier_text, key)) => (modifier_text, key.trim()),
+            None => ("", text.trim()),
+        };
+        if key.is_empty() {
+            bail!("Empty key combination");
+        }
+        let modifier_names = modifier_text.split('+').map(str::trim);
+        for modifier in modifier_names.filter(|_| !modifier_text.is_empty()) {
+            match modifier.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => modifiers.control = true,
+                "shift" => modifiers.shift = true,
+                "alt" | "option" => modifiers.alt = true,
+                "cmd" | "meta" | "super" | "logo" => modifiers.logo = true,
+                _ => bail!(
+                    "Unknown modifier '{}' in key combination '{}'",
+                    modifier,
+                    text
+                ),
+            }
+        }
+        let Some(key) = KeyboardKey::from_name(key) else {
+            bail!("Unknown key '{}' in key combination '{}'", key, text);
+        };
+        Ok(Self { key, modifiers })
+    }
+}
+
+impl Display for KeyCombination {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (held, name) in [
+            (self.modifiers.control, "Ctrl"),
+            (self.modifiers.alt, "Alt"),
+            (self.modifiers.shift, "Shift"),
+            (self.modifiers.logo, "Cmd"),
+        ] {
+            if held {
+                write!(f, "{name}+")?;
+            }
+        }
+        f.write_str(self.key.name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_key_combination() {
+        let combination = KeyCombination::parse("ctrl+Shift+z").unwrap();
+        assert_eq!(combination.key, KeyboardKey::Z);
+        assert_eq!(combination.modifiers, Modifiers::CONTROL | Modifiers::SHIFT);
+        assert_eq!(combination.to_string(), "Ctrl+Shift+Z");
+        assert_eq!(KeyCombination::parse("F5").unwrap().to_string(), "F5");
+        assert!(KeyCombination::parse("Hyper+S").is_err());
+        assert!(KeyCombination::parse("Ctrl+Foo").is_err());
+    }
+
+    #[test]
+    fn test_parse_plus_key() {
+        let combination = KeyCombination::parse("Ctrl++").unwrap();
+        assert_eq!(combination.key, KeyboardKey::Plus);
+        assert_eq!(combination.modifiers, Modifiers::CONTROL);
+        assert_eq!(combination.to_string(), "Ctrl+Plus");
+        assert_eq!(KeyCombination::parse("Ctrl+Plus").unwrap(), combination);
+        assert_eq!(KeyCombination::parse("Ctrl + +").unwrap(), combination);
+        assert_eq!(KeyCombination::parse("+").unwrap().key, KeyboardKey::Plus);
+        assert_eq!(
+            KeyCombination::parse("Ctrl+Shift+=").unwrap().modifiers,
+            Modifiers::CONTROL | Modifiers::SHIFT
+        );
+        assert!(KeyCombination::parse("Ctrl+").is_err());
+        assert!(KeyCombination::parse("").is_err());
+    }
+}
@@ -0,0 +1,65 @@
+use crate::nodes::data::NodeIdx;
+use crate::types::{Point, Rect};
+use rstar::primitives::{GeomWithData, Rectangle};
+use rstar::RTree;
+use std::collections::HashMap;
+
+/// Screen area of a node, with its position in rendering order
+type HitTarget = GeomWithData<Rectangle<[f32; 2]>, (usize, NodeIdx)>;
+
+/// Spatial index of the rendered nodes, used to find the node under the pointer
+#[derive(Default)]
+pub struct HitIndex {
+    tree: RTree<HitTarget>,
+    parents: HashMap<NodeIdx, NodeIdx>,
+}
+
+impl HitIndex {
+    /// Builds the index from the nodes in rendering order, so that later nodes are on top
+    ///
+    /// The screen bounds of each node should already be clipped by its ancestors.
+    pub fn new(
+        nodes: impl IntoIterator<Item = (NodeIdx, Rect)>,
+        parents: HashMap<NodeIdx, NodeIdx>,
+    ) -> Self {
+        let targets = nodes
+            .into_iter()
+            .enumerate()
+            .filter(|(_, (_, screen_bounds))| !screen_bounds.is_empty())
+            .map(|(order, (node_idx, screen_bounds))| {
+                let rectangle = Rectangle::from_corners(
+                    screen_bounds.min().to_array(),
+                    screen_bounds.max().to_array(),
+                );
+                GeomWithData::new(rectangle, (order, node_idx))
+            })
+            .collect();
+        Self {
+            tree: RTree::bulk_load(targets),
+            parents,
+        }
+    }
+
+    /// The topmost node at the position, `contains` checks the exact shape of candidate nodes
+    pub fn hit_test(
+        &self,
+        position: Point,
+        contains: impl Fn(&NodeIdx) -> bool,
+    ) -> Option<NodeIdx> {
+        self.tree
+            .locate_all_at_point(&position.to_array())
+            .map(|target| target.data)
+            .filter(|(_, node_idx)| contains(node_idx))
+            .max_by_key(|(order, _)| *order)
+            .map(|(_, node_idx)| node_idx)
+    }
+
+    pub fn parent(&self, node_idx: &NodeIdx) -> Option<NodeIdx> {
+        self.parents.get(node_idx).copied()
+    }
+
+    /// The node followed by its ancestors up to the root
+    pub fn ancestry(&self, node_idx: NodeIdx) -> impl Iterator<Item = NodeIdx> + '_ {
+        std::iter::successors(Some(node_idx), |node_idx| self.parent(node_idx))
+    }
+}
@@ -36,4 +36,10 @@ pub struct ForItem {
     pub binding_name: String,
     pub item_template: ItemIdx,
     pub items: Vec<ItemIdx>,
+    /// Index in the collection of the value each item is instantiated for
+    pub item_indices: Vec<usize>,
+    /// Items no longer needed, reused before cloning the template again
+    pub spare_items: Vec<ItemIdx>,
+    /// Node the item is a child of, which picks the values that are instantiated
+    pub owner: Option<NodeIdx>,
 }
@@ -0,0 +1,522 @@
+use crate::ast::value::ExpressionValue;
+use crate::bail;
+use crate::result::ViuiResult;
+use taffy::prelude::{
+    auto, fr, length, line, percent, repeat, span, TaffyMaxContent, TaffyMinContent,
+};
+use taffy::{
+    AlignItems, Dimension, Display, GridPlacement, GridTrackRepetition, JustifyContent,
+    LengthPercentage, LengthPercentageAuto, Line, NonRepeatedTrackSizingFunction, Rect, Size,
+    Style, TrackSizingFunction,
+};
+
+/// Layout properties that can be set on any node, applied on top of the element's own layout
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LayoutProps {
+    padding: Option<Rect<LengthPercentage>>,
+    margin: Option<Rect<LengthPercentageAuto>>,
+    gap: Option<Size<LengthPercentage>>,
+    align: Option<AlignItems>,
+    justify: Option<JustifyContent>,
+    grow: Option<f32>,
+    shrink: Option<f32>,
+    basis: Option<Dimension>,
+    min_width: Option<Dimension>,
+    max_width: Option<Dimension>,
+    min_height: Option<Dimension>,
+    max_height: Option<Dimension>,
+    grid_template_columns: Option<Vec<TrackSizingFunction>>,
+    grid_row: Option<Line<GridPlacement>>,
+    grid_column: Option<Line<GridPlacement>>,
+}
+
+impl LayoutProps {
+    pub const NAMES: &'static [&'static str] = &[
+        "padding",
+        "margin",
+        "gap",
+        "align",
+        "justify",
+        "grow",
+        "shrink",
+        "basis",
+        "min_width",
+        "max_width",
+        "min_height",
+        "max_height",
+        "grid_template_columns",
+        "grid_row",
+        "grid_column",
+    ];
+
+    pub fn is_layout_prop(name: &str) -> bool {
+        Self::NAMES.contains(&name)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Sets a layout property from its value in the UI language, validating the value
+    pub fn set(&mut self, name: &str, value: &ExpressionValue) -> ViuiResult<()> {
+        let text = match value {
+            ExpressionValue::Float(value) => value.to_string(),
+            ExpressionValue::String(value) => value.trim().to_string(),
+            _ => bail!(
+                "Invalid value '{}' for layout property '{}', expected a number or string",
+                value,
+                name
+            ),
+        };
+        let (parsed, expected) = match name {
+            "padding" => (
+                parse_sides(&text, parse_length_percentage).map(|padding| self.padding = Some(padding)),
+                "1 to 4 non-negative lengths or percentages",
+            ),
+            "margin" => (
+                parse_sides(&text, parse_length_percentage_auto)
+                    .map(|margin| self.margin = Some(margin)),
+                "1 to 4 lengths, percentages or 'auto'",
+            ),
+            "gap" => (
+                parse_gap(&text).map(|gap| self.gap = Some(gap)),
+                "1 or 2 non-negative lengths or percentages",
+            ),
+            "align" => (
+                parse_align(&text).map(|align| self.align = Some(align)),
+                "one of 'start', 'end', 'center', 'baseline' or 'stretch'",
+            ),
+            "justify" => (
+                parse_justify(&text).map(|justify| self.justify = Some(justify)),
+                "one of 'start', 'end', 'center', 'stretch', 'space-between', 'space-around' or 'space-evenly'",
+            ),
+            "grow" => (
+                parse_factor(&text).map(|grow| self.grow = Some(grow)),
+                "a non-negative number",
+            ),
+            "shrink" => (
+                parse_factor(&text).map(|shrink| self.shrink = Some(shrink)),
+                "a non-negative number",
+            ),
+            "basis" | "min_width" | "max_width" | "min_height" | "max_height" => {
+                let field = match name {
+                    "basis" => &mut self.basis,
+                    "min_width" => &mut self.min_width,
+                    "max_width" => &mut self.max_width,
+                    "min_height" => &mut self.min_height,
+                    _ => &mut self.max_height,
+                };
+                (
+                    parse_dimension(&text).map(|dimension| *field = Some(dimension)),
+                    "a non-negative length, percentage or 'auto'",
+                )
+            }
+            "grid_template_columns" => (
+                parse_track_list(&text).map(|tracks| self.grid_template_columns = Some(tracks)),
+                "a list of track sizes like '100 1fr auto' or 'repeat(3, 1fr)'",
+            ),
+            "grid_row" | "grid_column" => {
+                let field = if name == "grid_row" {
+                    &mut self.grid_row
+                } else {
+                    &mut self.grid_column
+                };
+                (
+                    parse_grid_line(&text).map(|placement| *field = Some(placement)),
+                    "a grid placement like '2', 'span 2' or '1 / 3'",
+                )
+            }
+            _ => bail!("Unknown layout property '{}'", name),
+        };
+        if parsed.is_none() {
+            bail!(
+                "Invalid value '{}' for layout property '{}', expected {}",
+                text,
+                name,
+                expected
+            );
+        }
+        Ok(())
+    }
+
+    pub fn apply_to(&self, style: &mut Style) {
+        if let Some(padding) = self.padding {
+            style.padding = padding;
+        }
+        if let Some(margin) = self.margin {
+            style.margin = margin;
+        }
+        if let Some(gap) = self.gap {
+            style.gap = gap;
+        }
+        if let Some(align) = self.align {
+            style.align_items = Some(align);
+        }
+        if let Some(justify) = self.justify {
+            style.justify_content = Some(justify);
+        }
+        if let Some(grow) = self.grow {
+            style.flex_grow = grow;
+        }
+        if let Some(shrink) = self.shrink {
+            style.flex_shrink = shrink;
+        }
+        if let Some(basis) = self.basis {
+            style.flex_basis = basis;
+        }
+        if let Some(min_width) = self.min_width {
+            style.min_size.width = min_width;
+        }
+        if let Some(max_width) = self.max_width {
+            style.max_size.width = max_width;
+        }
+        if let Some(min_height) = self.min_height {
+            style.min_size.height = min_height;
+        }
+        if let Some(max_height) = self.max_height {
+            style.max_size.height = max_height;
+        }
+        if let Some(grid_template_columns) = &self.grid_template_columns {
+            style.display = Display::Grid;
+            style.grid_template_columns = grid_template_columns.clone();
+        }
+        if let Some(grid_row) = self.grid_row {
+            style.grid_row = grid_row;
+        }
+        if let Some(grid_column) = self.grid_column {
+            style.grid_column = grid_column;
+        }
+    }
+}
+
+/// Parses a number with an optional `px` suffix
+fn parse_number(text: &str) -> Option<f32> {
+    let number = text.strip_suffix("px").unwrap_or(text);
+    number
+        .parse::<f32>()
+        .ok()
+        .filter(|number| number.is_finite())
+}
+
+fn parse_non_negative(text: &str) -> Option<f32> {
+    parse_number(text).filter(|number| *number >= 0.0)
+}
+
+fn parse_percent(text: &str) -> Option<f32> {
+    let number = text.strip_suffix('%')?.parse::<f32>().ok()?;
+    Some(number / 100.0).filter(|fraction| fraction.is_finite())
+}
+
+fn parse_length_percentage(text: &str) -> Option<LengthPercentage> {
+    match parse_percent(text) {
+        Some(fraction) => Some(percent(fraction)).filter(|_| fraction >= 0.0),
+        None => parse_non_negative(text).map(length),
+    }
+}
+
+fn parse_length_percentage_auto(text: &str) -> Option<LengthPercentageAuto> {
+    if text == "auto" {
+        return Some(auto());
+    }
+    match parse_percent(text) {
+        Some(fraction) => Some(percent(fraction)),
+        None => parse_number(text).map(length),
+    }
+}
+
+fn parse_dimension(text: &str) -> Option<Dimension> {
+    if text == "auto" {
+        return Some(auto());
+    }
+    match parse_percent(text) {
+        Some(fraction) => Some(percent(fraction)).filter(|_| fraction >= 0.0),
+        None => parse_non_negative(text).map(length),
+    }
+}
+
+fn parse_factor(text: &str) -> Option<f32> {
+    text.parse::<f32>()
+        .ok()
+        .filter(|factor| factor.is_finite() && *factor >= 0.0)
+}
+
+/// Parses 1 to 4 values for top, right, bottom and left in CSS shorthand order
+fn parse_sides<T: Copy>(text: &str, parse: fn(&str) -> Option<T>) -> Option<Rect<T>> {
+    let values = text
+        .split_whitespace()
+        .map(parse)
+        .collect::<Option<Vec<T>>>()?;
+    let (top, right, bottom, left) = match values[..] {
+        [all] => (all, all, all, all),
+        [vertical, horizontal] => (vertical, horizontal, vertical, horizontal),
+        [top, horizontal, bottom] => (top, horizontal, bottom, horizontal),
+        [top, right, bottom, left] => (top, right, bottom, left),
+        _ => return None,
+    };
+    Some(Rect {
+        left,
+        right,
+        top,
+        bottom,
+    })
+}
+
+/// Parses the row gap and optionally the column gap, as in CSS
+fn parse_gap(text: &str) -> Option<Size<LengthPercentage>> {
+    let values = text
+        .split_whitespace()
+        .map(parse_length_percentage)
+        .collect::<Option<Vec<_>>>()?;
+    match values[..] {
+        [gap] => Some(Size {
+            width: gap,
+            height: gap,
+        }),
+        [row_gap, column_gap] => Some(Size {
+            width: column_gap,
+            height: row_gap,
+        }),
+        _ => None,
+    }
+}
+
+fn parse_align(text: &str) -> Option<AlignItems> {
+    Some(match text {
+        "start" => AlignItems::Start,
+        "end" => AlignItems::End,
+        "center" => AlignItems::Center,
+        "baseline" => AlignItems::Baseline,
+        "stretch" => AlignItems::Stretch,
+        _ => return None,
+    })
+}
+
+fn parse_justify(text: &str) -> Option<JustifyContent> {
+    Some(match text {
+        "start" => JustifyContent::Start,
+        "end" => JustifyContent::End,
+        "center" => JustifyContent::Center,
+        "stretch" => JustifyContent::Stretch,
+        "space-between" => JustifyContent::SpaceBetween,
+        "space-around" => JustifyContent::SpaceAround,
+        "space-evenly" => JustifyContent::SpaceEvenly,
+        _ => return None,
+    })
+}
+
+fn parse_track(text: &str) -> Option<NonRepeatedTrackSizingFunction> {
+    match text {
+        "auto" => Some(auto()),
+        "min-content" => Some(NonRepeatedTrackSizingFunction::MIN_CONTENT),
+        "max-content" => Some(NonRepeatedTrackSizingFunction::MAX_CONTENT),
+        _ => {
+            if let Some(fraction) = text.strip_suffix("fr") {
+                return parse_factor(fraction).map(fr);
+            }
+            match parse_percent(text) {
+                Some(fraction) => Some(percent(fraction)).filter(|_| fraction >= 0.0),
+                None => parse_non_negative(text).map(length),
+            }
+        }
+    }
+}
+
+/// Splits at whitespace outside of parentheses
+fn split_tracks(text: &str) -> Option<Vec<&str>> {
+    let mut tracks = vec![];
+    let mut depth = 0;
+    let mut start = None;
+    for (index, character) in text.char_indices() {
+        match character {
+            '(' => depth += 1,
+            ')' => depth = usize::checked_sub(depth, 1)?,
+            character if character.is_whitespace() && depth == 0 => {
+                if let Some(start) = start.take() {
+                    tracks.push(&text[start..index]);
+                }
+                continue;
+            }
+            _ => {}
+        }
+        start.get_or_insert(index);
+    }
+    if depth != 0 {
+        return None;
+    }
+    if let Some(start) = start {
+        tracks.push(&text[start..]);
+    }
+    Some(tracks)
+}
+
+fn parse_track_list(text: &str) -> Option<Vec<TrackSizingFunction>> {
+    let tracks = split_tracks(text)?;
+    if tracks.is_empty() {
+        return None;
+    }
+    tracks
+        .into_iter()
+        .map(|track| {
+            let Some(arguments) = track
+                .strip_prefix("repeat(")
+                .and_then(|rest| rest.strip_suffix(')'))
+            else {
+                return parse_track(track).map(TrackSizingFunction::Single);
+            };
+            let (count, repeated) = arguments.split_once(',')?;
+            let repetition = match count.trim() {
+                "auto-fill" => GridTrackRepetition::AutoFill,
+                "auto-fit" => GridTrackRepetition::AutoFit,
+                count => GridTrackRepetition::Count(count.parse().ok().filter(|count| *count > 0)?),
+            };
+            let repeated = repeated
+                .split_whitespace()
+                .map(parse_track)
+                .collect::<Option<Vec<_>>>()?;
+            if repeated.is_empty() {
+                return None;
+            }
+            Some(repeat(repetition, repeated))
+        })
+        .collect()
+}
+
+fn parse_grid_placement(text: &str) -> Option<GridPlacement> {
+    let parts: Vec<&str> = text.split_whitespace().collect();
+    match parts[..] {
+        ["auto"] => Some(GridPlacement::Auto),
+        ["span", count] => Some(span(count.parse().ok().filter(|count| *count > 0)?)),
+        [index] => Some(line(index.parse().ok().filter(|index| *index != 0)?)),
+        _ => None,
+    }
+}
+
+/// Parses a start placement and an optional end placement separated by a slash
+fn parse_grid_line(text: &str) -> Option<Line<GridPlacement>> {
+    match text.split_once('/') {
+        Some((start, end)) => Some(Line {
+            start: parse_grid_placement(start)?,
+            end: parse_grid_placement(end)?,
+        }),
+        None => Some(Line {
+            start: parse_grid_placement(text)?,
+            end: GridPlacement::Auto,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use expect_test::{expect, Expect};
+    use std::fmt::Debug;
+
+    fn check<T: Debug>(parsed: Option<T>, expected: Expect) {
+        expected.assert_eq(&format!("{:?}", parsed));
+    }
+
+    fn check_error(name: &str, value: ExpressionValue, expected: Expect) {
+        let error = LayoutProps::default().set(name, &value).unwrap_err();
+        expected.assert_eq(&error.0.current_context().to_string());
+    }
+
+    #[test]
+    fn parse_sides_shorthands() {
+        check(parse_sides("1 2 3", parse_length_percentage), expect!["Some(Rect { left: Length(2.0), right: Length(2.0), top: Length(1.0), bottom: Length(3.0) })"]);
+        check(
+            parse_sides("auto 10% -5px", parse_length_percentage_auto),
+            expect!["Some(Rect { left: Percent(0.1), right: Percent(0.1), top: Auto, bottom: Length(-5.0) })"],
+        );
+        check(parse_sides("", parse_length_percentage), expect!["None"]);
+    }
+
+    #[test]
+    fn parse_gap_row_and_column() {
+        check(
+            parse_gap("10 5%"),
+            expect!["Some(Size { width: Percent(0.05), height: Length(10.0) })"],
+        );
+    }
+
+    #[test]
+    fn parse_track_lists() {
+        check(
+            parse_track_list("100 repeat(2, 1fr auto) min-content 25%"),
+            expect!["Some([Single(MinMax { min: Fixed(Length(100.0)), max: Fixed(Length(100.0)) }), Repeat(Count(2), [MinMax { min: Auto, max: Fraction(1.0) }, MinMax { min: Auto, max: Auto }]), Single(MinMax { min: MinContent, max: MinContent }), Single(MinMax { min: Fixed(Percent(0.25)), max: Fixed(Percent(0.25)) })])"],
+        );
+        check(parse_track_list("repeat(auto-fill, 50)"), expect!["Some([Repeat(AutoFill, [MinMax { min: Fixed(Length(50.0)), max: Fixed(Length(50.0)) }])])"]);
+        check(parse_track_list("repeat(2, 1fr"), expect!["None"]);
+    }
+
+    #[test]
+    fn parse_grid_lines() {
+        check(
+            parse_grid_line("2"),
+            expect!["Some(Line { start: Line(GridLine(2)), end: Auto })"],
+        );
+        check(
+            parse_grid_line("1 / span 2"),
+            expect!["Some(Line { start: Line(GridLine(1)), end: Span(2) })"],
+        );
+        check(
+            parse_grid_line("auto"),
+            expect!["Some(Line { start: Auto, end: Auto })"],
+        );
+    }
+
+    #[test]
+    fn apply_props_to_style() {
+        let mut layout_props = LayoutProps::default();
+        for (name, value) in [
+            ("margin", ExpressionValue::Float(5.0)),
+            ("grow", ExpressionValue::Float(1.0)),
+            ("align", ExpressionValue::String("center".to_string())),
+            (
+                "justify",
+                ExpressionValue::String("space-between".to_string()),
+            ),
+            ("min_width", ExpressionValue::String("100px".to_string())),
+            (
+                "grid_template_columns",
+                ExpressionValue::String("1fr 1fr".to_string()),
+            ),
+        ] {
+            layout_props.set(name, &value).unwrap();
+        }
+        let mut style = Style::default();
+        layout_props.apply_to(&mut style);
+        assert_eq!(style.margin, Rect::length(5.0));
+        assert_eq!(style.flex_grow, 1.0);
+        assert_eq!(style.align_items, Some(AlignItems::Center));
+        assert_eq!(style.justify_content, Some(JustifyContent::SpaceBetween));
+        assert_eq!(style.min_size.width, length(100.0));
+        assert_eq!(style.display, Display::Grid);
+        assert_eq!(style.grid_template_columns.len(), 2);
+    }
+
+    #[test]
+    fn invalid_values() {
+        check_error(
+            "padding",
+            ExpressionValue::String("1 2 3 4 5".to_string()),
+            expect!["General Error: Invalid value '1 2 3 4 5' for layout property 'padding', expected 1 to 4 non-negative lengths or percentages"],
+        );
+        check_error("padding", ExpressionValue::Float(-1.0), expect!["General Error: Invalid value '-1' for layout property 'padding', expected 1 to 4 non-negative lengths or percentages"]);
+        check_error(
+            "align",
+            ExpressionValue::String("middle".to_string()),
+            expect!["General Error: Invalid value 'middle' for layout property 'align', expected one of 'start', 'end', 'center', 'baseline' or 'stretch'"],
+        );
+        check_error("grow", ExpressionValue::Bool(true), expect!["General Error: Invalid value 'true' for layout property 'grow', expected a number or string"]);
+        check_error(
+            "grid_template_columns",
+            ExpressionValue::String("repeat(0, 1fr)".to_string()),
+            expect!["General Error: Invalid value 'repeat(0, 1fr)' for layout property 'grid_template_columns', expected a list of track sizes like '100 1fr auto' or 'repeat(3, 1fr)'"],
+        );
+        check_error(
+            "grid_column",
+            ExpressionValue::String("0 / 2".to_string()),
+            expect!["General Error: Invalid value '0 / 2' for layout property 'grid_column', expected a grid placement like '2', 'span 2' or '1 / 3'"],
+        );
+    }
+}
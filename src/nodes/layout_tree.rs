@@ -0,0 +1,258 @@
+use crate::nodes::data::NodeIdx;
+use crate::nodes::elements::kind::MeasureFn;
+use crate::result::ViuiResult;
+use crate::types::{Float, Rect, Vector};
+use std::collections::{HashMap, HashSet};
+use taffy::{AvailableSpace, NodeId, Size, Style, TaffyTree};
+
+/// Taffy tree of a backend, kept between layout passes so that only changed nodes are laid out again
+///
+/// Nodes are marked dirty when their style, children or intrinsic size change. Taffy keeps the
+/// results of clean subtrees, and positions are only updated for changed nodes and nodes that moved.
+/// The constraints of nodes are only computed again when their props or state changed.
+pub struct LayoutTree {
+    pub taffy: TaffyTree<NodeIdx>,
+    nodes: HashMap<NodeIdx, LayoutNode>,
+    constraints: HashMap<NodeIdx, CachedConstraints>,
+    /// Nodes whose constraints were computed in the current pass
+    restyled: Vec<NodeIdx>,
+    /// Nodes changed in the current pass, including their ancestors
+    changed: HashSet<NodeId>,
+    pass: u64,
+}
+
+impl Default for LayoutTree {
+    fn default() -> Self {
+        Self {
+            taffy: TaffyTree::new(),
+            nodes: HashMap::new(),
+            constraints: HashMap::new(),
+            restyled: vec![],
+            changed: HashSet::new(),
+            pass: 0,
+        }
+    }
+}
+
+/// Style and layout details derived from the constraints of a node
+pub struct NodeConstraints {
+    /// Whether the node is animated, which lays it out again in the next pass
+    pub is_animated: bool,
+    /// Style before the props and the parent of the node are applied, `None` for passthrough nodes
+    pub style: Option<Style>,
+    pub measure_fn: Option<MeasureFn>,
+    pub is_overlay: bool,
+    pub scroll_offset: Option<Vector>,
+    /// Row height of virtual lists, `Some(None)` if their rows are measured
+    pub list_row_height: Option<Option<Float>>,
+    /// Whether a layer is anchored
+    pub layer: Option<bool>,
+}
+
+struct CachedConstraints {
+    constraints: NodeConstraints,
+    /// Props and state revision the constraints were computed for
+    revisions: (u64, u64),
+    pass: u64,
+}
+
+struct LayoutNode {
+    id: NodeId,
+    style: Style,
+    children: Vec<NodeId>,
+    measure_revision: Option<u64>,
+    scroll_offset: Option<Vector>,
+    /// Window coordinates as of the last position update
+    bounds: Rect,
+    viewport: Option<Rect>,
+    pass: u64,
+}
+
+impl LayoutTree {
+    pub fn begin_pass(&mut self) {
+        self.pass += 1;
+        self.changed.clear();
+        self.restyled.clear();
+    }
+
+    /// Returns the constraints of a node, computing them again if its props or state changed
+    pub fn node_constraints(
+        &mut self,
+        node_idx: NodeIdx,
+        revisions: (u64, u64),
+        compute: impl FnOnce() -> ViuiResult<NodeConstraints>,
+    ) -> ViuiResult<&NodeConstraints> {
+        let pass = self.pass;
+        let is_clean = self
+            .constraints
+            .get(&node_idx)
+            .is_some_and(|cached| cached.revisions == revisions && !cached.constraints.is_animated);
+        if !is_clean {
+            let constraints = compute()?;
+            self.constraints.insert(
+                node_idx,
+                CachedConstraints {
+                    constraints,
+                    revisions,
+                    pass,
+                },
+            );
+            self.restyled.push(node_idx);
+        }
+        let cached = self.constraints.get_mut(&node_idx).unwrap();
+        cached.pass = pass;
+        Ok(&cached.constraints)
+    }
+
+    /// Creates or updates the layout node of a node, marking it dirty if anything changed
+    ///
+    /// `measure_revision` is the props revision of measured nodes, whose size changes with their props.
+    pub fn update_node(
+        &mut self,
+        node_idx: NodeIdx,
+        style: Style,
+        measure_revision: Option<u64>,
+        scroll_offset: Option<Vector>,
+    ) -> ViuiResult<NodeId> {
+        let pass = self.pass;
+        let Some(node) = self.nodes.get_mut(&node_idx) else {
+            let id = self.taffy.new_leaf_with_context(style.clone(), node_idx)?;
+            self.nodes.insert(
+                node_idx,
+                LayoutNode {
+                    id,
+                    style,
+                    children: vec![],
+                    measure_revision,
+                    scroll_offset,
+                    bounds: Rect::zero(),
+                    viewport: None,
+                    pass,
+                },
+            );
+            self.changed.insert(id);
+            return Ok(id);
+        };
+        node.pass = pass;
+        if node.style != style {
+            self.taffy.set_style(node.id, style.clone())?;
+            node.style = style;
+            self.changed.insert(node.id);
+        }
+        if node.measure_revision != measure_revision {
+            self.taffy.mark_dirty(node.id)?;
+            node.measure_revision = measure_revision;
+            self.changed.insert(node.id);
+        }
+        if node.scroll_offset != scroll_offset {
+            // Only moves the children, so the layout itself stays valid
+            node.scroll_offset = scroll_offset;
+            self.changed.insert(node.id);
+        }
+        Ok(node.id)
+    }
+
+    /// Applies the children collected in this pass and removes nodes that are no longer shown
+    pub fn finish_update(&mut self, children: HashMap<NodeId, Vec<NodeId>>) -> ViuiResult<()> {
+        let mut changed_children = vec![];
+        for (id, children) in children {
+            let node_idx = self.taffy.get_node_context(id).unwrap();
+            let node = self.nodes.get_mut(node_idx).unwrap();
+            if node.children != children {
+                // Detach first, so that children moved between parents keep their new parent
+                self.taffy.set_children(node.id, &[])?;
+                self.changed.insert(node.id);
+                changed_children.push((node.id, children.clone()));
+                node.children = children;
+            }
+        }
+        for (id, children) in changed_children {
+            self.taffy.set_children(id, &children)?;
+        }
+        let pass = self.pass;
+        let mut removed = vec![];
+        self.nodes.retain(|_, node| {
+            let is_shown = node.pass == pass;
+            if !is_shown {
+                removed.push(node.id);
+            }
+            is_shown
+        });
+        // Their former parents are marked as changed, as their children changed
+        for id in removed {
+            self.taffy.remove(id)?;
+        }
+        self.constraints.retain(|_, cached| cached.pass == pass);
+        // Ancestors of changed nodes need to be positioned again as well
+        for id in self.changed.clone() {
+            let mut parent = self.taffy.parent(id);
+            while let Some(id) = parent {
+                if !self.changed.insert(id) {
+                    break;
+                }
+                parent = self.taffy.parent(id);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn has_changes(&self) -> bool {
+        !self.changed.is_empty()
+    }
+
+    /// Number of layout nodes changed in the current pass
+    pub fn changed_count(&self) -> usize {
+        self.changed.len()
+    }
+
+    /// Computes the layout of the changed subtrees, calling `measure` for measured nodes
+    pub fn compute_layout(
+        &mut self,
+        root: NodeId,
+        mut measure: impl FnMut(&MeasureFn, Size<Option<f32>>, Size<AvailableSpace>) -> Size<f32>,
+    ) -> ViuiResult<()> {
+        let constraints = &self.constraints;
+        self.taffy.compute_layout_with_measure(
+            root,
+            Size::max_content(),
+            |known_dimensions, available_space, _node_id, node_idx, _style| {
+                let measure_fn = node_idx
+                    .and_then(|node_idx| constraints.get(node_idx))
+                    .and_then(|cached| cached.constraints.measure_fn.as_ref());
+                match measure_fn {
+                    Some(measure_fn) => measure(measure_fn, known_dimensions, available_space),
+                    None => Size::zero(),
+                }
+            },
+        )?;
+        Ok(())
+    }
+
+    /// Nodes whose constraints were computed in the current pass, as they changed
+    pub fn restyled_nodes(&self) -> &[NodeIdx] {
+        &self.restyled
+    }
+
+    /// Records the new position of a node, returning whether it and its subtree need to be updated
+    pub fn update_position(
+        &mut self,
+        node_idx: &NodeIdx,
+        bounds: Rect,
+        viewport: Option<Rect>,
+    ) -> bool {
+        let Some(node) = self.nodes.get_mut(node_idx) else {
+            return true;
+        };
+        if !self.changed.contains(&node.id) && node.bounds == bounds && node.viewport == viewport {
+            return false;
+        }
+        node.bounds = bounds;
+        node.viewport = viewport;
+        true
+    }
+
+    /// Lays out all nodes again in the next pass
+    pub fn invalidate(&mut self) {
+        *self = Self::default();
+    }
+}
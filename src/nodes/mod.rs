@@ -1,8 +1,12 @@
+pub mod common;
 pub mod data;
 pub mod descriptor;
 pub mod elements;
 pub mod events;
+pub mod hit_index;
 pub mod item;
+pub mod layout;
+pub mod layout_tree;
 pub mod registry;
 pub mod state;
 pub mod types;
@@ -1,14 +1,20 @@
-use crate::ast::nodes::ItemAst;
+use crate::ast::nodes::{ItemAst, NodeAst};
 use crate::err;
 use crate::infrastructure::layout_context::LayoutContext;
 use crate::nodes::data::NodeData;
-use crate::nodes::descriptor::{LayoutFn, NodeDescriptor};
-use crate::nodes::elements::kind::{Element, EventTrigger, LayoutConstraints};
+use crate::nodes::descriptor::NodeDescriptor;
+use crate::nodes::elements::component::ComponentElement;
+use crate::nodes::elements::kind::{
+    Element, EventTrigger, ForCollection, ForValue, LayoutConstraints, LayoutUpdateContext,
+    UpdateContext, ValueField,
+};
 use crate::nodes::events::InputEvent;
-use crate::nodes::types::{NodeEventHandler, NodeEvents, NodeProps, NodeRenderFn, NodeState};
+use crate::nodes::item::ItemIdx;
+use crate::nodes::types::{NodeEvents, NodeProps, PropsBox, StateBox};
 use crate::render::context::RenderContext;
 use crate::render::parameters::RenderParameters;
 use crate::result::ViuiResult;
+use crate::types::Rect;
 use std::collections::HashMap;
 
 pub struct NodeRegistry {
@@ -30,75 +36,34 @@ impl NodeRegistry {
         }
     }
 
-    #[allow(clippy::too_many_arguments)]
-    pub fn register(
-        &mut self,
-        name: impl Into<String>,
-        make_state: impl Fn() -> ViuiResult<Box<dyn NodeState>> + Send + 'static,
-        make_props: impl Fn() -> ViuiResult<Box<dyn NodeProps>> + Send + 'static,
-        event_handler: impl Fn(InputEvent, &mut NodeData, &mut EventTrigger<Box<dyn NodeEvents>>) -> ViuiResult<()>
-            + Send
-            + 'static,
-        render_fn: impl Fn(&mut RenderContext, &RenderParameters, &NodeData) -> ViuiResult<()>
-            + Send
-            + 'static,
-        layout_fn: impl Fn(&mut LayoutContext, &mut NodeData) -> ViuiResult<LayoutConstraints>
-            + Send
-            + 'static,
-        children: Vec<ItemAst>,
-    ) {
-        self.register_internal(
-            name.into(),
-            Box::new(make_state),
-            Box::new(make_props),
-            Box::new(event_handler),
-            Box::new(render_fn),
-            Box::new(layout_fn),
-            children,
-        );
-    }
-
-    #[allow(clippy::too_many_arguments)]
-    fn register_internal(
-        &mut self,
-        name: String,
-        make_state: Box<dyn Fn() -> ViuiResult<Box<dyn NodeState>> + Send>,
-        make_props: Box<dyn Fn() -> ViuiResult<Box<dyn NodeProps>> + Send>,
-        event_handler: NodeEventHandler<Box<dyn NodeEvents>>,
-        render_fn: NodeRenderFn,
-        layout_fn: LayoutFn,
-        children: Vec<ItemAst>,
-    ) {
+    pub fn register_node<T: Element>(&mut self) {
+        self.register_element::<T>(T::NAME, vec![]);
+    }
+
+    /// Registers a component declared in the UI language, whose nodes hold its children
+    pub fn register_component(&mut self, name: impl Into<String>, children: Vec<ItemAst>) {
+        self.register_element::<ComponentElement>(name, children);
+    }
+
+    fn register_element<T: Element>(&mut self, name: impl Into<String>, children: Vec<ItemAst>) {
         let kind_index = self.nodes.len();
         self.nodes.push(NodeDescriptor {
             kind_index,
-            event_handler,
-            render_fn,
-            make_state,
-            make_props,
-            layout_fn,
-            children,
-        });
-        self.node_map.insert(name, kind_index);
-    }
-
-    pub fn register_node<T: Element>(&mut self) {
-        self.register(
-            T::NAME,
-            || Ok(Box::new(T::State::default())),
-            || Ok(Box::new(T::Props::default())),
-            Box::new(
-                |event: InputEvent,
+            make_state: Box::new(|| Ok(Box::new(T::State::default()))),
+            make_props: Box::new(|| Ok(Box::new(T::Props::default()))),
+            event_handler: Box::new(
+                |event: &InputEvent,
                  node_data: &mut NodeData,
                  event_trigger: &mut EventTrigger<Box<dyn NodeEvents>>| {
+                    let layout = node_data.layout.clone();
                     let (state, props) =
                         node_data.cast_state_mut_and_props::<T::State, T::Props>()?;
                     let mut event_handler = |e: T::Events| event_trigger(Box::new(e));
-                    T::handle_event(&event, state, props, &mut event_handler);
+                    T::handle_event(event, state, props, &layout, &mut event_handler);
                     Ok(())
                 },
             ),
-            Box::new(
+            render_fn: Box::new(
                 |render_context: &mut RenderContext,
                  render_parameters: &RenderParameters,
                  node_data: &NodeData| {
@@ -107,15 +72,62 @@ impl NodeRegistry {
                     Ok(())
                 },
             ),
-            Box::new(
+            layout_fn: Box::new(
                 |layout_context: &mut LayoutContext, node_data: &mut NodeData| {
                     let (state, props) =
                         node_data.cast_state_mut_and_props::<T::State, T::Props>()?;
                     T::layout_element(layout_context, state, props)
                 },
             ),
-            vec![],
-        );
+            layout_updated_fn: Box::new(
+                |node_data: &mut NodeData, context: &mut LayoutUpdateContext| {
+                    let (state, props) =
+                        node_data.cast_state_mut_and_props::<T::State, T::Props>()?;
+                    T::layout_updated(state, props, context);
+                    Ok(())
+                },
+            ),
+            expand_template_fn: Box::new(|node: &NodeAst| T::expand_template(node)),
+            shows_children_fn: Box::new(|node_data: &NodeData| {
+                let state = node_data.state.as_any().downcast_ref::<T::State>();
+                state.is_none_or(T::shows_children)
+            }),
+            update_children_fn: Box::new(
+                |state: &mut StateBox, props: &PropsBox, context: &mut UpdateContext| {
+                    let state = state
+                        .as_any_mut()
+                        .downcast_mut::<T::State>()
+                        .ok_or_else(|| err!("Could not cast state of {}", T::NAME))?;
+                    let props = props
+                        .as_any()
+                        .downcast_ref::<T::Props>()
+                        .ok_or_else(|| err!("Could not cast props of {}", T::NAME))?;
+                    T::update_children(state, props, context)
+                },
+            ),
+            for_values_fn: Box::new(
+                |node_data: &mut NodeData, values: &dyn ForCollection, field: &ValueField| {
+                    let (state, props) =
+                        node_data.cast_state_mut_and_props::<T::State, T::Props>()?;
+                    T::for_values(state, props, values, field)
+                },
+            ),
+            descendant_event_handler: Box::new(
+                |event: &dyn NodeEvents,
+                 node_data: &mut NodeData,
+                 event_trigger: &mut EventTrigger<Box<dyn NodeEvents>>| {
+                    let (state, props) =
+                        node_data.cast_state_mut_and_props::<T::State, T::Props>()?;
+                    let mut event_handler = |e: T::Events| event_trigger(Box::new(e));
+                    T::handle_descendant_event(event, state, props, &mut event_handler);
+                    Ok(())
+                },
+            ),
+            children,
+            focusable: T::FOCUSABLE,
+            edits_text: T::EDITS_TEXT,
+        });
+        self.node_map.insert(name.into(), kind_index);
     }
 
     pub fn get_node_by_name(&self, name: &str) -> ViuiResult<&NodeDescriptor> {
@@ -136,11 +148,13 @@ impl NodeRegistry {
     pub fn handle_event(
         &self,
         node_index: usize,
-        event: InputEvent,
+        event: &InputEvent,
         node_data: &mut NodeData,
         event_trigger: &mut EventTrigger<Box<dyn NodeEvents>>,
     ) -> ViuiResult<()> {
-        (self.nodes[node_index].event_handler)(event, node_data, event_trigger)
+        node_data.track_state(|node_data| {
+            (self.nodes[node_index].event_handler)(event, node_data, event_trigger)
+        })
     }
 
     pub fn get_node_index(&self, kind: &str) -> usize {
@@ -162,4 +176,63 @@ impl NodeRegistry {
     ) -> ViuiResult<LayoutConstraints> {
         (self.nodes[node_data.kind_index()].layout_fn)(layout_context, node_data)
     }
+
+    /// Lets the node react to its newly computed layout and the window bounds of its children
+    ///
+    /// Returns whether the node requested another layout pass.
+    pub fn layout_updated(&self, node_data: &mut NodeData, children: &[Rect]) -> ViuiResult<bool> {
+        let layout_updated_fn = &self.nodes[node_data.kind_index()].layout_updated_fn;
+        let layout = node_data.layout.clone();
+        let mut context = LayoutUpdateContext::new(&layout, children);
+        node_data.track_state(|node_data| layout_updated_fn(node_data, &mut context))?;
+        if context.is_changed() {
+            node_data.state_revision += 1;
+        }
+        Ok(context.needs_layout_pass())
+    }
+
+    /// The node rewritten by its element before it is created, `None` if it is kept as written
+    pub fn expand_template(&self, node: &NodeAst) -> ViuiResult<Option<NodeAst>> {
+        (self.get_node_by_name(&node.tag)?.expand_template_fn)(node)
+    }
+
+    /// Children of the node that are shown, such as the content of the selected tab
+    pub fn shown_children<'a>(&self, node_data: &'a NodeData) -> &'a [ItemIdx] {
+        if (self.nodes[node_data.kind_index()].shows_children_fn)(node_data) {
+            &node_data.children
+        } else {
+            &[]
+        }
+    }
+
+    pub fn update_children(
+        &self,
+        node_index: usize,
+        state: &mut StateBox,
+        props: &PropsBox,
+        context: &mut UpdateContext,
+    ) -> ViuiResult<()> {
+        (self.nodes[node_index].update_children_fn)(state, props, context)
+    }
+
+    /// Values the `for` items among the children of the node are instantiated for
+    pub fn for_values(
+        &self,
+        node_data: &mut NodeData,
+        values: &dyn ForCollection,
+        field: &ValueField,
+    ) -> ViuiResult<Vec<ForValue>> {
+        let for_values_fn = &self.nodes[node_data.kind_index()].for_values_fn;
+        node_data.track_state(|node_data| for_values_fn(node_data, values, field))
+    }
+
+    pub fn handle_descendant_event(
+        &self,
+        event: &dyn NodeEvents,
+        node_data: &mut NodeData,
+        event_trigger: &mut EventTrigger<Box<dyn NodeEvents>>,
+    ) -> ViuiResult<()> {
+        let descendant_event_handler = &self.nodes[node_data.kind_index()].descendant_event_handler;
+        node_data.track_state(|node_data| descendant_event_handler(event, node_data, event_trigger))
+    }
 }
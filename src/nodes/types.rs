@@ -16,7 +16,7 @@ pub type PropsBox = Box<dyn NodeProps>;
 
 pub trait NodeEvents: Enum + Reflect + Debug + 'static {}
 pub type NodeEventHandler<E> =
-    Box<dyn Fn(InputEvent, &mut NodeData, &mut EventTrigger<'_, E>) -> ViuiResult<()> + Send>;
+    Box<dyn Fn(&InputEvent, &mut NodeData, &mut EventTrigger<'_, E>) -> ViuiResult<()> + Send>;
 pub type NodeRenderFn =
     Box<dyn Fn(&mut RenderContext, &RenderParameters, &NodeData) -> ViuiResult<()> + Send>;
 
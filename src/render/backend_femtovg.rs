@@ -1,12 +1,18 @@
+use crate::bail;
 use crate::infrastructure::font_pool::FontIndex;
-use crate::nodes::events::{KeyboardKey, MouseEventKind, UiEvent};
+use crate::nodes::events::{KeyEventKind, KeyboardKey, Modifiers, MouseEventKind, UiEvent};
 use crate::render::backend::RenderBackendParameters;
-use crate::render::command::{ImageId, RenderCommand};
+use crate::render::command::{
+    DrawMode, Gradient, GradientStop, ImageId, LineCap, LineJoin, RenderCommand,
+};
+use crate::render::path::{dash_polylines, PathData, PathSegment};
 use crate::result::ViuiResult;
-use crate::types::{Color, Float, Point, Size};
+use crate::types::{Color, Float, Point, Size, Vector};
 use crate::ui::RenderBackendMessage;
 use femtovg::renderer::OpenGl;
-use femtovg::{Baseline, Canvas, FontId, ImageFlags, Paint, Path, Solidity};
+use femtovg::{
+    Baseline, Canvas, FontId, ImageFlags, Paint, Path, PixelFormat, RenderTarget, Solidity,
+};
 use glutin::config::ConfigTemplateBuilder;
 use glutin::context::{
     ContextAttributesBuilder, NotCurrentGlContextSurfaceAccessor, PossiblyCurrentContext,
@@ -17,15 +23,20 @@ use glutin::surface::{Surface, SurfaceAttributesBuilder, WindowSurface};
 use glutin_winit::DisplayBuilder;
 use log::info;
 use raw_window_handle::HasRawWindowHandle;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::num::NonZeroU32;
 use std::thread;
 use tracing::error;
 use winit::dpi::PhysicalSize;
-use winit::event::{ElementState, Event, MouseButton, VirtualKeyCode, WindowEvent};
+use winit::event::{
+    ElementState, Event, ModifiersState, MouseButton, MouseScrollDelta, VirtualKeyCode, WindowEvent,
+};
 use winit::event_loop::{ControlFlow, EventLoop, EventLoopBuilder};
 use winit::window::{Window, WindowBuilder};
 
+/// Pixels scrolled per line of mouse wheel movement
+const SCROLL_LINE_HEIGHT: Float = 40.0;
+
 pub struct FemtovgRenderBackend {
     pub render_backend_parameters: RenderBackendParameters,
 }
@@ -37,6 +48,8 @@ pub struct RenderState<'a> {
     canvas: &'a mut Canvas<OpenGl>,
     image_map: &'a mut HashMap<ImageId, femtovg::ImageId>,
     font_map: &'a mut HashMap<FontIndex, FontId>,
+    /// Offscreen image holding the current frame, so that frames can be updated partially
+    frame_image: &'a mut Option<femtovg::ImageId>,
 }
 
 impl FemtovgRenderBackend {
@@ -75,9 +88,11 @@ impl FemtovgRenderBackend {
                 .expect("Cannot create renderer");
 
         let mut canvas = Canvas::new(renderer).expect("Cannot create canvas");
-        let mut render_list: Vec<RenderCommand> = Vec::new();
         let mut image_map = Default::default();
         let mut font_map = Default::default();
+        let mut frame_image = None;
+        let mut modifiers = ModifiersState::empty();
+        let mut pressed_keys = HashSet::new();
         event_loop.run(move |event, _target, control_flow| {
             let mut render_state = RenderState {
                 context: &context,
@@ -86,6 +101,7 @@ impl FemtovgRenderBackend {
                 canvas: &mut canvas,
                 image_map: &mut image_map,
                 font_map: &mut font_map,
+                frame_image: &mut frame_image,
             };
             *control_flow = ControlFlow::Wait;
             let event_sender = &self.render_backend_parameters.event_sender;
@@ -95,7 +111,10 @@ impl FemtovgRenderBackend {
                         let mouse_position = Point::new(position.x as f32, position.y as f32);
 
                         event_sender
-                            .send(UiEvent::mouse_move(mouse_position))
+                            .send(
+                                UiEvent::mouse_move(mouse_position)
+                                    .with_modifiers(map_modifiers(modifiers)),
+                            )
                             .unwrap()
                     }
                     WindowEvent::MouseInput {
@@ -103,34 +122,54 @@ impl FemtovgRenderBackend {
                         button: MouseButton::Left,
                         ..
                     } => event_sender
-                        .send(UiEvent::mouse_input(if state == ElementState::Pressed {
-                            MouseEventKind::Pressed
-                        } else {
-                            MouseEventKind::Released
-                        }))
+                        .send(
+                            UiEvent::mouse_input(if state == ElementState::Pressed {
+                                MouseEventKind::Pressed
+                            } else {
+                                MouseEventKind::Released
+                            })
+                            .with_modifiers(map_modifiers(modifiers)),
+                        )
                         .unwrap(),
+                    WindowEvent::MouseWheel { delta, .. } => {
+                        // Wheel deltas point towards the content to reveal
+                        let delta = match delta {
+                            MouseScrollDelta::LineDelta(x, y) => {
+                                Vector::new(-x, -y) * SCROLL_LINE_HEIGHT
+                            }
+                            MouseScrollDelta::PixelDelta(position) => {
+                                Vector::new(-position.x as Float, -position.y as Float)
+                            }
+                        };
+                        event_sender
+                            .send(
+                                UiEvent::mouse_wheel(delta)
+                                    .with_modifiers(map_modifiers(modifiers)),
+                            )
+                            .unwrap()
+                    }
                     WindowEvent::CloseRequested => *control_flow = ControlFlow::Exit,
+                    WindowEvent::ModifiersChanged(state) => modifiers = state,
                     WindowEvent::ReceivedCharacter(character) => event_sender
                         .send(UiEvent::character_input(character))
                         .unwrap(),
                     WindowEvent::KeyboardInput { input, .. } => {
-                        if input.state == ElementState::Pressed {
-                            let key = match input.virtual_keycode {
-                                Some(VirtualKeyCode::Left) => Some(KeyboardKey::ArrowLeft),
-                                Some(VirtualKeyCode::Right) => Some(KeyboardKey::ArrowRight),
-                                Some(VirtualKeyCode::End) => Some(KeyboardKey::End),
-                                Some(VirtualKeyCode::Home) => Some(KeyboardKey::Home),
-                                Some(VirtualKeyCode::Return) => Some(KeyboardKey::Enter),
-                                Some(VirtualKeyCode::Escape) => Some(KeyboardKey::Escape),
-                                Some(VirtualKeyCode::Tab) => Some(KeyboardKey::Tab),
-                                Some(VirtualKeyCode::Delete) => Some(KeyboardKey::Delete),
-                                Some(VirtualKeyCode::Back) => Some(KeyboardKey::Backspace),
-                                _ => None,
-                            };
-                            if let Some(key) = key {
-                                event_sender.send(UiEvent::key_input(key)).unwrap();
-                            }
-                        }
+                        let Some(key) = input.virtual_keycode.and_then(map_key) else {
+                            return;
+                        };
+                        let (key_event_kind, repeat) = if input.state == ElementState::Pressed {
+                            // Held keys are pressed again without being released
+                            (KeyEventKind::Pressed, !pressed_keys.insert(key))
+                        } else {
+                            pressed_keys.remove(&key);
+                            (KeyEventKind::Released, false)
+                        };
+                        event_sender
+                            .send(
+                                UiEvent::key_event(key, key_event_kind, repeat)
+                                    .with_modifiers(map_modifiers(modifiers)),
+                            )
+                            .unwrap();
                     }
                     WindowEvent::Resized(size) => {
                         event_sender
@@ -143,12 +182,10 @@ impl FemtovgRenderBackend {
                     _ => {}
                 },
                 Event::RedrawRequested(_) => {
-                    render(&mut render_state, &render_list).unwrap();
+                    present_frame(&mut render_state).unwrap();
                 }
                 Event::UserEvent(message) => {
-                    render_list = message.render_commands;
-                    render(&mut render_state, &render_list).unwrap();
-                    //window.request_redraw();
+                    render(&mut render_state, &message.render_commands).unwrap();
                 }
                 _ => {}
             }
@@ -156,6 +193,97 @@ impl FemtovgRenderBackend {
     }
 }
 
+fn map_modifiers(modifiers: ModifiersState) -> Modifiers {
+    Modifiers {
+        shift: modifiers.shift(),
+        control: modifiers.ctrl(),
+        alt: modifiers.alt(),
+        logo: modifiers.logo(),
+    }
+}
+
+fn map_key(key: VirtualKeyCode) -> Option<KeyboardKey> {
+    use VirtualKeyCode as Code;
+    Some(match key {
+        Code::Left => KeyboardKey::ArrowLeft,
+        Code::Right => KeyboardKey::ArrowRight,
+        Code::Up => KeyboardKey::ArrowUp,
+        Code::Down => KeyboardKey::ArrowDown,
+        Code::Home => KeyboardKey::Home,
+        Code::End => KeyboardKey::End,
+        Code::PageUp => KeyboardKey::PageUp,
+        Code::PageDown => KeyboardKey::PageDown,
+        Code::Insert => KeyboardKey::Insert,
+        Code::Delete => KeyboardKey::Delete,
+        Code::Back => KeyboardKey::Backspace,
+        Code::Return | Code::NumpadEnter => KeyboardKey::Enter,
+        Code::Escape => KeyboardKey::Escape,
+        Code::Tab => KeyboardKey::Tab,
+        Code::Space => KeyboardKey::Space,
+        Code::A => KeyboardKey::A,
+        Code::B => KeyboardKey::B,
+        Code::C => KeyboardKey::C,
+        Code::D => KeyboardKey::D,
+        Code::E => KeyboardKey::E,
+        Code::F => KeyboardKey::F,
+        Code::G => KeyboardKey::G,
+        Code::H => KeyboardKey::H,
+        Code::I => KeyboardKey::I,
+        Code::J => KeyboardKey::J,
+        Code::K => KeyboardKey::K,
+        Code::L => KeyboardKey::L,
+        Code::M => KeyboardKey::M,
+        Code::N => KeyboardKey::N,
+        Code::O => KeyboardKey::O,
+        Code::P => KeyboardKey::P,
+        Code::Q => KeyboardKey::Q,
+        Code::R => KeyboardKey::R,
+        Code::S => KeyboardKey::S,
+        Code::T => KeyboardKey::T,
+        Code::U => KeyboardKey::U,
+        Code::V => KeyboardKey::V,
+        Code::W => KeyboardKey::W,
+        Code::X => KeyboardKey::X,
+        Code::Y => KeyboardKey::Y,
+        Code::Z => KeyboardKey::Z,
+        Code::Key0 | Code::Numpad0 => KeyboardKey::Digit0,
+        Code::Key1 | Code::Numpad1 => KeyboardKey::Digit1,
+        Code::Key2 | Code::Numpad2 => KeyboardKey::Digit2,
+        Code::Key3 | Code::Numpad3 => KeyboardKey::Digit3,
+        Code::Key4 | Code::Numpad4 => KeyboardKey::Digit4,
+        Code::Key5 | Code::Numpad5 => KeyboardKey::Digit5,
+        Code::Key6 | Code::Numpad6 => KeyboardKey::Digit6,
+        Code::Key7 | Code::Numpad7 => KeyboardKey::Digit7,
+        Code::Key8 | Code::Numpad8 => KeyboardKey::Digit8,
+        Code::Key9 | Code::Numpad9 => KeyboardKey::Digit9,
+        Code::Minus | Code::NumpadSubtract => KeyboardKey::Minus,
+        Code::Equals => KeyboardKey::Equals,
+        Code::Plus | Code::NumpadAdd => KeyboardKey::Plus,
+        Code::Comma => KeyboardKey::Comma,
+        Code::Period => KeyboardKey::Period,
+        Code::Slash => KeyboardKey::Slash,
+        Code::Backslash => KeyboardKey::Backslash,
+        Code::Semicolon => KeyboardKey::Semicolon,
+        Code::Apostrophe => KeyboardKey::Apostrophe,
+        Code::LBracket => KeyboardKey::BracketLeft,
+        Code::RBracket => KeyboardKey::BracketRight,
+        Code::Grave => KeyboardKey::Grave,
+        Code::F1 => KeyboardKey::F1,
+        Code::F2 => KeyboardKey::F2,
+        Code::F3 => KeyboardKey::F3,
+        Code::F4 => KeyboardKey::F4,
+        Code::F5 => KeyboardKey::F5,
+        Code::F6 => KeyboardKey::F6,
+        Code::F7 => KeyboardKey::F7,
+        Code::F8 => KeyboardKey::F8,
+        Code::F9 => KeyboardKey::F9,
+        Code::F10 => KeyboardKey::F10,
+        Code::F11 => KeyboardKey::F11,
+        Code::F12 => KeyboardKey::F12,
+        _ => return None,
+    })
+}
+
 fn create_window(
     event_loop: &EventLoop<RenderBackendMessage>,
     initial_window_size: Size,
@@ -224,35 +352,43 @@ fn create_window(
 
 fn render(render_state: &mut RenderState, render_commands: &[RenderCommand]) -> ViuiResult<()> {
     let RenderState {
-        window: _window,
         canvas,
         surface,
         context,
         image_map,
         font_map,
+        frame_image,
+        ..
     } = render_state;
+    // Commands only update the damaged parts of the previous frame
+    let mut frame = ensure_frame_image(canvas, frame_image)?;
+    canvas.set_render_target(RenderTarget::Image(frame));
     canvas.reset_transform();
-    // Compensate for height difference to reduce flickering/jumping during window resize
-    let height_delta = _window.inner_size().height as i32 - canvas.height() as i32;
-    canvas.translate(0.0, -height_delta as Float);
 
     //
-    let mut fill_paint = Paint::color(femtovg::Color::hsl(0.0, 0.0, 1.0))
-        .with_text_baseline(Baseline::Middle)
-        .with_font_size(20.0)
-        .with_anti_alias(true);
-    let mut stroke_paint = Paint::color(femtovg::Color::hsl(0.0, 0.0, 0.0))
-        .with_text_baseline(Baseline::Middle)
-        .with_font_size(25.0)
-        .with_line_width(0.5)
-        .with_anti_alias(true);
+    let mut paint_state = PaintState {
+        fill_paint: Paint::color(femtovg::Color::hsl(0.0, 0.0, 1.0))
+            .with_text_baseline(Baseline::Middle)
+            .with_font_size(20.0)
+            .with_anti_alias(true),
+        stroke_paint: Paint::color(femtovg::Color::hsl(0.0, 0.0, 0.0))
+            .with_text_baseline(Baseline::Middle)
+            .with_font_size(25.0)
+            .with_line_width(0.5)
+            .with_anti_alias(true),
+        line_dash: vec![],
+        line_dash_offset: 0.0,
+    };
+    let mut paint_state_stack = vec![];
+    let mut layer_stack: Vec<Layer> = vec![];
+    let mut images_to_delete = vec![];
     for command in render_commands {
         match command {
             RenderCommand::FillRect { rect } => {
                 let mut path = Path::new();
                 path.rect(rect.min_x(), rect.min_y(), rect.width(), rect.height());
 
-                canvas.fill_path(&path, &fill_paint);
+                canvas.fill_path(&path, &paint_state.fill_paint);
             }
             RenderCommand::FillRoundRect { rect, radius } => {
                 let mut path = Path::new();
@@ -264,38 +400,211 @@ fn render(render_state: &mut RenderState, render_commands: &[RenderCommand]) ->
                     *radius,
                 );
 
-                canvas.fill_path(&path, &fill_paint);
-                canvas.stroke_path(&path, &stroke_paint);
+                canvas.fill_path(&path, &paint_state.fill_paint);
+                canvas.stroke_path(&path, &paint_state.stroke_paint);
             }
             RenderCommand::Translate { x, y } => {
                 canvas.translate(*x, *y);
             }
-            /*            RenderCommand::ResetTransform => {
+            RenderCommand::Rotate { angle } => {
+                canvas.rotate(*angle);
+            }
+            RenderCommand::Scale { x, y } => {
+                canvas.scale(*x, *y);
+            }
+            RenderCommand::Transform(transform) => {
+                let [a, b, c, d, e, f] = transform.to_array();
+                canvas.set_transform(&femtovg::Transform2D::new(a, b, c, d, e, f));
+            }
+            RenderCommand::ResetTransform => {
                 canvas.reset_transform();
-            }*/
+            }
             RenderCommand::DrawText(text) => {
-                stroke_paint.set_line_width(1.0);
-                stroke_paint.set_anti_alias(true);
-                stroke_paint.set_text_baseline(Baseline::Bottom);
-                canvas.fill_text(0.0, 10.0, text, &stroke_paint)?;
+                // Use a copy, so text settings do not leak into subsequent strokes
+                let mut text_paint = paint_state.stroke_paint.clone();
+                text_paint.set_line_width(1.0);
+                text_paint.set_anti_alias(true);
+                text_paint.set_text_baseline(Baseline::Bottom);
+                canvas.fill_text(0.0, 10.0, text, &text_paint)?;
             }
             RenderCommand::Save => {
                 canvas.save();
+                paint_state_stack.push(paint_state.clone());
             }
             RenderCommand::Restore => {
                 canvas.restore();
+                if let Some(saved_paint_state) = paint_state_stack.pop() {
+                    paint_state = saved_paint_state;
+                }
             }
             RenderCommand::SetFillColor(color) => {
-                fill_paint.set_color(color.into());
+                paint_state.fill_paint.set_color(color.into());
+            }
+            RenderCommand::SetFillGradient(gradient) => {
+                fn to_femtovg_stops(
+                    stops: &[GradientStop],
+                ) -> impl Iterator<Item = (f32, femtovg::Color)> + '_ {
+                    stops.iter().map(|stop| (stop.offset, (&stop.color).into()))
+                }
+                let paint = match gradient {
+                    Gradient::Linear { start, end, stops } => Paint::linear_gradient_stops(
+                        start.x,
+                        start.y,
+                        end.x,
+                        end.y,
+                        to_femtovg_stops(stops),
+                    ),
+                    Gradient::Radial {
+                        center,
+                        inner_radius,
+                        outer_radius,
+                        stops,
+                    } => Paint::radial_gradient_stops(
+                        center.x,
+                        center.y,
+                        *inner_radius,
+                        *outer_radius,
+                        to_femtovg_stops(stops),
+                    ),
+                };
+                paint_state.fill_paint = paint.with_anti_alias(true);
+            }
+            RenderCommand::DrawBoxShadow {
+                rect,
+                radius,
+                offset,
+                blur,
+                color,
+            } => {
+                let shadow_rect = rect.translate(*offset);
+                let paint = Paint::box_gradient(
+                    shadow_rect.min_x(),
+                    shadow_rect.min_y(),
+                    shadow_rect.width(),
+                    shadow_rect.height(),
+                    *radius,
+                    blur.max(0.5),
+                    color.into(),
+                    femtovg::Color::rgba(0, 0, 0, 0),
+                );
+                let mut path = Path::new();
+                path.rect(
+                    shadow_rect.min_x() - blur,
+                    shadow_rect.min_y() - blur,
+                    shadow_rect.width() + blur * 2.0,
+                    shadow_rect.height() + blur * 2.0,
+                );
+                canvas.fill_path(&path, &paint);
+            }
+            RenderCommand::PushLayer { opacity } => {
+                let (width, height) = (canvas.width(), canvas.height());
+                let image = canvas.create_image_empty(
+                    width as usize,
+                    height as usize,
+                    PixelFormat::Rgba8,
+                    ImageFlags::FLIP_Y | ImageFlags::PREMULTIPLIED,
+                )?;
+                canvas.set_render_target(RenderTarget::Image(image));
+                canvas.clear_rect(0, 0, width, height, femtovg::Color::rgba(0, 0, 0, 0));
+                canvas.save();
+                paint_state_stack.push(paint_state.clone());
+                layer_stack.push(Layer {
+                    image,
+                    opacity: *opacity,
+                });
+            }
+            RenderCommand::PopLayer => {
+                let Some(layer) = layer_stack.pop() else {
+                    bail!("PopLayer without matching PushLayer");
+                };
+                canvas.restore();
+                if let Some(saved_paint_state) = paint_state_stack.pop() {
+                    paint_state = saved_paint_state;
+                }
+                canvas.set_render_target(
+                    layer_stack
+                        .last()
+                        .map(|parent| RenderTarget::Image(parent.image))
+                        .unwrap_or(RenderTarget::Image(frame)),
+                );
+                composite_layer(canvas, &layer);
+                // The image is still referenced by queued draw commands until the canvas is flushed
+                images_to_delete.push(layer.image);
             }
             RenderCommand::SetStrokeColor(color) => {
-                stroke_paint.set_color(color.into());
+                paint_state.stroke_paint.set_color(color.into());
+            }
+            RenderCommand::SetLineCap(line_cap) => {
+                paint_state.stroke_paint.set_line_cap(match line_cap {
+                    LineCap::Butt => femtovg::LineCap::Butt,
+                    LineCap::Round => femtovg::LineCap::Round,
+                    LineCap::Square => femtovg::LineCap::Square,
+                });
+            }
+            RenderCommand::SetLineJoin(line_join) => {
+                paint_state.stroke_paint.set_line_join(match line_join {
+                    LineJoin::Miter => femtovg::LineJoin::Miter,
+                    LineJoin::Round => femtovg::LineJoin::Round,
+                    LineJoin::Bevel => femtovg::LineJoin::Bevel,
+                });
+            }
+            RenderCommand::SetLineDash { pattern, offset } => {
+                paint_state.line_dash = pattern.clone();
+                paint_state.line_dash_offset = *offset;
+            }
+            RenderCommand::DrawPath { path, mode } => {
+                draw_path_data(canvas, &paint_state, path, *mode);
+            }
+            RenderCommand::DrawRect { rect, mode } => {
+                draw_path_data(canvas, &paint_state, &PathData::rect(rect), *mode);
+            }
+            RenderCommand::DrawRoundRect { rect, radius, mode } => {
+                draw_path_data(
+                    canvas,
+                    &paint_state,
+                    &PathData::round_rect(rect, *radius),
+                    *mode,
+                );
+            }
+            RenderCommand::DrawCircle {
+                center,
+                radius,
+                mode,
+            } => {
+                draw_path_data(
+                    canvas,
+                    &paint_state,
+                    &PathData::circle(*center, *radius),
+                    *mode,
+                );
+            }
+            RenderCommand::DrawEllipse {
+                center,
+                radius_x,
+                radius_y,
+                mode,
+            } => {
+                draw_path_data(
+                    canvas,
+                    &paint_state,
+                    &PathData::ellipse(*center, *radius_x, *radius_y),
+                    *mode,
+                );
             }
             RenderCommand::Line { start, end } => {
-                let mut path = Path::new();
-                path.move_to(start.x, start.y);
-                path.line_to(end.x, end.y);
-                canvas.stroke_path(&path, &stroke_paint);
+                stroke_path_data(canvas, &paint_state, &PathData::line(*start, *end));
+            }
+            RenderCommand::Arc {
+                center,
+                radius,
+                start_angle,
+                end_angle,
+            } if !paint_state.line_dash.is_empty() => {
+                stroke_path_data(
+                    canvas,
+                    &paint_state,
+                    &PathData::arc(*center, *radius, *start_angle, *end_angle),
+                );
             }
             RenderCommand::Arc {
                 center,
@@ -312,13 +621,13 @@ fn render(render_state: &mut RenderState, render_commands: &[RenderCommand]) ->
                     *end_angle,
                     Solidity::Hole,
                 );
-                canvas.stroke_path(&path, &stroke_paint);
+                canvas.stroke_path(&path, &paint_state.stroke_paint);
             }
             RenderCommand::SetStrokeWidth(width) => {
-                stroke_paint.set_line_width(*width);
+                paint_state.stroke_paint.set_line_width(*width);
             }
             RenderCommand::ClipRect(clip_rect) => {
-                canvas.scissor(
+                canvas.intersect_scissor(
                     clip_rect.origin.x,
                     clip_rect.origin.y,
                     clip_rect.size.width,
@@ -347,20 +656,92 @@ fn render(render_state: &mut RenderState, render_commands: &[RenderCommand]) ->
             }
             RenderCommand::SetFont { font_idx } => {
                 let femto_id = font_map[font_idx];
-                stroke_paint.set_font(&[femto_id]);
+                paint_state.stroke_paint.set_font(&[femto_id]);
             }
             RenderCommand::SetWindowSize { size } => {
                 surface.resize(
                     context,
                     NonZeroU32::try_from(size.width as u32).unwrap(),
-                    NonZeroU32::try_from(size.width as u32).unwrap(),
+                    NonZeroU32::try_from(size.height as u32).unwrap(),
                 );
                 canvas.set_size(size.width as u32, size.height as u32, 1.0);
+                frame = ensure_frame_image(canvas, frame_image)?;
+                canvas.set_render_target(RenderTarget::Image(frame));
             }
         }
     }
 
-    // Tell renderer to execute all drawing commands*/
+    // Composite layers that were not explicitly popped
+    while let Some(layer) = layer_stack.pop() {
+        canvas.set_render_target(
+            layer_stack
+                .last()
+                .map(|parent| RenderTarget::Image(parent.image))
+                .unwrap_or(RenderTarget::Image(frame)),
+        );
+        composite_layer(canvas, &layer);
+        images_to_delete.push(layer.image);
+    }
+
+    // Execute drawing commands before the layer images are gone
+    canvas.flush();
+    for image in images_to_delete {
+        canvas.delete_image(image);
+    }
+    present_frame(render_state)
+}
+
+/// Returns the frame image, (re)creating it if missing or not matching the canvas size
+fn ensure_frame_image(
+    canvas: &mut Canvas<OpenGl>,
+    frame_image: &mut Option<femtovg::ImageId>,
+) -> ViuiResult<femtovg::ImageId> {
+    let size = (canvas.width() as usize, canvas.height() as usize);
+    if let Some(image) = frame_image {
+        if canvas.image_size(*image)? == size {
+            return Ok(*image);
+        }
+        canvas.delete_image(*image);
+    }
+    let image = canvas.create_image_empty(
+        size.0,
+        size.1,
+        PixelFormat::Rgba8,
+        ImageFlags::FLIP_Y | ImageFlags::PREMULTIPLIED,
+    )?;
+    canvas.set_render_target(RenderTarget::Image(image));
+    canvas.clear_rect(0, 0, size.0 as u32, size.1 as u32, femtovg::Color::black());
+    *frame_image = Some(image);
+    Ok(image)
+}
+
+/// Copies the frame image to the window
+fn present_frame(render_state: &mut RenderState) -> ViuiResult<()> {
+    let RenderState {
+        window,
+        canvas,
+        surface,
+        context,
+        frame_image,
+        ..
+    } = render_state;
+    let Some(frame) = **frame_image else {
+        return Ok(());
+    };
+    canvas.set_render_target(RenderTarget::Screen);
+    canvas.reset_transform();
+    canvas.reset_scissor();
+    // Compensate for height difference to reduce flickering/jumping during window resize
+    let height_delta = window.inner_size().height as i32 - canvas.height() as i32;
+    canvas.translate(0.0, -height_delta as Float);
+    let (width, height) = (canvas.width() as Float, canvas.height() as Float);
+    let mut path = Path::new();
+    path.rect(0.0, 0.0, width, height);
+    canvas.fill_path(
+        &path,
+        &Paint::image(frame, 0.0, 0.0, width, height, 0.0, 1.0),
+    );
+    // Tell renderer to execute all drawing commands
     canvas.flush();
     // Display what we've just rendered
     surface
@@ -369,6 +750,84 @@ fn render(render_state: &mut RenderState, render_commands: &[RenderCommand]) ->
     Ok(())
 }
 
+struct Layer {
+    image: femtovg::ImageId,
+    opacity: Float,
+}
+
+/// Draws the offscreen layer image onto the current render target in device coordinates
+fn composite_layer(canvas: &mut Canvas<OpenGl>, layer: &Layer) {
+    let (width, height) = (canvas.width() as Float, canvas.height() as Float);
+    canvas.save();
+    canvas.reset_transform();
+    canvas.reset_scissor();
+    let paint = Paint::image(layer.image, 0.0, 0.0, width, height, 0.0, layer.opacity);
+    let mut path = Path::new();
+    path.rect(0.0, 0.0, width, height);
+    canvas.fill_path(&path, &paint);
+    canvas.restore();
+}
+
+#[derive(Clone)]
+struct PaintState {
+    fill_paint: Paint,
+    stroke_paint: Paint,
+    line_dash: Vec<Float>,
+    line_dash_offset: Float,
+}
+
+/// Maximum distance between a curve and its straight line approximation when dashing
+const DASH_FLATTEN_TOLERANCE: Float = 0.25;
+
+fn to_femtovg_path(path_data: &PathData) -> Path {
+    let mut path = Path::new();
+    for segment in path_data.segments() {
+        match segment {
+            PathSegment::MoveTo(point) => path.move_to(point.x, point.y),
+            PathSegment::LineTo(point) => path.line_to(point.x, point.y),
+            PathSegment::QuadTo { control, end } => {
+                path.quad_to(control.x, control.y, end.x, end.y)
+            }
+            PathSegment::CubicTo {
+                control1,
+                control2,
+                end,
+            } => path.bezier_to(control1.x, control1.y, control2.x, control2.y, end.x, end.y),
+            PathSegment::Close => path.close(),
+        }
+    }
+    path
+}
+
+fn draw_path_data(
+    canvas: &mut Canvas<OpenGl>,
+    paint_state: &PaintState,
+    path_data: &PathData,
+    mode: DrawMode,
+) {
+    if mode.fills() {
+        canvas.fill_path(&to_femtovg_path(path_data), &paint_state.fill_paint);
+    }
+    if mode.strokes() {
+        stroke_path_data(canvas, paint_state, path_data);
+    }
+}
+
+fn stroke_path_data(canvas: &mut Canvas<OpenGl>, paint_state: &PaintState, path_data: &PathData) {
+    if paint_state.line_dash.is_empty() {
+        canvas.stroke_path(&to_femtovg_path(path_data), &paint_state.stroke_paint);
+        return;
+    }
+    // femtovg has no native dash support, so split the flattened path into dashes ourselves
+    let dashes = dash_polylines(
+        &path_data.flatten(DASH_FLATTEN_TOLERANCE),
+        &paint_state.line_dash,
+        paint_state.line_dash_offset,
+    );
+    let dashed_path: PathData = dashes.into_iter().map(PathData::from).collect();
+    canvas.stroke_path(&to_femtovg_path(&dashed_path), &paint_state.stroke_paint);
+}
+
 impl From<&Color> for femtovg::Color {
     fn from(color: &Color) -> Self {
         let rgba = color.rgba;
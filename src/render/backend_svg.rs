@@ -1,12 +1,17 @@
 use crate::bail;
-use crate::render::command::RenderCommand;
+use crate::render::command::{DrawMode, Gradient, GradientStop, LineCap, LineJoin, RenderCommand};
+use crate::render::transform::TransformState;
 use crate::result::ViuiResult;
-use crate::types::Size;
+use crate::types::{Color, Size, Transform};
+use itertools::Itertools;
 use pathdiff::diff_paths;
 use std::collections::HashMap;
 use std::io::Write;
 use std::path::Path;
-use svg::node::element::{Group, Style};
+use svg::node::element::{
+    ClipPath, Definitions, Filter, FilterEffectGaussianBlur, Group, LinearGradient, RadialGradient,
+    Stop, Style,
+};
 use svg::{Document, Node};
 
 pub fn render_svg(
@@ -66,8 +71,28 @@ pub fn render_svg_document(
             .children
             .push(Box::new(group));
     }
+    fn apply_draw_mode(element: &mut impl Node, mode: &DrawMode) {
+        match mode {
+            DrawMode::Fill => element.assign("stroke", "none"),
+            DrawMode::Stroke => element.assign("fill", "none"),
+            DrawMode::FillAndStroke => {}
+        }
+    }
+    fn append_stops(gradient: &mut impl Node, stops: &[GradientStop]) {
+        for stop in stops {
+            gradient.append(
+                Stop::new()
+                    .set("offset", stop.offset)
+                    .set("stop-color", svg_color(&stop.color)),
+            );
+        }
+    }
     let mut image_map = HashMap::new();
     let mut style_content = String::new();
+    let mut definitions = Definitions::new();
+    let mut definition_count = 0;
+    let mut layer_stack = vec![];
+    let mut transform_state = TransformState::new();
     for render_command in render_list {
         match render_command {
             RenderCommand::Line { start, end } => {
@@ -84,19 +109,168 @@ pub fn render_svg_document(
                 push_group(Group::new().set("stroke-width", *width), entry_stack);
             }
             RenderCommand::SetStrokeColor(color) => {
-                let rgba = color.rgba;
-                push_group(
-                    Group::new().set("stroke", format!("rgb({} {} {})", rgba.r, rgba.g, rgba.b)),
+                push_group(Group::new().set("stroke", svg_color(color)), entry_stack);
+            }
+            RenderCommand::SetFillColor(color) => {
+                push_group(Group::new().set("fill", svg_color(color)), entry_stack);
+            }
+            RenderCommand::SetFillGradient(gradient) => {
+                let id = format!("gradient-{definition_count}");
+                definition_count += 1;
+                match gradient {
+                    Gradient::Linear { start, end, stops } => {
+                        let mut element = LinearGradient::new()
+                            .set("id", id.as_str())
+                            .set("gradientUnits", "userSpaceOnUse")
+                            .set("x1", start.x)
+                            .set("y1", start.y)
+                            .set("x2", end.x)
+                            .set("y2", end.y);
+                        append_stops(&mut element, stops);
+                        definitions.append(element);
+                    }
+                    Gradient::Radial {
+                        center,
+                        inner_radius,
+                        outer_radius,
+                        stops,
+                    } => {
+                        let mut element = RadialGradient::new()
+                            .set("id", id.as_str())
+                            .set("gradientUnits", "userSpaceOnUse")
+                            .set("cx", center.x)
+                            .set("cy", center.y)
+                            .set("fr", *inner_radius)
+                            .set("r", *outer_radius);
+                        append_stops(&mut element, stops);
+                        definitions.append(element);
+                    }
+                }
+                push_group(Group::new().set("fill", format!("url(#{id})")), entry_stack);
+            }
+            RenderCommand::DrawBoxShadow {
+                rect,
+                radius,
+                offset,
+                blur,
+                color,
+            } => {
+                let id = format!("shadow-{definition_count}");
+                definition_count += 1;
+                definitions.append(
+                    Filter::new()
+                        .set("id", id.as_str())
+                        .set("x", "-50%")
+                        .set("y", "-50%")
+                        .set("width", "200%")
+                        .set("height", "200%")
+                        // CSS blur radius corresponds to twice the standard deviation
+                        .add(FilterEffectGaussianBlur::new().set("stdDeviation", blur / 2.0)),
+                );
+                push_element(
+                    svg::node::element::Rectangle::new()
+                        .set("x", rect.origin.x + offset.x)
+                        .set("y", rect.origin.y + offset.y)
+                        .set("width", rect.size.width)
+                        .set("height", rect.size.height)
+                        .set("rx", *radius)
+                        .set("fill", svg_color(color))
+                        .set("stroke", "none")
+                        .set("filter", format!("url(#{id})")),
                     entry_stack,
                 );
             }
-            RenderCommand::SetFillColor(color) => {
-                let rgba = color.rgba;
+            RenderCommand::PushLayer { opacity } => {
+                layer_stack.push(entry_stack.len());
+                push_group(Group::new().set("opacity", *opacity), entry_stack);
+            }
+            RenderCommand::PopLayer => {
+                let Some(desired_size) = layer_stack.pop() else {
+                    bail!("PopLayer without matching PushLayer");
+                };
+                while entry_stack.len() > desired_size {
+                    pop_stack(entry_stack);
+                }
+            }
+            RenderCommand::SetLineCap(line_cap) => {
+                let line_cap = match line_cap {
+                    LineCap::Butt => "butt",
+                    LineCap::Round => "round",
+                    LineCap::Square => "square",
+                };
+                push_group(Group::new().set("stroke-linecap", line_cap), entry_stack);
+            }
+            RenderCommand::SetLineJoin(line_join) => {
+                let line_join = match line_join {
+                    LineJoin::Miter => "miter",
+                    LineJoin::Round => "round",
+                    LineJoin::Bevel => "bevel",
+                };
+                push_group(Group::new().set("stroke-linejoin", line_join), entry_stack);
+            }
+            RenderCommand::SetLineDash { pattern, offset } => {
+                let dash_array = if pattern.is_empty() {
+                    "none".to_string()
+                } else {
+                    pattern.iter().map(|length| length.to_string()).join(" ")
+                };
                 push_group(
-                    Group::new().set("fill", format!("rgb({} {} {})", rgba.r, rgba.g, rgba.b)),
+                    Group::new()
+                        .set("stroke-dasharray", dash_array)
+                        .set("stroke-dashoffset", *offset),
                     entry_stack,
                 );
             }
+            RenderCommand::DrawPath { path, mode } => {
+                let mut element = svg::node::element::Path::new().set("d", path.to_svg_path_data());
+                apply_draw_mode(&mut element, mode);
+                push_element(element, entry_stack);
+            }
+            RenderCommand::DrawRect { rect, mode } => {
+                let mut element = svg::node::element::Rectangle::new()
+                    .set("x", rect.origin.x)
+                    .set("y", rect.origin.y)
+                    .set("width", rect.size.width)
+                    .set("height", rect.size.height);
+                apply_draw_mode(&mut element, mode);
+                push_element(element, entry_stack);
+            }
+            RenderCommand::DrawRoundRect { rect, radius, mode } => {
+                let mut element = svg::node::element::Rectangle::new()
+                    .set("x", rect.origin.x)
+                    .set("y", rect.origin.y)
+                    .set("width", rect.size.width)
+                    .set("height", rect.size.height)
+                    .set("rx", *radius);
+                apply_draw_mode(&mut element, mode);
+                push_element(element, entry_stack);
+            }
+            RenderCommand::DrawCircle {
+                center,
+                radius,
+                mode,
+            } => {
+                let mut element = svg::node::element::Circle::new()
+                    .set("cx", center.x)
+                    .set("cy", center.y)
+                    .set("r", *radius);
+                apply_draw_mode(&mut element, mode);
+                push_element(element, entry_stack);
+            }
+            RenderCommand::DrawEllipse {
+                center,
+                radius_x,
+                radius_y,
+                mode,
+            } => {
+                let mut element = svg::node::element::Ellipse::new()
+                    .set("cx", center.x)
+                    .set("cy", center.y)
+                    .set("rx", *radius_x)
+                    .set("ry", *radius_y);
+                apply_draw_mode(&mut element, mode);
+                push_element(element, entry_stack);
+            }
             RenderCommand::FillRect { rect } => {
                 push_element(
                     svg::node::element::Rectangle::new()
@@ -170,18 +344,49 @@ pub fn render_svg_document(
                     entry_stack,
                 );
             }
-            RenderCommand::ClipRect(rect) => {
+            RenderCommand::Rotate { angle } => {
                 push_group(
-                    Group::new().set("clip-path", "url(#clip-rect)"),
+                    Group::new().set("transform", format!("rotate({})", angle.to_degrees())),
                     entry_stack,
                 );
-                push_element(
-                    svg::node::element::Rectangle::new()
-                        .set("id", "clip-rect")
-                        .set("x", rect.origin.x)
-                        .set("y", rect.origin.y)
-                        .set("width", rect.size.width)
-                        .set("height", rect.size.height),
+            }
+            RenderCommand::Scale { x, y } => {
+                push_group(
+                    Group::new().set("transform", format!("scale({} {})", x, y)),
+                    entry_stack,
+                );
+            }
+            RenderCommand::Transform(transform) => {
+                push_group(
+                    Group::new().set("transform", svg_matrix(transform)),
+                    entry_stack,
+                );
+            }
+            RenderCommand::ResetTransform => {
+                // SVG transforms nest, so undo the accumulated transform instead
+                let Some(inverse) = transform_state.transform().inverse() else {
+                    bail!("Cannot reset non-invertible transform");
+                };
+                push_group(
+                    Group::new().set("transform", svg_matrix(&inverse)),
+                    entry_stack,
+                );
+            }
+            RenderCommand::ClipRect(rect) => {
+                // Clip paths use the coordinate system of the referencing group, so transforms apply
+                let id = format!("clip-{definition_count}");
+                definition_count += 1;
+                definitions.append(
+                    ClipPath::new().set("id", id.clone()).add(
+                        svg::node::element::Rectangle::new()
+                            .set("x", rect.origin.x)
+                            .set("y", rect.origin.y)
+                            .set("width", rect.size.width)
+                            .set("height", rect.size.height),
+                    ),
+                );
+                push_group(
+                    Group::new().set("clip-path", format!("url(#{id})")),
                     entry_stack,
                 );
             }
@@ -214,13 +419,19 @@ pub fn render_svg_document(
                 bail!("Unsupported render command {:?}", render_command);
             }
         }
+        transform_state.apply(render_command);
     }
     while entry_stack.len() > 1 {
         pop_stack(entry_stack);
     }
 
     let mut document = Document::new().set("viewBox", (0, 0, size.width, size.height));
-    document.append(Style::new(style_content));
+    if !style_content.is_empty() {
+        document.append(Style::new(style_content));
+    }
+    if definition_count > 0 {
+        document.append(definitions);
+    }
 
     let root_children = entry_stack.pop().unwrap().children;
     for child in root_children {
@@ -230,14 +441,35 @@ pub fn render_svg_document(
     Ok(document)
 }
 
+fn svg_matrix(transform: &Transform) -> String {
+    let [a, b, c, d, e, f] = transform.to_array();
+    format!("matrix({a} {b} {c} {d} {e} {f})")
+}
+
+/// Formats a color for SVG attributes, only including the alpha channel if not fully opaque
+fn svg_color(color: &Color) -> String {
+    let rgba = color.rgba;
+    if rgba.a == 255 {
+        format!("rgb({} {} {})", rgba.r, rgba.g, rgba.b)
+    } else {
+        let alpha = (rgba.a as f32 / 255.0 * 1000.0).round() / 1000.0;
+        format!("rgb({} {} {} / {})", rgba.r, rgba.g, rgba.b, alpha)
+    }
+}
+
 // Tests
 #[cfg(test)]
 mod tests {
     use crate::render::backend_svg::render_svg;
-    use crate::render::command::{ImageId, RenderCommand};
+    use crate::render::command::{
+        DrawMode, Gradient, GradientStop, ImageId, LineCap, LineJoin, RenderCommand,
+    };
+    use crate::render::path::PathData;
     use crate::resource::Resource;
-    use crate::types::{Color, Point, Rect, Size};
+    use crate::test::rasterize::{assert_pixmaps_eq, rasterize};
+    use crate::types::{Color, Point, Rect, Size, Transform, Vector};
     use expect_test::{expect, Expect};
+    use std::f32::consts::FRAC_PI_2;
     use std::fs;
     use std::path::Path;
 
@@ -257,17 +489,11 @@ mod tests {
     }
 
     fn test_render_svg(name: &str, commands: &[RenderCommand], expected: Expect) {
-        let mut buffer = Vec::new();
-        render_svg(
-            Size::new(1000.0, 1000.0),
-            commands,
-            &mut buffer,
-            Path::new(""),
-        )
-        .unwrap();
-        let svg = String::from_utf8(buffer).unwrap();
         let file_path = format!("test/{name}.svg");
         let file_path = Path::new(&file_path);
+        let mut buffer = Vec::new();
+        render_svg(Size::new(1000.0, 1000.0), commands, &mut buffer, file_path).unwrap();
+        let svg = String::from_utf8(buffer).unwrap();
         let content = if file_path.exists() {
             fs::read_to_string(file_path).unwrap()
         } else {
@@ -321,6 +547,51 @@ mod tests {
           <line x1="0" x2="200" y1="10" y2="300"/>
           </g>
           </svg>"#]];
+      rotate, &[RenderCommand::Translate { x: 50.0, y: 50.0}, RenderCommand::Rotate { angle: FRAC_PI_2 }, RenderCommand::Line { start: Point::new(0.0, 0.0), end: Point::new(40.0, 0.0) }], expect![[r#"
+          <svg style="background-color: white" viewBox="0 0 1000 1000" xmlns="http://www.w3.org/2000/svg">
+          <g transform="translate(50 50)">
+          <g transform="rotate(90)">
+          <line x1="0" x2="40" y1="0" y2="0"/>
+          </g>
+          </g>
+          </svg>"#]];
+      scale, &[RenderCommand::Scale { x: 2.0, y: 0.5}, RenderCommand::Line { start: Point::new(0.0, 10.0), end: Point::new(200.0, 300.0) }], expect![[r#"
+          <svg style="background-color: white" viewBox="0 0 1000 1000" xmlns="http://www.w3.org/2000/svg">
+          <g transform="scale(2 0.5)">
+          <line x1="0" x2="200" y1="10" y2="300"/>
+          </g>
+          </svg>"#]];
+      transform, &[RenderCommand::Transform(Transform::new(1.0, 0.0, 0.5, 1.0, 10.0, 20.0)), RenderCommand::Line { start: Point::new(0.0, 10.0), end: Point::new(200.0, 300.0) }], expect![[r#"
+          <svg style="background-color: white" viewBox="0 0 1000 1000" xmlns="http://www.w3.org/2000/svg">
+          <g transform="matrix(1 0 0.5 1 10 20)">
+          <line x1="0" x2="200" y1="10" y2="300"/>
+          </g>
+          </svg>"#]];
+      reset_transform, &[RenderCommand::Translate { x: 70.0, y: 80.0}, RenderCommand::Scale { x: 2.0, y: 2.0}, RenderCommand::ResetTransform, RenderCommand::Line { start: Point::new(0.0, 10.0), end: Point::new(200.0, 300.0) }], expect![[r#"
+          <svg style="background-color: white" viewBox="0 0 1000 1000" xmlns="http://www.w3.org/2000/svg">
+          <g transform="translate(70 80)">
+          <g transform="scale(2 2)">
+          <g transform="matrix(0.5 0 0 0.5 -35 -40)">
+          <line x1="0" x2="200" y1="10" y2="300"/>
+          </g>
+          </g>
+          </g>
+          </svg>"#]];
+      clip_rect, &[RenderCommand::Translate { x: 10.0, y: 20.0}, RenderCommand::ClipRect(Rect::new(Point::new(0.0, 0.0), Size::new(100.0, 50.0))), RenderCommand::SetFillColor(Color::GRAY), RenderCommand::FillRect { rect: Rect::new(Point::new(-10.0, -10.0), Size::new(200.0, 200.0)) }], expect![[r#"
+          <svg style="background-color: white" viewBox="0 0 1000 1000" xmlns="http://www.w3.org/2000/svg">
+          <defs>
+          <clipPath id="clip-0">
+          <rect height="50" width="100" x="0" y="0"/>
+          </clipPath>
+          </defs>
+          <g transform="translate(10 20)">
+          <g clip-path="url(#clip-0)">
+          <g fill="rgb(235 235 235)">
+          <rect height="200" width="200" x="-10" y="-10"/>
+          </g>
+          </g>
+          </g>
+          </svg>"#]];
       fill_rect, &[RenderCommand::SetFillColor(Color::GRAY), RenderCommand::FillRect { rect: Rect::new(Point::new(3.0, 4.0), Size::new(100.0, 200.0)) }], expect![[r#"
           <svg style="background-color: white" viewBox="0 0 1000 1000" xmlns="http://www.w3.org/2000/svg">
           <g fill="rgb(235 235 235)">
@@ -347,6 +618,145 @@ mod tests {
           <svg style="background-color: white" viewBox="0 0 1000 1000" xmlns="http://www.w3.org/2000/svg">
           <image href="../assets/images/cat_playing.jpg"/>
           </svg>"#]];
+      path, &[RenderCommand::DrawPath { path: PathData::new().move_to(Point::new(10.0, 20.0)).line_to(Point::new(30.0, 20.0)).quad_to(Point::new(40.0, 30.0), Point::new(30.0, 40.0)).cubic_to(Point::new(20.0, 50.0), Point::new(15.0, 45.0), Point::new(10.0, 40.0)).close(), mode: DrawMode::FillAndStroke }], expect![[r#"
+          <svg style="background-color: white" viewBox="0 0 1000 1000" xmlns="http://www.w3.org/2000/svg">
+          <path d="M 10 20 L 30 20 Q 40 30 30 40 C 20 50 15 45 10 40 Z"/>
+          </svg>"#]];
+      stroke_path, &[RenderCommand::SetStrokeColor(Color::BLACK), RenderCommand::DrawPath { path: PathData::line(Point::new(10.0, 20.0), Point::new(30.0, 40.0)), mode: DrawMode::Stroke }], expect![[r#"
+          <svg style="background-color: white" viewBox="0 0 1000 1000" xmlns="http://www.w3.org/2000/svg">
+          <g stroke="rgb(0 0 0)">
+          <path d="M 10 20 L 30 40" fill="none"/>
+          </g>
+          </svg>"#]];
+      fill_path, &[RenderCommand::SetFillColor(Color::GRAY), RenderCommand::DrawPath { path: PathData::rect(&Rect::new(Point::new(3.0, 4.0), Size::new(10.0, 20.0))), mode: DrawMode::Fill }], expect![[r#"
+          <svg style="background-color: white" viewBox="0 0 1000 1000" xmlns="http://www.w3.org/2000/svg">
+          <g fill="rgb(235 235 235)">
+          <path d="M 3 4 L 13 4 L 13 24 L 3 24 Z" stroke="none"/>
+          </g>
+          </svg>"#]];
+      stroke_rect, &[RenderCommand::DrawRect { rect: Rect::new(Point::new(3.0, 4.0), Size::new(100.0, 200.0)), mode: DrawMode::Stroke }], expect![[r#"
+          <svg style="background-color: white" viewBox="0 0 1000 1000" xmlns="http://www.w3.org/2000/svg">
+          <rect fill="none" height="200" width="100" x="3" y="4"/>
+          </svg>"#]];
+      fill_and_stroke_round_rect, &[RenderCommand::DrawRoundRect { rect: Rect::new(Point::new(3.0, 4.0), Size::new(100.0, 200.0)), radius: 5.0, mode: DrawMode::FillAndStroke }], expect![[r#"
+          <svg style="background-color: white" viewBox="0 0 1000 1000" xmlns="http://www.w3.org/2000/svg">
+          <rect height="200" rx="5" width="100" x="3" y="4"/>
+          </svg>"#]];
+      stroke_circle, &[RenderCommand::DrawCircle { center: Point::new(50.0, 60.0), radius: 20.0, mode: DrawMode::Stroke }], expect![[r#"
+          <svg style="background-color: white" viewBox="0 0 1000 1000" xmlns="http://www.w3.org/2000/svg">
+          <circle cx="50" cy="60" fill="none" r="20"/>
+          </svg>"#]];
+      fill_ellipse, &[RenderCommand::DrawEllipse { center: Point::new(50.0, 60.0), radius_x: 20.0, radius_y: 10.0, mode: DrawMode::Fill }], expect![[r#"
+          <svg style="background-color: white" viewBox="0 0 1000 1000" xmlns="http://www.w3.org/2000/svg">
+          <ellipse cx="50" cy="60" rx="20" ry="10" stroke="none"/>
+          </svg>"#]];
+      line_cap_and_join, &[RenderCommand::SetLineCap(LineCap::Round), RenderCommand::SetLineJoin(LineJoin::Bevel), RenderCommand::Line { start: Point::new(0.0, 1.0), end: Point::new(200.0, 300.0) }], expect![[r#"
+          <svg style="background-color: white" viewBox="0 0 1000 1000" xmlns="http://www.w3.org/2000/svg">
+          <g stroke-linecap="round">
+          <g stroke-linejoin="bevel">
+          <line x1="0" x2="200" y1="1" y2="300"/>
+          </g>
+          </g>
+          </svg>"#]];
+      line_dash, &[RenderCommand::SetLineDash { pattern: vec![5.0, 2.5], offset: 1.0 }, RenderCommand::Line { start: Point::new(0.0, 1.0), end: Point::new(200.0, 300.0) }, RenderCommand::SetLineDash { pattern: vec![], offset: 0.0 }, RenderCommand::Line { start: Point::new(0.0, 10.0), end: Point::new(200.0, 300.0) }], expect![[r#"
+          <svg style="background-color: white" viewBox="0 0 1000 1000" xmlns="http://www.w3.org/2000/svg">
+          <g stroke-dasharray="5 2.5" stroke-dashoffset="1">
+          <line x1="0" x2="200" y1="1" y2="300"/>
+          <g stroke-dasharray="none" stroke-dashoffset="0">
+          <line x1="0" x2="200" y1="10" y2="300"/>
+          </g>
+          </g>
+          </svg>"#]];
+
+      fill_color_with_alpha, &[RenderCommand::SetFillColor(Color::rgba(10, 20, 30, 128)), RenderCommand::FillRect { rect: Rect::new(Point::new(3.0, 4.0), Size::new(100.0, 200.0)) }], expect![[r#"
+          <svg style="background-color: white" viewBox="0 0 1000 1000" xmlns="http://www.w3.org/2000/svg">
+          <g fill="rgb(10 20 30 / 0.502)">
+          <rect height="200" width="100" x="3" y="4"/>
+          </g>
+          </svg>"#]];
+      linear_gradient, &[RenderCommand::SetFillGradient(Gradient::Linear { start: Point::new(0.0, 0.0), end: Point::new(100.0, 0.0), stops: vec![GradientStop::new(0.0, Color::BLACK), GradientStop::new(1.0, Color::WHITE.with_alpha(0))] }), RenderCommand::FillRect { rect: Rect::new(Point::new(0.0, 0.0), Size::new(100.0, 50.0)) }], expect![[r#"
+          <svg style="background-color: white" viewBox="0 0 1000 1000" xmlns="http://www.w3.org/2000/svg">
+          <defs>
+          <linearGradient gradientUnits="userSpaceOnUse" id="gradient-0" x1="0" x2="100" y1="0" y2="0">
+          <stop offset="0" stop-color="rgb(0 0 0)"/>
+          <stop offset="1" stop-color="rgb(255 255 255 / 0)"/>
+          </linearGradient>
+          </defs>
+          <g fill="url(#gradient-0)">
+          <rect height="50" width="100" x="0" y="0"/>
+          </g>
+          </svg>"#]];
+      radial_gradient, &[RenderCommand::SetFillGradient(Gradient::Radial { center: Point::new(50.0, 50.0), inner_radius: 10.0, outer_radius: 50.0, stops: vec![GradientStop::new(0.0, Color::WHITE), GradientStop::new(1.0, Color::GRAY)] }), RenderCommand::DrawCircle { center: Point::new(50.0, 50.0), radius: 50.0, mode: DrawMode::Fill }], expect![[r#"
+          <svg style="background-color: white" viewBox="0 0 1000 1000" xmlns="http://www.w3.org/2000/svg">
+          <defs>
+          <radialGradient cx="50" cy="50" fr="10" gradientUnits="userSpaceOnUse" id="gradient-0" r="50">
+          <stop offset="0" stop-color="rgb(255 255 255)"/>
+          <stop offset="1" stop-color="rgb(235 235 235)"/>
+          </radialGradient>
+          </defs>
+          <g fill="url(#gradient-0)">
+          <circle cx="50" cy="50" r="50" stroke="none"/>
+          </g>
+          </svg>"#]];
+      layer, &[RenderCommand::PushLayer { opacity: 0.5 }, RenderCommand::SetFillColor(Color::GRAY), RenderCommand::FillRect { rect: Rect::new(Point::new(0.0, 0.0), Size::new(100.0, 50.0)) }, RenderCommand::PopLayer, RenderCommand::FillRect { rect: Rect::new(Point::new(0.0, 60.0), Size::new(100.0, 50.0)) }], expect![[r#"
+          <svg style="background-color: white" viewBox="0 0 1000 1000" xmlns="http://www.w3.org/2000/svg">
+          <g opacity="0.5">
+          <g fill="rgb(235 235 235)">
+          <rect height="50" width="100" x="0" y="0"/>
+          </g>
+          </g>
+          <rect height="50" width="100" x="0" y="60"/>
+          </svg>"#]];
+      box_shadow, &[RenderCommand::DrawBoxShadow { rect: Rect::new(Point::new(10.0, 10.0), Size::new(100.0, 50.0)), radius: 5.0, offset: Vector::new(2.0, 3.0), blur: 8.0, color: Color::BLACK.with_alpha(64) }], expect![[r#"
+          <svg style="background-color: white" viewBox="0 0 1000 1000" xmlns="http://www.w3.org/2000/svg">
+          <defs>
+          <filter height="200%" id="shadow-0" width="200%" x="-50%" y="-50%">
+          <feGaussianBlur stdDeviation="4"/>
+          </filter>
+          </defs>
+          <rect fill="rgb(0 0 0 / 0.251)" filter="url(#shadow-0)" height="50" rx="5" stroke="none" width="100" x="12" y="13"/>
+          </svg>"#]];
 
     );
+
+    /// Layers reset the state changed inside them in the SVG backend and the rasterizer alike
+    #[test]
+    fn layer_matches_rasterizer() {
+        let (width, height) = (120, 120);
+        // resvg draws the white background of the SVG document
+        let commands = [
+            RenderCommand::Save,
+            RenderCommand::SetFillColor(Color::WHITE),
+            RenderCommand::FillRect {
+                rect: Rect::new(Point::new(0.0, 0.0), Size::new(width as f32, height as f32)),
+            },
+            RenderCommand::Restore,
+            RenderCommand::PushLayer { opacity: 0.5 },
+            RenderCommand::SetFillColor(Color::GRAY),
+            RenderCommand::Translate { x: 10.0, y: 10.0 },
+            RenderCommand::ClipRect(Rect::new(Point::new(0.0, 0.0), Size::new(50.0, 50.0))),
+            RenderCommand::FillRect {
+                rect: Rect::new(Point::new(0.0, 0.0), Size::new(100.0, 40.0)),
+            },
+            RenderCommand::PopLayer,
+            RenderCommand::FillRect {
+                rect: Rect::new(Point::new(0.0, 60.0), Size::new(100.0, 40.0)),
+            },
+        ];
+        let mut buffer = Vec::new();
+        render_svg(
+            Size::new(width as f32, height as f32),
+            &commands,
+            &mut buffer,
+            Path::new("test/layer.svg"),
+        )
+        .unwrap();
+        let tree = resvg::usvg::Tree::from_data(&buffer, &resvg::usvg::Options::default()).unwrap();
+        let mut svg_pixmap = resvg::tiny_skia::Pixmap::new(width, height).unwrap();
+        resvg::render(&tree, Default::default(), &mut svg_pixmap.as_mut());
+        // resvg uses a newer tiny-skia than the rasterizer
+        let mut expected = tiny_skia::Pixmap::new(width, height).unwrap();
+        expected.data_mut().copy_from_slice(svg_pixmap.data());
+        assert_pixmaps_eq(&rasterize(width, height, &commands), &expected);
+    }
 }
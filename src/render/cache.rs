@@ -0,0 +1,137 @@
+use crate::nodes::data::{NodeData, NodeIdx};
+use crate::render::command::RenderCommand;
+use crate::render::context::PopupCommands;
+use crate::types::{Rect, Transform};
+use bevy_reflect::Reflect;
+use std::collections::HashMap;
+
+/// Render commands of a node from a previous frame, reused as long as the node is unchanged
+pub struct CachedNodeCommands {
+    kind_index: usize,
+    bounds: Rect,
+    viewport: Option<Rect>,
+    is_active: bool,
+    is_focused: bool,
+    is_animated: bool,
+    props: Box<dyn Reflect>,
+    state: Box<dyn Reflect>,
+    pub commands: Vec<RenderCommand>,
+    pub transform: Transform,
+    pub clip_bounds: Option<Rect>,
+    /// Area of the window the commands may draw to
+    pub screen_bounds: Rect,
+    /// Popup drawn above all other nodes
+    pub popup: Option<PopupCommands>,
+}
+
+impl CachedNodeCommands {
+    pub fn new(
+        node: &NodeData,
+        is_active: bool,
+        is_focused: bool,
+        is_animated: bool,
+        commands: Vec<RenderCommand>,
+        transform: Transform,
+        clip_bounds: Option<Rect>,
+    ) -> Self {
+        let local_bounds = Rect::from_size(node.bounds().size);
+        let mut screen_bounds = transform.outer_transformed_rect(&local_bounds);
+        if let Some(clip_bounds) = &clip_bounds {
+            screen_bounds = screen_bounds.intersection(clip_bounds).unwrap_or_default();
+        }
+        Self {
+            kind_index: node.kind_index(),
+            bounds: *node.bounds(),
+            viewport: node.layout.viewport,
+            is_active,
+            is_focused,
+            is_animated,
+            props: node.props.clone_value(),
+            state: node.state.clone_value(),
+            commands,
+            transform,
+            clip_bounds,
+            screen_bounds,
+            popup: None,
+        }
+    }
+
+    /// Window areas drawn to by the node and its popup
+    pub fn damage_bounds(&self) -> impl Iterator<Item = &Rect> {
+        std::iter::once(&self.screen_bounds)
+            .chain(self.popup.as_ref().map(|popup| &popup.screen_bounds))
+    }
+
+    /// Whether rendering the node again would produce the same commands
+    pub fn is_valid_for(&self, node: &NodeData, is_active: bool, is_focused: bool) -> bool {
+        !self.is_animated
+            && self.kind_index == node.kind_index()
+            && self.bounds == *node.bounds()
+            && self.viewport == node.layout.viewport
+            && self.is_active == is_active
+            && self.is_focused == is_focused
+            && node.props.reflect_partial_eq(self.props.as_ref()) == Some(true)
+            && node.state.reflect_partial_eq(self.state.as_ref()) == Some(true)
+    }
+}
+
+/// Cached render commands of all nodes rendered in the previous frame of a backend
+#[derive(Default)]
+pub struct RenderCache {
+    pub nodes: HashMap<NodeIdx, CachedNodeCommands>,
+    /// Whether the backend holds a complete previous frame that can be partially updated
+    pub has_frame: bool,
+    /// Text and window area of the tooltip in the previous frame
+    pub tooltip: Option<(String, Rect)>,
+}
+
+impl RenderCache {
+    pub fn invalidate(&mut self) {
+        self.nodes.clear();
+        self.has_frame = false;
+        self.tooltip = None;
+    }
+}
+
+/// Smallest rect containing all damaged areas of a frame
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct DamageRect {
+    rect: Option<Rect>,
+}
+
+impl DamageRect {
+    pub fn add(&mut self, rect: &Rect) {
+        if rect.is_empty() {
+            return;
+        }
+        self.rect = Some(match self.rect {
+            Some(damage) => damage.union(rect),
+            None => *rect,
+        });
+    }
+
+    /// The damaged area extended to whole pixels, `None` if nothing changed
+    pub fn rect(&self) -> Option<Rect> {
+        // Anti-aliased edges may touch pixels just outside the bounds
+        self.rect.map(|rect| rect.inflate(1.0, 1.0).round_out())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Point, Size};
+
+    #[test]
+    fn damage_rect_is_union_of_added_rects() {
+        let mut damage = DamageRect::default();
+        assert_eq!(damage.rect(), None);
+        damage.add(&Rect::new(Point::new(10.5, 10.0), Size::new(10.0, 10.0)));
+        damage.add(&Rect::zero());
+        damage.add(&Rect::new(Point::new(30.0, 5.0), Size::new(10.0, 5.2)));
+        assert_eq!(
+            damage.rect(),
+            Some(Rect::new(Point::new(9.0, 4.0), Size::new(32.0, 17.0)))
+        );
+    }
+}
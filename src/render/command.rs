@@ -1,17 +1,90 @@
 use crate::infrastructure::font_pool::FontIndex;
+use crate::render::path::PathData;
 use crate::resource::Resource;
-use crate::types::{Color, Float, Point, Rect, Size};
+use crate::types::{Color, Float, Point, Rect, Size, Transform, Vector};
+use euclid::Angle;
 
 #[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
 pub struct ImageId(pub u64);
 
-#[derive(Clone, Debug)]
+/// Whether a shape is filled with the fill color, outlined with the stroke color, or both
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum DrawMode {
+    Fill,
+    Stroke,
+    FillAndStroke,
+}
+
+impl DrawMode {
+    pub fn fills(&self) -> bool {
+        matches!(self, DrawMode::Fill | DrawMode::FillAndStroke)
+    }
+
+    pub fn strokes(&self) -> bool {
+        matches!(self, DrawMode::Stroke | DrawMode::FillAndStroke)
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum LineCap {
+    #[default]
+    Butt,
+    Round,
+    Square,
+}
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum LineJoin {
+    #[default]
+    Miter,
+    Round,
+    Bevel,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct GradientStop {
+    /// Position of the stop along the gradient, from 0.0 to 1.0
+    pub offset: Float,
+    pub color: Color,
+}
+
+impl GradientStop {
+    pub fn new(offset: Float, color: Color) -> Self {
+        Self { offset, color }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Gradient {
+    Linear {
+        start: Point,
+        end: Point,
+        stops: Vec<GradientStop>,
+    },
+    Radial {
+        center: Point,
+        inner_radius: Float,
+        outer_radius: Float,
+        stops: Vec<GradientStop>,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq)]
 pub enum RenderCommand {
     Save,
     Restore,
     SetStrokeColor(Color),
     SetStrokeWidth(Float),
     SetFillColor(Color),
+    /// Fill subsequent shapes with a gradient, until the next fill color or gradient is set
+    SetFillGradient(Gradient),
+    SetLineCap(LineCap),
+    SetLineJoin(LineJoin),
+    /// Alternating dash and gap lengths, an empty pattern draws solid lines
+    SetLineDash {
+        pattern: Vec<Float>,
+        offset: Float,
+    },
     FillRect {
         rect: Rect,
     },
@@ -29,11 +102,61 @@ pub enum RenderCommand {
         start_angle: Float,
         end_angle: Float,
     },
+    DrawPath {
+        path: PathData,
+        mode: DrawMode,
+    },
+    DrawRect {
+        rect: Rect,
+        mode: DrawMode,
+    },
+    DrawRoundRect {
+        rect: Rect,
+        radius: Float,
+        mode: DrawMode,
+    },
+    DrawCircle {
+        center: Point,
+        radius: Float,
+        mode: DrawMode,
+    },
+    DrawEllipse {
+        center: Point,
+        radius_x: Float,
+        radius_y: Float,
+        mode: DrawMode,
+    },
+    /// Blurred shadow below a (rounded) rectangle, as in the CSS `box-shadow` property
+    DrawBoxShadow {
+        rect: Rect,
+        radius: Float,
+        offset: Vector,
+        blur: Float,
+        color: Color,
+    },
+    /// Draw everything up to the matching `PopLayer` into a layer, composited with the given opacity
+    ///
+    /// Like `Save` and `Restore`, `PopLayer` resets the state changed inside the layer.
+    PushLayer {
+        opacity: Float,
+    },
+    PopLayer,
     Translate {
         x: Float,
         y: Float,
     },
-    //    ResetTransform,
+    /// Rotate around the current origin, angle in radians (clockwise, since the y axis points down)
+    Rotate {
+        angle: Float,
+    },
+    Scale {
+        x: Float,
+        y: Float,
+    },
+    /// Apply an arbitrary affine transform on top of the current transform
+    Transform(Transform),
+    /// Reset the current transform to the identity, clip rects already set remain in effect
+    ResetTransform,
     DrawText(String),
     ClipRect(Rect),
     LoadImage {
@@ -55,4 +178,17 @@ pub enum RenderCommand {
     },
 }
 
+impl RenderCommand {
+    /// The transform this command applies on top of the current transform, if any
+    pub fn local_transform(&self) -> Option<Transform> {
+        match self {
+            RenderCommand::Translate { x, y } => Some(Transform::translation(*x, *y)),
+            RenderCommand::Rotate { angle } => Some(Transform::rotation(Angle::radians(*angle))),
+            RenderCommand::Scale { x, y } => Some(Transform::scale(*x, *y)),
+            RenderCommand::Transform(transform) => Some(*transform),
+            _ => None,
+        }
+    }
+}
+
 pub type RenderCommands = Vec<RenderCommand>;
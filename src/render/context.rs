@@ -1,19 +1,38 @@
 use crate::infrastructure::font_pool::{FontIndex, FontPool};
 use crate::infrastructure::image_pool::ImagePool;
-use crate::infrastructure::measure_text::TextMeasurement;
-use crate::render::command::{ImageId, RenderCommand};
+use crate::infrastructure::measure_text::{wrap_text, TextMeasurement};
+use crate::render::command::{DrawMode, ImageId, RenderCommand};
+use crate::render::transform::TransformState;
 use crate::resource::Resource;
 use crate::result::ViuiResult;
-use crate::types::Float;
+use crate::types::{Color, Float, Rect, Size, Transform};
+
+pub const DEFAULT_FONT_SIZE: Float = 25.0;
+const FOCUS_RING_WIDTH: Float = 2.0;
+const FOCUS_RING_RADIUS: Float = 3.0;
+
+/// Commands of a popup, drawn above all other content
+pub struct PopupCommands {
+    /// Replayable outside of the node, as they include the transform of the node
+    pub commands: Vec<RenderCommand>,
+    /// Area of the window the popup covers
+    pub screen_bounds: Rect,
+}
 
 pub struct RenderContext<'a> {
     font_size: Float,
+    size: Size,
     render_queue: Vec<RenderCommand>,
+    transform_state: TransformState,
     image_pool: &'a mut ImagePool,
     font_pool: &'a mut FontPool,
     time: Float,
     is_animated: bool,
     is_active: bool,
+    is_focused: bool,
+    /// Start of the popup commands, with the popup bounds and the transform of the node
+    popup_start: Option<(usize, Rect, Transform)>,
+    popup: Option<PopupCommands>,
 }
 
 impl<'a> RenderContext<'a> {
@@ -34,28 +53,62 @@ impl<'a> RenderContext<'a> {
         }
 
         Ok(Self {
-            font_size: 25.0,
+            font_size: DEFAULT_FONT_SIZE,
+            size: Size::zero(),
             render_queue,
+            transform_state: TransformState::new(),
             image_pool,
             font_pool,
             time,
             is_animated: false,
             is_active: false,
+            is_focused: false,
+            popup_start: None,
+            popup: None,
         })
     }
 }
 impl RenderContext<'_> {
     pub fn add_command(&mut self, command: RenderCommand) {
+        self.transform_state.apply(&command);
         self.render_queue.push(command);
     }
     pub fn add_commands(&mut self, commands: impl IntoIterator<Item = RenderCommand>) {
-        self.render_queue.extend(commands);
+        for command in commands {
+            self.add_command(command);
+        }
+    }
+
+    /// Transform from current local coordinates to window coordinates
+    pub fn transform(&self) -> &Transform {
+        self.transform_state.transform()
+    }
+
+    /// Bounds of the current clip region in window coordinates
+    pub fn clip_bounds(&self) -> Option<&Rect> {
+        self.transform_state.clip_bounds()
+    }
+
+    /// Whether any of the current clip region is inside the window, so that drawing shows
+    pub fn is_visible(&self) -> bool {
+        self.clip_bounds()
+            .is_none_or(|clip_bounds| !clip_bounds.size.is_empty())
     }
 
     pub fn get_image_id(&mut self, path: &str) -> ViuiResult<ImageId> {
         self.image_pool.get_image_id(path)
     }
 
+    /// Number of commands added so far
+    pub fn command_count(&self) -> usize {
+        self.render_queue.len()
+    }
+
+    /// Removes and returns all commands added after the first `start` commands
+    pub fn split_off_commands(&mut self, start: usize) -> Vec<RenderCommand> {
+        self.render_queue.split_off(start)
+    }
+
     pub fn render_queue(self) -> Vec<RenderCommand> {
         self.render_queue
     }
@@ -79,6 +132,25 @@ impl RenderContext<'_> {
             .measure_text(FontIndex::new(0), text, self.font_size)
     }
 
+    pub fn wrap_text(&self, text: &str, max_width: Option<Float>) -> ViuiResult<Vec<String>> {
+        wrap_text(text, max_width, |line| Ok(self.measure_text(line)?.width))
+    }
+
+    /// Distance from the top of a line of text to its baseline
+    pub fn text_ascent(&self) -> Float {
+        self.font_pool
+            .text_ascent(FontIndex::new(0), self.font_size)
+    }
+
+    /// Sets the laid out size of the node being rendered
+    pub fn set_size(&mut self, size: Size) {
+        self.size = size;
+    }
+
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
     pub fn set_active(&mut self, active: bool) {
         self.is_active = active;
     }
@@ -86,4 +158,59 @@ impl RenderContext<'_> {
     pub fn is_active(&self) -> bool {
         self.is_active
     }
+
+    pub fn set_focused(&mut self, focused: bool) {
+        self.is_focused = focused;
+    }
+
+    /// Whether the node being rendered has keyboard focus
+    pub fn is_focused(&self) -> bool {
+        self.is_focused
+    }
+
+    /// Outlines the node being rendered to show that it has keyboard focus
+    pub fn draw_focus_ring(&mut self, color: Color) {
+        let inset = FOCUS_RING_WIDTH / 2.0;
+        self.add_command(RenderCommand::SetStrokeColor(color));
+        self.add_command(RenderCommand::SetStrokeWidth(FOCUS_RING_WIDTH));
+        self.add_command(RenderCommand::DrawRoundRect {
+            rect: Rect::from_size(self.size).inflate(-inset, -inset),
+            radius: FOCUS_RING_RADIUS,
+            mode: DrawMode::Stroke,
+        });
+    }
+
+    /// Starts a popup covering `bounds` in local coordinates
+    ///
+    /// Commands until `end_popup` are drawn above all other content, translated to the popup
+    /// origin and clipped to the popup instead of the node. Popups receive pointer events first.
+    pub fn begin_popup(&mut self, bounds: Rect) {
+        let screen_bounds = self.transform().outer_transformed_rect(&bounds);
+        self.popup_start = Some((self.command_count(), screen_bounds, *self.transform()));
+        self.add_command(RenderCommand::Save);
+        self.add_command(RenderCommand::Translate {
+            x: bounds.origin.x,
+            y: bounds.origin.y,
+        });
+        self.add_command(RenderCommand::ClipRect(Rect::from_size(bounds.size)));
+    }
+
+    pub fn end_popup(&mut self) {
+        let Some((start, screen_bounds, transform)) = self.popup_start.take() else {
+            return;
+        };
+        self.add_command(RenderCommand::Restore);
+        let mut commands = vec![RenderCommand::Save, RenderCommand::Transform(transform)];
+        commands.extend(self.split_off_commands(start));
+        commands.push(RenderCommand::Restore);
+        self.popup = Some(PopupCommands {
+            commands,
+            screen_bounds,
+        });
+    }
+
+    /// Removes the popup drawn by the node being rendered
+    pub fn take_popup(&mut self) -> Option<PopupCommands> {
+        self.popup.take()
+    }
 }
@@ -1,7 +1,11 @@
 pub mod backend;
 pub mod backend_femtovg;
 pub mod backend_svg;
+pub mod cache;
 pub mod command;
 pub mod context;
 pub mod make_render_commands;
+pub mod optimize;
 pub mod parameters;
+pub mod path;
+pub mod transform;
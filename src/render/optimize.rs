@@ -0,0 +1,542 @@
+//! Optimisation pass over render commands, removing commands that do not change the rendered output
+
+use crate::render::command::RenderCommand;
+use crate::render::transform::TransformState;
+use crate::types::{Float, Point, Rect, Size, Vector};
+use std::collections::HashMap;
+
+/// Conservative bound for how far strokes extend beyond their geometry, relative to the stroke width
+///
+/// Miter joins may extend up to half the stroke width times the miter limit (10 in femtovg)
+const STROKE_EXTENT_FACTOR: Float = 5.0;
+
+/// Extra margin around draw bounds to account for anti-aliasing
+const ANTI_ALIAS_MARGIN: Float = 1.0;
+
+pub fn optimize_render_commands(commands: Vec<RenderCommand>) -> Vec<RenderCommand> {
+    let commands = cull_clipped_draws(commands);
+    let commands = merge_translates(commands);
+    let commands = remove_redundant_state(commands);
+    elide_empty_save_restore(commands)
+}
+
+/// Removes draw commands whose bounds lie fully outside the active clip rect
+fn cull_clipped_draws(commands: Vec<RenderCommand>) -> Vec<RenderCommand> {
+    let mut transform_state = TransformState::new();
+    let mut stroke_width = None;
+    let mut stroke_width_stack = vec![];
+    commands
+        .into_iter()
+        .filter(|command| {
+            let visible = match (
+                transform_state.clip_bounds(),
+                draw_bounds(command, stroke_width),
+            ) {
+                (Some(clip_bounds), Some(bounds)) => {
+                    let bounds = transform_state.transform().outer_transformed_rect(&bounds);
+                    clip_bounds.intersects(&bounds)
+                }
+                _ => true,
+            };
+            match command {
+                RenderCommand::Save | RenderCommand::PushLayer { .. } => {
+                    stroke_width_stack.push(stroke_width)
+                }
+                RenderCommand::Restore | RenderCommand::PopLayer => {
+                    stroke_width = stroke_width_stack.pop().flatten()
+                }
+                RenderCommand::SetStrokeWidth(width) => stroke_width = Some(*width),
+                _ => {}
+            }
+            transform_state.apply(command);
+            visible
+        })
+        .collect()
+}
+
+/// Local bounds of everything a draw command may touch, `None` if unknown or not a draw command
+fn draw_bounds(command: &RenderCommand, stroke_width: Option<Float>) -> Option<Rect> {
+    fn circle_bounds(center: &Point, radius_x: Float, radius_y: Float) -> Rect {
+        Rect::new(
+            *center - Vector::new(radius_x, radius_y),
+            Size::new(radius_x * 2.0, radius_y * 2.0),
+        )
+    }
+    let (bounds, strokes) = match command {
+        RenderCommand::FillRect { rect } => (*rect, false),
+        RenderCommand::FillRoundRect { rect, .. } => (*rect, true),
+        RenderCommand::Line { start, end } => (Rect::from_points([start, end]), true),
+        RenderCommand::Arc { center, radius, .. } => {
+            (circle_bounds(center, *radius, *radius), true)
+        }
+        RenderCommand::DrawPath { path, mode } => (path.bounds()?, mode.strokes()),
+        RenderCommand::DrawRect { rect, mode }
+        | RenderCommand::DrawRoundRect { rect, mode, .. } => (*rect, mode.strokes()),
+        RenderCommand::DrawCircle {
+            center,
+            radius,
+            mode,
+        } => (circle_bounds(center, *radius, *radius), mode.strokes()),
+        RenderCommand::DrawEllipse {
+            center,
+            radius_x,
+            radius_y,
+            mode,
+        } => (circle_bounds(center, *radius_x, *radius_y), mode.strokes()),
+        RenderCommand::DrawBoxShadow {
+            rect, offset, blur, ..
+        } => (
+            rect.translate(*offset).inflate(blur * 2.0, blur * 2.0),
+            false,
+        ),
+        _ => return None,
+    };
+    let margin = if strokes {
+        // Strokes of unknown width can not be culled safely
+        stroke_width? * STROKE_EXTENT_FACTOR + ANTI_ALIAS_MARGIN
+    } else {
+        ANTI_ALIAS_MARGIN
+    };
+    Some(bounds.inflate(margin, margin))
+}
+
+/// Merges consecutive translations and drops translations by zero
+fn merge_translates(commands: Vec<RenderCommand>) -> Vec<RenderCommand> {
+    let mut result: Vec<RenderCommand> = Vec::with_capacity(commands.len());
+    for command in commands {
+        if let RenderCommand::Translate { x, y } = command {
+            if let Some(RenderCommand::Translate {
+                x: previous_x,
+                y: previous_y,
+            }) = result.last_mut()
+            {
+                *previous_x += x;
+                *previous_y += y;
+                if *previous_x == 0.0 && *previous_y == 0.0 {
+                    result.pop();
+                }
+                continue;
+            }
+            if x == 0.0 && y == 0.0 {
+                continue;
+            }
+        }
+        result.push(command);
+    }
+    result
+}
+
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+enum StateProperty {
+    StrokeColor,
+    StrokeWidth,
+    Fill,
+    LineCap,
+    LineJoin,
+    LineDash,
+    Font,
+}
+
+fn state_property(command: &RenderCommand) -> Option<StateProperty> {
+    Some(match command {
+        RenderCommand::SetStrokeColor(_) => StateProperty::StrokeColor,
+        RenderCommand::SetStrokeWidth(_) => StateProperty::StrokeWidth,
+        RenderCommand::SetFillColor(_) | RenderCommand::SetFillGradient(_) => StateProperty::Fill,
+        RenderCommand::SetLineCap(_) => StateProperty::LineCap,
+        RenderCommand::SetLineJoin(_) => StateProperty::LineJoin,
+        RenderCommand::SetLineDash { .. } => StateProperty::LineDash,
+        RenderCommand::SetFont { .. } => StateProperty::Font,
+        _ => return None,
+    })
+}
+
+/// Whether the command only changes transform or clipping, without using the paint state
+fn is_geometry_state(command: &RenderCommand) -> bool {
+    matches!(
+        command,
+        RenderCommand::ResetTransform | RenderCommand::ClipRect(_)
+    ) || command.local_transform().is_some()
+}
+
+/// Removes state changes that set the current value again, or are overwritten before being used
+fn remove_redundant_state(commands: Vec<RenderCommand>) -> Vec<RenderCommand> {
+    let mut result: Vec<Option<RenderCommand>> = Vec::with_capacity(commands.len());
+    // Known values of state properties, unknown properties are missing
+    let mut values: HashMap<StateProperty, RenderCommand> = HashMap::new();
+    let mut values_stack = vec![];
+    // State changes not used by any command yet, with the index in the result and the previous value
+    let mut unused: HashMap<StateProperty, (usize, Option<RenderCommand>)> = HashMap::new();
+    for command in commands {
+        let Some(property) = state_property(&command) else {
+            if !is_geometry_state(&command) {
+                unused.clear();
+            }
+            match command {
+                RenderCommand::Save | RenderCommand::PushLayer { .. } => {
+                    values_stack.push(values.clone())
+                }
+                RenderCommand::Restore | RenderCommand::PopLayer => {
+                    values = values_stack.pop().unwrap_or_default()
+                }
+                _ => {}
+            }
+            result.push(Some(command));
+            continue;
+        };
+        if let Some((index, previous_value)) = unused.remove(&property) {
+            result[index] = None;
+            match previous_value {
+                Some(previous_value) => values.insert(property, previous_value),
+                None => values.remove(&property),
+            };
+        }
+        if values.get(&property) == Some(&command) {
+            continue;
+        }
+        unused.insert(property, (result.len(), values.get(&property).cloned()));
+        values.insert(property, command.clone());
+        result.push(Some(command));
+    }
+    result.into_iter().flatten().collect()
+}
+
+/// Removes `Save`/`Restore` pairs that only contain state changes
+fn elide_empty_save_restore(commands: Vec<RenderCommand>) -> Vec<RenderCommand> {
+    let mut result = Vec::with_capacity(commands.len());
+    let mut save_indices = vec![];
+    for command in commands {
+        match command {
+            RenderCommand::Save => {
+                save_indices.push(result.len());
+                result.push(command);
+            }
+            RenderCommand::Restore => match save_indices.pop() {
+                Some(index)
+                    if result[index + 1..].iter().all(|command| {
+                        state_property(command).is_some() || is_geometry_state(command)
+                    }) =>
+                {
+                    result.truncate(index);
+                }
+                _ => result.push(command),
+            },
+            command => result.push(command),
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::command::{DrawMode, Gradient, GradientStop};
+    use crate::render::path::PathData;
+    use crate::test::rasterize::{assert_pixmaps_eq, rasterize};
+    use crate::types::Color;
+    use expect_test::{expect, Expect};
+    use std::f32::consts::FRAC_PI_4;
+
+    const WIDTH: u32 = 200;
+    const HEIGHT: u32 = 150;
+
+    /// Checks the optimised commands and that they rasterise exactly like the original ones
+    fn check(commands: Vec<RenderCommand>, expected: Expect) {
+        let optimized = optimize_render_commands(commands.clone());
+        assert_pixmaps_eq(
+            &rasterize(WIDTH, HEIGHT, &optimized),
+            &rasterize(WIDTH, HEIGHT, &commands),
+        );
+        let actual: String = optimized
+            .iter()
+            .map(|command| format!("{command:?}\n"))
+            .collect();
+        expected.assert_eq(&actual);
+    }
+
+    fn node(x: Float, y: Float, width: Float, height: Float) -> Vec<RenderCommand> {
+        vec![
+            RenderCommand::Save,
+            RenderCommand::Translate { x, y },
+            RenderCommand::ClipRect(Rect::new(Point::zero(), Size::new(width, height))),
+        ]
+    }
+
+    fn rect(x: Float, y: Float, width: Float, height: Float) -> Rect {
+        Rect::new(Point::new(x, y), Size::new(width, height))
+    }
+
+    #[test]
+    fn merges_translates() {
+        check(
+            vec![
+                RenderCommand::SetFillColor(Color::GRAY),
+                RenderCommand::Translate { x: 10.0, y: 20.0 },
+                RenderCommand::Translate { x: 5.0, y: -5.0 },
+                RenderCommand::FillRect {
+                    rect: rect(0.0, 0.0, 30.0, 30.0),
+                },
+                RenderCommand::Translate { x: 0.0, y: 0.0 },
+                RenderCommand::Translate { x: 10.0, y: 10.0 },
+                RenderCommand::Translate { x: -10.0, y: -10.0 },
+                RenderCommand::FillRect {
+                    rect: rect(40.0, 0.0, 30.0, 30.0),
+                },
+            ],
+            expect![[r#"
+                SetFillColor(Color { rgba: Rgba { r: 235, g: 235, b: 235, a: 255 } })
+                Translate { x: 15.0, y: 15.0 }
+                FillRect { rect: Rect(30.0x30.0 at (0.0, 0.0)) }
+                FillRect { rect: Rect(30.0x30.0 at (40.0, 0.0)) }
+            "#]],
+        );
+    }
+
+    #[test]
+    fn removes_redundant_and_overwritten_state() {
+        check(
+            vec![
+                RenderCommand::SetStrokeColor(Color::BLACK),
+                RenderCommand::SetStrokeWidth(0.0),
+                RenderCommand::SetStrokeWidth(3.0),
+                RenderCommand::SetFillColor(Color::GRAY),
+                RenderCommand::DrawRect {
+                    rect: rect(10.0, 10.0, 50.0, 50.0),
+                    mode: DrawMode::FillAndStroke,
+                },
+                RenderCommand::SetStrokeColor(Color::BLACK),
+                RenderCommand::SetStrokeWidth(3.0),
+                RenderCommand::SetFillColor(Color::WHITE),
+                RenderCommand::Translate { x: 5.0, y: 5.0 },
+                RenderCommand::SetFillColor(Color::GRAY),
+                RenderCommand::DrawCircle {
+                    center: Point::new(100.0, 50.0),
+                    radius: 20.0,
+                    mode: DrawMode::FillAndStroke,
+                },
+                RenderCommand::SetFillGradient(Gradient::Linear {
+                    start: Point::new(0.0, 0.0),
+                    end: Point::new(100.0, 0.0),
+                    stops: vec![
+                        GradientStop::new(0.0, Color::BLACK),
+                        GradientStop::new(1.0, Color::WHITE),
+                    ],
+                }),
+                RenderCommand::SetFillColor(Color::GRAY),
+                RenderCommand::FillRect {
+                    rect: rect(130.0, 10.0, 50.0, 50.0),
+                },
+            ],
+            expect![[r#"
+                SetStrokeColor(Color { rgba: Rgba { r: 0, g: 0, b: 0, a: 255 } })
+                SetStrokeWidth(3.0)
+                SetFillColor(Color { rgba: Rgba { r: 235, g: 235, b: 235, a: 255 } })
+                DrawRect { rect: Rect(50.0x50.0 at (10.0, 10.0)), mode: FillAndStroke }
+                Translate { x: 5.0, y: 5.0 }
+                DrawCircle { center: (100.0, 50.0), radius: 20.0, mode: FillAndStroke }
+                FillRect { rect: Rect(50.0x50.0 at (130.0, 10.0)) }
+            "#]],
+        );
+    }
+
+    #[test]
+    fn keeps_state_needed_after_restore() {
+        check(
+            vec![
+                RenderCommand::SetFillColor(Color::GRAY),
+                RenderCommand::Save,
+                RenderCommand::SetFillColor(Color::BLACK),
+                RenderCommand::FillRect {
+                    rect: rect(0.0, 0.0, 30.0, 30.0),
+                },
+                RenderCommand::Restore,
+                RenderCommand::SetFillColor(Color::GRAY),
+                RenderCommand::FillRect {
+                    rect: rect(40.0, 0.0, 30.0, 30.0),
+                },
+                RenderCommand::SetFillColor(Color::BLACK),
+                RenderCommand::Save,
+                RenderCommand::FillRect {
+                    rect: rect(80.0, 0.0, 30.0, 30.0),
+                },
+                RenderCommand::Restore,
+            ],
+            expect![[r#"
+                SetFillColor(Color { rgba: Rgba { r: 235, g: 235, b: 235, a: 255 } })
+                Save
+                SetFillColor(Color { rgba: Rgba { r: 0, g: 0, b: 0, a: 255 } })
+                FillRect { rect: Rect(30.0x30.0 at (0.0, 0.0)) }
+                Restore
+                FillRect { rect: Rect(30.0x30.0 at (40.0, 0.0)) }
+                SetFillColor(Color { rgba: Rgba { r: 0, g: 0, b: 0, a: 255 } })
+                Save
+                FillRect { rect: Rect(30.0x30.0 at (80.0, 0.0)) }
+                Restore
+            "#]],
+        );
+    }
+
+    #[test]
+    fn elides_empty_save_restore() {
+        let mut commands = vec![RenderCommand::SetFillColor(Color::GRAY)];
+        commands.extend(node(10.0, 10.0, 50.0, 50.0));
+        commands.extend(node(5.0, 5.0, 20.0, 20.0));
+        commands.push(RenderCommand::SetStrokeWidth(2.0));
+        commands.push(RenderCommand::Restore);
+        commands.push(RenderCommand::Restore);
+        commands.extend(node(100.0, 10.0, 50.0, 50.0));
+        commands.push(RenderCommand::FillRect {
+            rect: rect(0.0, 0.0, 50.0, 50.0),
+        });
+        commands.push(RenderCommand::Restore);
+        check(
+            commands,
+            expect![[r#"
+            SetFillColor(Color { rgba: Rgba { r: 235, g: 235, b: 235, a: 255 } })
+            Save
+            Translate { x: 100.0, y: 10.0 }
+            ClipRect(Rect(50.0x50.0 at (0.0, 0.0)))
+            FillRect { rect: Rect(50.0x50.0 at (0.0, 0.0)) }
+            Restore
+        "#]],
+        );
+    }
+
+    #[test]
+    fn culls_draws_outside_clip() {
+        let mut commands = vec![
+            RenderCommand::SetFillColor(Color::GRAY),
+            RenderCommand::SetStrokeColor(Color::BLACK),
+            RenderCommand::ClipRect(rect(0.0, 0.0, WIDTH as Float, HEIGHT as Float)),
+        ];
+        commands.extend(node(10.0, 10.0, 50.0, 50.0));
+        commands.extend([
+            // Partially visible
+            RenderCommand::FillRect {
+                rect: rect(-10.0, -10.0, 30.0, 30.0),
+            },
+            // Outside
+            RenderCommand::FillRect {
+                rect: rect(60.0, 0.0, 30.0, 30.0),
+            },
+            // Stroke of unknown width might reach into the clip rect
+            RenderCommand::Line {
+                start: Point::new(55.0, 0.0),
+                end: Point::new(55.0, 50.0),
+            },
+            RenderCommand::SetStrokeWidth(2.0),
+            // Stroke reaches into the clip rect
+            RenderCommand::Line {
+                start: Point::new(51.0, 0.0),
+                end: Point::new(51.0, 50.0),
+            },
+            // Stroke outside
+            RenderCommand::Line {
+                start: Point::new(70.0, 0.0),
+                end: Point::new(70.0, 50.0),
+            },
+            RenderCommand::Restore,
+        ]);
+        // Node outside the window
+        commands.extend(node(300.0, 10.0, 50.0, 50.0));
+        commands.extend([
+            RenderCommand::FillRect {
+                rect: rect(0.0, 0.0, 50.0, 50.0),
+            },
+            RenderCommand::Restore,
+        ]);
+        check(
+            commands,
+            expect![[r#"
+            SetFillColor(Color { rgba: Rgba { r: 235, g: 235, b: 235, a: 255 } })
+            SetStrokeColor(Color { rgba: Rgba { r: 0, g: 0, b: 0, a: 255 } })
+            ClipRect(Rect(200.0x150.0 at (0.0, 0.0)))
+            Save
+            Translate { x: 10.0, y: 10.0 }
+            ClipRect(Rect(50.0x50.0 at (0.0, 0.0)))
+            FillRect { rect: Rect(30.0x30.0 at (-10.0, -10.0)) }
+            Line { start: (55.0, 0.0), end: (55.0, 50.0) }
+            SetStrokeWidth(2.0)
+            Line { start: (51.0, 0.0), end: (51.0, 50.0) }
+            Restore
+        "#]],
+        );
+    }
+
+    #[test]
+    fn culls_draws_outside_transformed_clip() {
+        let mut commands = vec![RenderCommand::SetFillColor(Color::GRAY)];
+        commands.extend(node(100.0, 20.0, 50.0, 50.0));
+        commands.extend([
+            RenderCommand::Rotate { angle: FRAC_PI_4 },
+            RenderCommand::ClipRect(rect(0.0, 0.0, 40.0, 40.0)),
+            RenderCommand::ResetTransform,
+            // Inside the rotated clip rect
+            RenderCommand::DrawEllipse {
+                center: Point::new(100.0, 40.0),
+                radius_x: 10.0,
+                radius_y: 5.0,
+                mode: DrawMode::Fill,
+            },
+            // Outside the rotated clip rect
+            RenderCommand::DrawPath {
+                path: PathData::rect(&rect(10.0, 10.0, 30.0, 30.0)),
+                mode: DrawMode::Fill,
+            },
+            RenderCommand::Restore,
+        ]);
+        check(
+            commands,
+            expect![[r#"
+            SetFillColor(Color { rgba: Rgba { r: 235, g: 235, b: 235, a: 255 } })
+            Save
+            Translate { x: 100.0, y: 20.0 }
+            ClipRect(Rect(50.0x50.0 at (0.0, 0.0)))
+            Rotate { angle: 0.7853982 }
+            ClipRect(Rect(40.0x40.0 at (0.0, 0.0)))
+            ResetTransform
+            DrawEllipse { center: (100.0, 40.0), radius_x: 10.0, radius_y: 5.0, mode: Fill }
+            Restore
+        "#]],
+        );
+    }
+
+    #[test]
+    fn keeps_layers_and_shadows() {
+        let mut commands = vec![
+            RenderCommand::ClipRect(rect(0.0, 0.0, WIDTH as Float, HEIGHT as Float)),
+            RenderCommand::PushLayer { opacity: 0.5 },
+        ];
+        commands.extend(node(10.0, 10.0, 80.0, 80.0));
+        commands.extend([
+            RenderCommand::DrawBoxShadow {
+                rect: rect(10.0, 10.0, 40.0, 40.0),
+                radius: 5.0,
+                offset: Vector::new(3.0, 3.0),
+                blur: 4.0,
+                color: Color::BLACK.with_alpha(128),
+            },
+            RenderCommand::SetFillColor(Color::GRAY),
+            RenderCommand::FillRoundRect {
+                rect: rect(10.0, 10.0, 40.0, 40.0),
+                radius: 5.0,
+            },
+            RenderCommand::Restore,
+            RenderCommand::PopLayer,
+        ]);
+        check(
+            commands,
+            expect![[r#"
+            ClipRect(Rect(200.0x150.0 at (0.0, 0.0)))
+            PushLayer { opacity: 0.5 }
+            Save
+            Translate { x: 10.0, y: 10.0 }
+            ClipRect(Rect(80.0x80.0 at (0.0, 0.0)))
+            DrawBoxShadow { rect: Rect(40.0x40.0 at (10.0, 10.0)), radius: 5.0, offset: (3.0, 3.0), blur: 4.0, color: Color { rgba: Rgba { r: 0, g: 0, b: 0, a: 128 } } }
+            SetFillColor(Color { rgba: Rgba { r: 235, g: 235, b: 235, a: 255 } })
+            FillRoundRect { rect: Rect(40.0x40.0 at (10.0, 10.0)), radius: 5.0 }
+            Restore
+            PopLayer
+        "#]],
+        );
+    }
+}
@@ -0,0 +1,490 @@
+use crate::types::{Float, Point, Rect};
+use std::f32::consts::{FRAC_PI_2, PI};
+use std::fmt::Write;
+
+/// Magic constant for approximating quarter circles with cubic bezier curves
+const KAPPA: Float = 0.552_284_8;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PathSegment {
+    MoveTo(Point),
+    LineTo(Point),
+    QuadTo {
+        control: Point,
+        end: Point,
+    },
+    CubicTo {
+        control1: Point,
+        control2: Point,
+        end: Point,
+    },
+    Close,
+}
+
+/// A vector path made up of move, line, bezier and close segments
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PathData {
+    segments: Vec<PathSegment>,
+}
+
+/// A flattened sub path, i.e. a list of points connected by straight lines
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Polyline {
+    pub points: Vec<Point>,
+    pub closed: bool,
+}
+
+impl PathData {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.segments
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    pub fn move_to(mut self, point: Point) -> Self {
+        self.segments.push(PathSegment::MoveTo(point));
+        self
+    }
+
+    pub fn line_to(mut self, point: Point) -> Self {
+        self.segments.push(PathSegment::LineTo(point));
+        self
+    }
+
+    pub fn quad_to(mut self, control: Point, end: Point) -> Self {
+        self.segments.push(PathSegment::QuadTo { control, end });
+        self
+    }
+
+    pub fn cubic_to(mut self, control1: Point, control2: Point, end: Point) -> Self {
+        self.segments.push(PathSegment::CubicTo {
+            control1,
+            control2,
+            end,
+        });
+        self
+    }
+
+    pub fn close(mut self) -> Self {
+        self.segments.push(PathSegment::Close);
+        self
+    }
+
+    pub fn line(start: Point, end: Point) -> Self {
+        Self::new().move_to(start).line_to(end)
+    }
+
+    pub fn rect(rect: &Rect) -> Self {
+        Self::new()
+            .move_to(rect.min())
+            .line_to(Point::new(rect.max_x(), rect.min_y()))
+            .line_to(rect.max())
+            .line_to(Point::new(rect.min_x(), rect.max_y()))
+            .close()
+    }
+
+    pub fn round_rect(rect: &Rect, radius: Float) -> Self {
+        let radius = radius
+            .min(rect.width() / 2.0)
+            .min(rect.height() / 2.0)
+            .max(0.0);
+        if radius == 0.0 {
+            return Self::rect(rect);
+        }
+        let k = radius * (1.0 - KAPPA);
+        let (x0, y0, x1, y1) = (rect.min_x(), rect.min_y(), rect.max_x(), rect.max_y());
+        Self::new()
+            .move_to(Point::new(x0 + radius, y0))
+            .line_to(Point::new(x1 - radius, y0))
+            .cubic_to(
+                Point::new(x1 - k, y0),
+                Point::new(x1, y0 + k),
+                Point::new(x1, y0 + radius),
+            )
+            .line_to(Point::new(x1, y1 - radius))
+            .cubic_to(
+                Point::new(x1, y1 - k),
+                Point::new(x1 - k, y1),
+                Point::new(x1 - radius, y1),
+            )
+            .line_to(Point::new(x0 + radius, y1))
+            .cubic_to(
+                Point::new(x0 + k, y1),
+                Point::new(x0, y1 - k),
+                Point::new(x0, y1 - radius),
+            )
+            .line_to(Point::new(x0, y0 + radius))
+            .cubic_to(
+                Point::new(x0, y0 + k),
+                Point::new(x0 + k, y0),
+                Point::new(x0 + radius, y0),
+            )
+            .close()
+    }
+
+    pub fn ellipse(center: Point, radius_x: Float, radius_y: Float) -> Self {
+        let (cx, cy) = (center.x, center.y);
+        let (kx, ky) = (radius_x * KAPPA, radius_y * KAPPA);
+        Self::new()
+            .move_to(Point::new(cx + radius_x, cy))
+            .cubic_to(
+                Point::new(cx + radius_x, cy + ky),
+                Point::new(cx + kx, cy + radius_y),
+                Point::new(cx, cy + radius_y),
+            )
+            .cubic_to(
+                Point::new(cx - kx, cy + radius_y),
+                Point::new(cx - radius_x, cy + ky),
+                Point::new(cx - radius_x, cy),
+            )
+            .cubic_to(
+                Point::new(cx - radius_x, cy - ky),
+                Point::new(cx - kx, cy - radius_y),
+                Point::new(cx, cy - radius_y),
+            )
+            .cubic_to(
+                Point::new(cx + kx, cy - radius_y),
+                Point::new(cx + radius_x, cy - ky),
+                Point::new(cx + radius_x, cy),
+            )
+            .close()
+    }
+
+    pub fn circle(center: Point, radius: Float) -> Self {
+        Self::ellipse(center, radius, radius)
+    }
+
+    /// Circular arc going clockwise (in screen space) from `start_angle` to `end_angle`
+    pub fn arc(center: Point, radius: Float, start_angle: Float, end_angle: Float) -> Self {
+        let point_at = |angle: Float| {
+            Point::new(
+                center.x + radius * angle.cos(),
+                center.y + radius * angle.sin(),
+            )
+        };
+        let mut sweep = end_angle - start_angle;
+        if sweep < 0.0 {
+            sweep += 2.0 * PI * (-sweep / (2.0 * PI)).ceil();
+        }
+        let steps = (sweep / FRAC_PI_2).ceil().max(1.0) as usize;
+        let step = sweep / steps as Float;
+        // Control point distance for a cubic approximation of an arc segment
+        let k = 4.0 / 3.0 * (step / 4.0).tan() * radius;
+        let mut path = Self::new().move_to(point_at(start_angle));
+        for index in 0..steps {
+            let a0 = start_angle + step * index as Float;
+            let a1 = a0 + step;
+            let (p0, p1) = (point_at(a0), point_at(a1));
+            path = path.cubic_to(
+                Point::new(p0.x - k * a0.sin(), p0.y + k * a0.cos()),
+                Point::new(p1.x + k * a1.sin(), p1.y - k * a1.cos()),
+                p1,
+            );
+        }
+        path
+    }
+
+    /// Bounding box of all points, including control points
+    pub fn bounds(&self) -> Option<Rect> {
+        let points = self.segments.iter().flat_map(|segment| match segment {
+            PathSegment::MoveTo(point) | PathSegment::LineTo(point) => vec![*point],
+            PathSegment::QuadTo { control, end } => vec![*control, *end],
+            PathSegment::CubicTo {
+                control1,
+                control2,
+                end,
+            } => vec![*control1, *control2, *end],
+            PathSegment::Close => vec![],
+        });
+        let mut points = points.peekable();
+        points.peek()?;
+        Some(Rect::from_points(points))
+    }
+
+    /// Converts the path into SVG path data, i.e. the value of the `d` attribute
+    pub fn to_svg_path_data(&self) -> String {
+        let mut result = String::new();
+        for segment in &self.segments {
+            if !result.is_empty() {
+                result.push(' ');
+            }
+            // Writing to a string is infallible
+            let _ = match segment {
+                PathSegment::MoveTo(point) => write!(result, "M {} {}", point.x, point.y),
+                PathSegment::LineTo(point) => write!(result, "L {} {}", point.x, point.y),
+                PathSegment::QuadTo { control, end } => {
+                    write!(result, "Q {} {} {} {}", control.x, control.y, end.x, end.y)
+                }
+                PathSegment::CubicTo {
+                    control1,
+                    control2,
+                    end,
+                } => write!(
+                    result,
+                    "C {} {} {} {} {} {}",
+                    control1.x, control1.y, control2.x, control2.y, end.x, end.y
+                ),
+                PathSegment::Close => write!(result, "Z"),
+            };
+        }
+        result
+    }
+
+    /// Approximates the path by straight line segments, one polyline per sub path
+    pub fn flatten(&self, tolerance: Float) -> Vec<Polyline> {
+        let mut polylines: Vec<Polyline> = vec![];
+        let mut current = Point::zero();
+        let mut start = Point::zero();
+        fn subdivisions(length: Float, tolerance: Float) -> usize {
+            ((length / tolerance.max(0.01)).sqrt().ceil() as usize).clamp(1, 100)
+        }
+        for segment in &self.segments {
+            match segment {
+                PathSegment::MoveTo(point) => {
+                    polylines.push(Polyline {
+                        points: vec![*point],
+                        closed: false,
+                    });
+                    current = *point;
+                    start = *point;
+                    continue;
+                }
+                PathSegment::Close => {
+                    if let Some(polyline) = polylines.last_mut() {
+                        polyline.closed = true;
+                    }
+                    current = start;
+                    continue;
+                }
+                _ => {}
+            }
+            if polylines.is_empty() {
+                polylines.push(Polyline {
+                    points: vec![current],
+                    closed: false,
+                });
+            }
+            let points = &mut polylines.last_mut().unwrap().points;
+            match segment {
+                PathSegment::LineTo(point) => {
+                    points.push(*point);
+                    current = *point;
+                }
+                PathSegment::QuadTo { control, end } => {
+                    let length = (*control - current).length() + (*end - *control).length();
+                    let steps = subdivisions(length, tolerance);
+                    for step in 1..=steps {
+                        let t = step as Float / steps as Float;
+                        let mt = 1.0 - t;
+                        points.push(Point::new(
+                            mt * mt * current.x + 2.0 * mt * t * control.x + t * t * end.x,
+                            mt * mt * current.y + 2.0 * mt * t * control.y + t * t * end.y,
+                        ));
+                    }
+                    current = *end;
+                }
+                PathSegment::CubicTo {
+                    control1,
+                    control2,
+                    end,
+                } => {
+                    let length = (*control1 - current).length()
+                        + (*control2 - *control1).length()
+                        + (*end - *control2).length();
+                    let steps = subdivisions(length, tolerance);
+                    for step in 1..=steps {
+                        let t = step as Float / steps as Float;
+                        let mt = 1.0 - t;
+                        let (a, b, c, d) =
+                            (mt * mt * mt, 3.0 * mt * mt * t, 3.0 * mt * t * t, t * t * t);
+                        points.push(Point::new(
+                            a * current.x + b * control1.x + c * control2.x + d * end.x,
+                            a * current.y + b * control1.y + c * control2.y + d * end.y,
+                        ));
+                    }
+                    current = *end;
+                }
+                PathSegment::MoveTo(_) | PathSegment::Close => unreachable!(),
+            }
+        }
+        polylines
+    }
+}
+
+/// Splits polylines into dashes according to the given dash pattern
+///
+/// The pattern alternates between dash and gap lengths, starting `offset` units into the pattern.
+/// An empty pattern (or one without any positive length) leaves the polylines untouched.
+pub fn dash_polylines(polylines: &[Polyline], pattern: &[Float], offset: Float) -> Vec<Polyline> {
+    let pattern_length: Float = pattern.iter().map(|length| length.max(0.0)).sum();
+    if pattern_length <= 0.0 {
+        return polylines.to_vec();
+    }
+    let mut dashes = vec![];
+    for polyline in polylines {
+        let mut points = polyline.points.clone();
+        if polyline.closed {
+            if let Some(first) = points.first().copied() {
+                points.push(first);
+            }
+        }
+        // Find the position inside the pattern at the start of this sub path
+        let mut pattern_index = 0;
+        let mut remaining = pattern[0].max(0.0);
+        let mut skip = offset.rem_euclid(pattern_length);
+        while skip > 0.0 {
+            if skip < remaining {
+                remaining -= skip;
+                skip = 0.0;
+            } else {
+                skip -= remaining;
+                pattern_index = (pattern_index + 1) % pattern.len();
+                remaining = pattern[pattern_index].max(0.0);
+            }
+        }
+        let mut current_dash: Option<Vec<Point>> = None;
+        for window in points.windows(2) {
+            let (mut from, to) = (window[0], window[1]);
+            let mut segment_length = (to - from).length();
+            while segment_length > 0.0 {
+                let is_dash = pattern_index % 2 == 0;
+                let advance = remaining.min(segment_length);
+                let next = from.lerp(to, advance / segment_length);
+                if is_dash {
+                    current_dash.get_or_insert_with(|| vec![from]).push(next);
+                }
+                from = next;
+                segment_length -= advance;
+                remaining -= advance;
+                if remaining <= 0.0 {
+                    if let Some(points) = current_dash.take() {
+                        dashes.push(Polyline {
+                            points,
+                            closed: false,
+                        });
+                    }
+                    pattern_index = (pattern_index + 1) % pattern.len();
+                    remaining = pattern[pattern_index].max(0.0);
+                }
+            }
+        }
+        if let Some(points) = current_dash.take() {
+            dashes.push(Polyline {
+                points,
+                closed: false,
+            });
+        }
+    }
+    dashes
+}
+
+impl From<Polyline> for PathData {
+    fn from(polyline: Polyline) -> Self {
+        let mut points = polyline.points.into_iter();
+        let mut path = PathData::new();
+        if let Some(first) = points.next() {
+            path = path.move_to(first);
+        }
+        for point in points {
+            path = path.line_to(point);
+        }
+        if polyline.closed {
+            path = path.close();
+        }
+        path
+    }
+}
+
+impl FromIterator<PathData> for PathData {
+    fn from_iter<T: IntoIterator<Item = PathData>>(iter: T) -> Self {
+        PathData {
+            segments: iter.into_iter().flat_map(|path| path.segments).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Size;
+    use expect_test::expect;
+
+    #[test]
+    fn svg_path_data() {
+        let path = PathData::new()
+            .move_to(Point::new(1.0, 2.0))
+            .line_to(Point::new(3.0, 4.0))
+            .quad_to(Point::new(5.0, 6.0), Point::new(7.0, 8.0))
+            .cubic_to(
+                Point::new(9.0, 10.0),
+                Point::new(11.0, 12.0),
+                Point::new(13.0, 14.5),
+            )
+            .close();
+        expect!["M 1 2 L 3 4 Q 5 6 7 8 C 9 10 11 12 13 14.5 Z"].assert_eq(&path.to_svg_path_data());
+    }
+
+    #[test]
+    fn bounds() {
+        let path = PathData::new()
+            .move_to(Point::new(1.0, 2.0))
+            .quad_to(Point::new(-5.0, 6.0), Point::new(7.0, 8.0));
+        assert_eq!(
+            path.bounds(),
+            Some(Rect::new(Point::new(-5.0, 2.0), Size::new(12.0, 6.0)))
+        );
+        assert_eq!(PathData::new().bounds(), None);
+    }
+
+    #[test]
+    fn flatten_rect() {
+        let polylines =
+            PathData::rect(&Rect::new(Point::new(0.0, 0.0), Size::new(2.0, 1.0))).flatten(0.1);
+        assert_eq!(polylines.len(), 1);
+        assert!(polylines[0].closed);
+        assert_eq!(polylines[0].points.len(), 4);
+    }
+
+    #[test]
+    fn flatten_circle_stays_on_circle() {
+        let polylines = PathData::circle(Point::new(10.0, 10.0), 5.0).flatten(0.1);
+        for point in &polylines[0].points {
+            let distance = (*point - Point::new(10.0, 10.0)).length();
+            assert!((distance - 5.0).abs() < 0.01, "distance was {distance}");
+        }
+    }
+
+    #[test]
+    fn dash_line() {
+        let polylines = PathData::line(Point::new(0.0, 0.0), Point::new(10.0, 0.0)).flatten(0.1);
+        let dashes = dash_polylines(&polylines, &[3.0, 2.0], 0.0);
+        let xs: Vec<_> = dashes
+            .iter()
+            .map(|dash| (dash.points[0].x, dash.points.last().unwrap().x))
+            .collect();
+        assert_eq!(xs, vec![(0.0, 3.0), (5.0, 8.0)]);
+    }
+
+    #[test]
+    fn dash_line_with_offset() {
+        let polylines = PathData::line(Point::new(0.0, 0.0), Point::new(10.0, 0.0)).flatten(0.1);
+        let dashes = dash_polylines(&polylines, &[3.0, 2.0], 4.0);
+        let xs: Vec<_> = dashes
+            .iter()
+            .map(|dash| (dash.points[0].x, dash.points.last().unwrap().x))
+            .collect();
+        assert_eq!(xs, vec![(1.0, 4.0), (6.0, 9.0)]);
+    }
+
+    #[test]
+    fn dash_empty_pattern_is_solid() {
+        let polylines = PathData::line(Point::new(0.0, 0.0), Point::new(10.0, 0.0)).flatten(0.1);
+        assert_eq!(dash_polylines(&polylines, &[], 0.0), polylines);
+    }
+}
@@ -0,0 +1,131 @@
+use crate::render::command::RenderCommand;
+use crate::types::{Point, Rect, Transform};
+
+/// Tracks the current transform and clip bounds while render commands are emitted or replayed
+#[derive(Clone, Debug)]
+pub struct TransformState {
+    transform: Transform,
+    /// Axis aligned bounds of the current clip region in window coordinates
+    clip_bounds: Option<Rect>,
+    save_stack: Vec<(Transform, Option<Rect>)>,
+}
+
+impl Default for TransformState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TransformState {
+    pub fn new() -> Self {
+        Self {
+            transform: Transform::identity(),
+            clip_bounds: None,
+            save_stack: vec![],
+        }
+    }
+
+    /// Updates the state according to the given command, non-transform commands are ignored
+    pub fn apply(&mut self, command: &RenderCommand) {
+        match command {
+            RenderCommand::Save | RenderCommand::PushLayer { .. } => {
+                self.save_stack.push((self.transform, self.clip_bounds));
+            }
+            RenderCommand::Restore | RenderCommand::PopLayer => {
+                if let Some((transform, clip_bounds)) = self.save_stack.pop() {
+                    self.transform = transform;
+                    self.clip_bounds = clip_bounds;
+                }
+            }
+            RenderCommand::ResetTransform => {
+                self.transform = Transform::identity();
+            }
+            RenderCommand::ClipRect(rect) => {
+                let bounds = self.transform.outer_transformed_rect(rect);
+                self.clip_bounds = Some(match self.clip_bounds {
+                    Some(clip_bounds) => clip_bounds.intersection(&bounds).unwrap_or_default(),
+                    None => bounds,
+                });
+            }
+            command => {
+                if let Some(local_transform) = command.local_transform() {
+                    self.transform = local_transform.then(&self.transform);
+                }
+            }
+        }
+    }
+
+    /// Transform from the current local coordinates to window coordinates
+    pub fn transform(&self) -> &Transform {
+        &self.transform
+    }
+
+    pub fn clip_bounds(&self) -> Option<&Rect> {
+        self.clip_bounds.as_ref()
+    }
+
+    /// Converts a point in window coordinates to the current local coordinates
+    pub fn to_local(&self, point: Point) -> Option<Point> {
+        Some(self.transform.inverse()?.transform_point(point))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::FRAC_PI_2;
+
+    fn assert_close(actual: Point, expected: Point) {
+        assert!(
+            (actual - expected).length() < 1e-4,
+            "{actual:?} != {expected:?}"
+        );
+    }
+
+    #[test]
+    fn transforms_compose_in_command_order() {
+        let mut state = TransformState::new();
+        state.apply(&RenderCommand::Translate { x: 10.0, y: 20.0 });
+        state.apply(&RenderCommand::Scale { x: 2.0, y: 3.0 });
+        state.apply(&RenderCommand::Rotate { angle: FRAC_PI_2 });
+        assert_close(
+            state.transform().transform_point(Point::new(1.0, 0.0)),
+            Point::new(10.0, 23.0),
+        );
+        assert_close(
+            state.to_local(Point::new(10.0, 23.0)).unwrap(),
+            Point::new(1.0, 0.0),
+        );
+    }
+
+    #[test]
+    fn save_and_restore() {
+        let mut state = TransformState::new();
+        state.apply(&RenderCommand::Save);
+        state.apply(&RenderCommand::Translate { x: 10.0, y: 20.0 });
+        state.apply(&RenderCommand::ClipRect(Rect::from_size((5.0, 5.0).into())));
+        assert_eq!(
+            state.clip_bounds(),
+            Some(&Rect::new(Point::new(10.0, 20.0), (5.0, 5.0).into()))
+        );
+        state.apply(&RenderCommand::Restore);
+        assert_eq!(state.transform(), &Transform::identity());
+        assert_eq!(state.clip_bounds(), None);
+    }
+
+    #[test]
+    fn clip_rects_intersect_and_survive_reset_transform() {
+        let mut state = TransformState::new();
+        state.apply(&RenderCommand::ClipRect(Rect::from_size(
+            (100.0, 100.0).into(),
+        )));
+        state.apply(&RenderCommand::Rotate { angle: FRAC_PI_2 });
+        state.apply(&RenderCommand::ClipRect(Rect::from_size(
+            (50.0, 50.0).into(),
+        )));
+        state.apply(&RenderCommand::ResetTransform);
+        assert_eq!(state.transform(), &Transform::identity());
+        let clip_bounds = state.clip_bounds().unwrap();
+        assert!(clip_bounds.size.is_empty());
+    }
+}
@@ -4,12 +4,12 @@ use std::io::{BufRead, BufReader, Seek};
 use std::path::PathBuf;
 use std::sync::Arc;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct Resource {
     inner: Arc<ResourceInner>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 struct ResourceInner {
     path: PathBuf,
 }
@@ -1 +1 @@
-mod render_svg;
+pub mod rasterize;
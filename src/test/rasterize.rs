@@ -0,0 +1,289 @@
+//! Minimal software rasterizer for render commands, used to compare rendered output in tests
+//!
+//! Text and images are not rendered, box shadows are drawn without blur and radial gradients
+//! ignore their inner radius.
+
+use crate::render::command::{DrawMode, Gradient, GradientStop, LineCap, LineJoin, RenderCommand};
+use crate::render::path::{PathData, PathSegment};
+use crate::types::{Color, Float, Transform};
+use tiny_skia::{
+    ClipMask, FillRule, Paint, Pixmap, PixmapPaint, Shader, SpreadMode, Stroke, StrokeDash,
+};
+
+#[derive(Clone)]
+struct State {
+    transform: Transform,
+    clip_mask: Option<ClipMask>,
+    fill: Shader<'static>,
+    stroke: Option<Color>,
+    stroke_width: Float,
+    line_cap: LineCap,
+    line_join: LineJoin,
+    line_dash: Vec<Float>,
+    line_dash_offset: Float,
+}
+
+pub fn rasterize(width: u32, height: u32, commands: &[RenderCommand]) -> Pixmap {
+    let mut pixmaps = vec![(Pixmap::new(width, height).unwrap(), 1.0)];
+    let mut state = State {
+        transform: Transform::identity(),
+        clip_mask: None,
+        fill: Shader::SolidColor(tiny_skia::Color::BLACK),
+        stroke: None,
+        stroke_width: 1.0,
+        line_cap: LineCap::default(),
+        line_join: LineJoin::default(),
+        line_dash: vec![],
+        line_dash_offset: 0.0,
+    };
+    let mut state_stack = vec![];
+    for command in commands {
+        let (pixmap, _) = pixmaps.last_mut().unwrap();
+        match command {
+            RenderCommand::Save => state_stack.push(state.clone()),
+            RenderCommand::Restore => state = state_stack.pop().unwrap(),
+            RenderCommand::SetStrokeColor(color) => state.stroke = Some(*color),
+            RenderCommand::SetStrokeWidth(width) => state.stroke_width = *width,
+            RenderCommand::SetFillColor(color) => state.fill = Shader::SolidColor(to_color(color)),
+            RenderCommand::SetFillGradient(gradient) => state.fill = to_shader(gradient),
+            RenderCommand::SetLineCap(line_cap) => state.line_cap = *line_cap,
+            RenderCommand::SetLineJoin(line_join) => state.line_join = *line_join,
+            RenderCommand::SetLineDash { pattern, offset } => {
+                state.line_dash = pattern.clone();
+                state.line_dash_offset = *offset;
+            }
+            RenderCommand::FillRect { rect } => {
+                draw(pixmap, &state, &PathData::rect(rect), DrawMode::Fill)
+            }
+            RenderCommand::FillRoundRect { rect, radius } => draw(
+                pixmap,
+                &state,
+                &PathData::round_rect(rect, *radius),
+                DrawMode::FillAndStroke,
+            ),
+            RenderCommand::Line { start, end } => draw(
+                pixmap,
+                &state,
+                &PathData::line(*start, *end),
+                DrawMode::Stroke,
+            ),
+            RenderCommand::Arc {
+                center,
+                radius,
+                start_angle,
+                end_angle,
+            } => draw(
+                pixmap,
+                &state,
+                &PathData::arc(*center, *radius, *start_angle, *end_angle),
+                DrawMode::Stroke,
+            ),
+            RenderCommand::DrawPath { path, mode } => draw(pixmap, &state, path, *mode),
+            RenderCommand::DrawRect { rect, mode } => {
+                draw(pixmap, &state, &PathData::rect(rect), *mode)
+            }
+            RenderCommand::DrawRoundRect { rect, radius, mode } => {
+                draw(pixmap, &state, &PathData::round_rect(rect, *radius), *mode)
+            }
+            RenderCommand::DrawCircle {
+                center,
+                radius,
+                mode,
+            } => draw(pixmap, &state, &PathData::circle(*center, *radius), *mode),
+            RenderCommand::DrawEllipse {
+                center,
+                radius_x,
+                radius_y,
+                mode,
+            } => draw(
+                pixmap,
+                &state,
+                &PathData::ellipse(*center, *radius_x, *radius_y),
+                *mode,
+            ),
+            RenderCommand::DrawBoxShadow {
+                rect,
+                radius,
+                offset,
+                color,
+                ..
+            } => {
+                let shadow_state = State {
+                    fill: Shader::SolidColor(to_color(color)),
+                    ..state.clone()
+                };
+                let path = PathData::round_rect(&rect.translate(*offset), *radius);
+                draw(pixmap, &shadow_state, &path, DrawMode::Fill);
+            }
+            RenderCommand::PushLayer { opacity } => {
+                state_stack.push(state.clone());
+                pixmaps.push((Pixmap::new(width, height).unwrap(), *opacity));
+            }
+            RenderCommand::PopLayer => {
+                state = state_stack.pop().unwrap();
+                let (layer, opacity) = pixmaps.pop().unwrap();
+                let paint = PixmapPaint {
+                    opacity,
+                    ..PixmapPaint::default()
+                };
+                let (pixmap, _) = pixmaps.last_mut().unwrap();
+                pixmap.draw_pixmap(
+                    0,
+                    0,
+                    layer.as_ref(),
+                    &paint,
+                    tiny_skia::Transform::identity(),
+                    None,
+                );
+            }
+            RenderCommand::ResetTransform => state.transform = Transform::identity(),
+            RenderCommand::ClipRect(rect) => {
+                let Some(path) = to_path(&PathData::rect(rect))
+                    .and_then(|path| path.transform(to_transform(&state.transform)))
+                else {
+                    continue;
+                };
+                match &mut state.clip_mask {
+                    Some(clip_mask) => {
+                        clip_mask.intersect_path(&path, FillRule::Winding, true);
+                    }
+                    None => {
+                        let mut clip_mask = ClipMask::new();
+                        clip_mask.set_path(width, height, &path, FillRule::Winding, true);
+                        state.clip_mask = Some(clip_mask);
+                    }
+                }
+            }
+            command => {
+                if let Some(local_transform) = command.local_transform() {
+                    state.transform = local_transform.then(&state.transform);
+                }
+            }
+        }
+    }
+    pixmaps.pop().unwrap().0
+}
+
+fn draw(pixmap: &mut Pixmap, state: &State, path: &PathData, mode: DrawMode) {
+    let Some(path) = to_path(path) else {
+        return;
+    };
+    let transform = to_transform(&state.transform);
+    let clip_mask = state.clip_mask.as_ref();
+    if mode.fills() {
+        let paint = Paint {
+            shader: state.fill.clone(),
+            ..Paint::default()
+        };
+        pixmap.fill_path(&path, &paint, FillRule::Winding, transform, clip_mask);
+    }
+    let stroke_color = state
+        .stroke
+        .filter(|_| mode.strokes() && state.stroke_width > 0.0);
+    if let Some(color) = stroke_color {
+        let paint = Paint {
+            shader: Shader::SolidColor(to_color(&color)),
+            ..Paint::default()
+        };
+        let mut dash_pattern = state.line_dash.clone();
+        if dash_pattern.len() % 2 == 1 {
+            dash_pattern.extend_from_within(..);
+        }
+        let stroke = Stroke {
+            width: state.stroke_width,
+            line_cap: match state.line_cap {
+                LineCap::Butt => tiny_skia::LineCap::Butt,
+                LineCap::Round => tiny_skia::LineCap::Round,
+                LineCap::Square => tiny_skia::LineCap::Square,
+            },
+            line_join: match state.line_join {
+                LineJoin::Miter => tiny_skia::LineJoin::Miter,
+                LineJoin::Round => tiny_skia::LineJoin::Round,
+                LineJoin::Bevel => tiny_skia::LineJoin::Bevel,
+            },
+            dash: StrokeDash::new(dash_pattern, state.line_dash_offset),
+            ..Stroke::default()
+        };
+        pixmap.stroke_path(&path, &paint, &stroke, transform, clip_mask);
+    }
+}
+
+fn to_path(path: &PathData) -> Option<tiny_skia::Path> {
+    let mut builder = tiny_skia::PathBuilder::new();
+    for segment in path.segments() {
+        match segment {
+            PathSegment::MoveTo(point) => builder.move_to(point.x, point.y),
+            PathSegment::LineTo(point) => builder.line_to(point.x, point.y),
+            PathSegment::QuadTo { control, end } => {
+                builder.quad_to(control.x, control.y, end.x, end.y)
+            }
+            PathSegment::CubicTo {
+                control1,
+                control2,
+                end,
+            } => builder.cubic_to(control1.x, control1.y, control2.x, control2.y, end.x, end.y),
+            PathSegment::Close => builder.close(),
+        }
+    }
+    builder.finish()
+}
+
+fn to_transform(transform: &Transform) -> tiny_skia::Transform {
+    let [a, b, c, d, e, f] = transform.to_array();
+    tiny_skia::Transform::from_row(a, b, c, d, e, f)
+}
+
+fn to_color(color: &Color) -> tiny_skia::Color {
+    let rgba = color.rgba;
+    tiny_skia::Color::from_rgba8(rgba.r, rgba.g, rgba.b, rgba.a)
+}
+
+fn to_shader(gradient: &Gradient) -> Shader<'static> {
+    let to_stops = |stops: &[GradientStop]| {
+        stops
+            .iter()
+            .map(|stop| tiny_skia::GradientStop::new(stop.offset, to_color(&stop.color)))
+            .collect()
+    };
+    let shader = match gradient {
+        Gradient::Linear { start, end, stops } => tiny_skia::LinearGradient::new(
+            tiny_skia::Point::from_xy(start.x, start.y),
+            tiny_skia::Point::from_xy(end.x, end.y),
+            to_stops(stops),
+            SpreadMode::Pad,
+            tiny_skia::Transform::identity(),
+        ),
+        Gradient::Radial {
+            center,
+            outer_radius,
+            stops,
+            ..
+        } => tiny_skia::RadialGradient::new(
+            tiny_skia::Point::from_xy(center.x, center.y),
+            tiny_skia::Point::from_xy(center.x, center.y),
+            *outer_radius,
+            to_stops(stops),
+            SpreadMode::Pad,
+            tiny_skia::Transform::identity(),
+        ),
+    };
+    shader.unwrap_or(Shader::SolidColor(tiny_skia::Color::TRANSPARENT))
+}
+
+/// Asserts that two pixmaps are identical, reporting the first differing pixel otherwise
+pub fn assert_pixmaps_eq(actual: &Pixmap, expected: &Pixmap) {
+    assert_eq!(
+        (actual.width(), actual.height()),
+        (expected.width(), expected.height())
+    );
+    if let Some((index, (actual_pixel, expected_pixel))) = actual
+        .pixels()
+        .iter()
+        .zip(expected.pixels())
+        .enumerate()
+        .find(|(_, (actual, expected))| actual != expected)
+    {
+        let (x, y) = (index as u32 % actual.width(), index as u32 / actual.width());
+        panic!("Pixel ({x}, {y}) differs: {actual_pixel:?} != {expected_pixel:?}");
+    }
+}
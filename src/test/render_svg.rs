@@ -1,3 +0,0 @@
-use crate::nodes::elements::kind::Element;
-
-pub fn test_render_svg<E: Element>(name: &str) {}
@@ -1,3 +1,4 @@
+use crate::bail;
 use crate::result::ViuiResult;
 use rgb::RGBA8;
 
@@ -6,8 +7,11 @@ pub type Float = f32;
 pub type Rect = euclid::Rect<Float, ScreenSpace>;
 pub type Point = euclid::Point2D<Float, ScreenSpace>;
 pub type Size = euclid::Size2D<Float, ScreenSpace>;
+pub type Vector = euclid::Vector2D<Float, ScreenSpace>;
+pub type SideOffsets = euclid::SideOffsets2D<Float, ScreenSpace>;
+pub type Transform = euclid::Transform2D<Float, ScreenSpace, ScreenSpace>;
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub struct Color {
     pub rgba: RGBA8,
 }
@@ -28,12 +32,52 @@ impl Color {
         }
     }
 
+    pub const TRANSPARENT: Self = Self {
+        rgba: RGBA8::new(0, 0, 0, 0),
+    };
+
+    /// Returns the same color with the given alpha value
+    pub const fn with_alpha(self, alpha: u8) -> Self {
+        let RGBA8 { r, g, b, .. } = self.rgba;
+        Self {
+            rgba: RGBA8::new(r, g, b, alpha),
+        }
+    }
+
     pub fn gray(whiteness: u8) -> Self {
         Self {
             rgba: RGBA8::new(whiteness, whiteness, whiteness, 255),
         }
     }
 
+    /// Parses a hex color like `#f80`, `#ff8800` or `#ff880080`
+    pub fn parse(value: &str) -> ViuiResult<Self> {
+        let Some(hex) = value.strip_prefix('#').filter(|hex| hex.is_ascii()) else {
+            bail!(
+                "Invalid color '{}', expected a hex color like '#ff8800'",
+                value
+            );
+        };
+        let channel = |index: usize, length: usize| {
+            let digits = &hex[index * length..(index + 1) * length];
+            let channel = u8::from_str_radix(digits, 16).ok()?;
+            Some(if length == 1 { channel * 17 } else { channel })
+        };
+        let channels: Option<Vec<u8>> = match hex.len() {
+            3 => (0..3).map(|index| channel(index, 1)).collect(),
+            6 | 8 => (0..hex.len() / 2).map(|index| channel(index, 2)).collect(),
+            _ => None,
+        };
+        match channels.as_deref() {
+            Some(&[red, green, blue]) => Ok(Self::rgb(red, green, blue)),
+            Some(&[red, green, blue, alpha]) => Ok(Self::rgba(red, green, blue, alpha)),
+            _ => bail!(
+                "Invalid color '{}', expected a hex color like '#ff8800'",
+                value
+            ),
+        }
+    }
+
     /// Constructs a `Color` from hue, saturation, and lightness (HSL) values.
     ///
     /// # Arguments
@@ -51,3 +95,21 @@ impl Color {
         Ok(Self::rgb(r, g, b))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_color() {
+        assert_eq!(Color::parse("#f80").unwrap(), Color::rgb(255, 136, 0));
+        assert_eq!(Color::parse("#ff8800").unwrap(), Color::rgb(255, 136, 0));
+        assert_eq!(
+            Color::parse("#ff880080").unwrap(),
+            Color::rgba(255, 136, 0, 128)
+        );
+        assert!(Color::parse("ff8800").is_err());
+        assert!(Color::parse("#ff88").is_err());
+        assert!(Color::parse("#gg8800").is_err());
+    }
+}
@@ -1,58 +1,102 @@
 use crate::arenal::Arenal;
 use crate::ast::eval::eval;
-use crate::ast::nodes::{ComponentAst, ExpressionAst, ItemAst, ItemDefinition};
+use crate::ast::nodes::{ComponentAst, ExpressionAst, ExpressionKind, ItemAst, ItemDefinition};
 use crate::ast::parser::parse_ui;
 use crate::ast::value::ExpressionValue;
 use crate::bail;
 use crate::infrastructure::binding_stack::BindingStack;
 use crate::infrastructure::font_pool::FontPool;
 use crate::infrastructure::image_pool::ImagePool;
-use crate::infrastructure::layout_context::LayoutContext;
+use crate::infrastructure::layout_context::{LayoutContext, MeasureContext};
 use crate::infrastructure::styling::Styling;
-use crate::nodes::data::{LayoutInfo, NodeData, NodeIdx, PropExpression};
+use crate::nodes::common::CommonProps;
+use crate::nodes::data::{EventMapping, LayoutInfo, NodeData, NodeIdx, PropExpression};
+use crate::nodes::elements::aligned_box::AlignedBoxElement;
 use crate::nodes::elements::button::ButtonElement;
+use crate::nodes::elements::checkbox::CheckboxElement;
+use crate::nodes::elements::collapsible::CollapsibleElement;
+use crate::nodes::elements::dialog::{DialogElement, PopupElement, PANEL_PADDING};
+use crate::nodes::elements::dropdown::DropdownElement;
+use crate::nodes::elements::grid::GridElement;
 use crate::nodes::elements::hstack::HStackElement;
 use crate::nodes::elements::image::ImageElement;
-use crate::nodes::elements::kind::{Element, LayoutConstraints};
+use crate::nodes::elements::kind::{
+    Alignment, Element, ForCollection, ForValue, LayoutConstraints, UpdateContext,
+};
 use crate::nodes::elements::knob::KnobElement;
 use crate::nodes::elements::label::LabelElement;
+use crate::nodes::elements::list::ListElement;
+use crate::nodes::elements::number_input::NumberInputElement;
+use crate::nodes::elements::overlay::OverlayElement;
+use crate::nodes::elements::progress::ProgressElement;
+use crate::nodes::elements::radio::{RadioElement, RadioGroupElement};
+use crate::nodes::elements::scroll::{clamp_scroll_offset, ScrollElement, SCROLLBAR_WIDTH};
+use crate::nodes::elements::shortcut::{ShortcutElement, ShortcutElementProps};
+use crate::nodes::elements::slider::SliderElement;
+use crate::nodes::elements::spacer::SpacerElement;
 use crate::nodes::elements::spinner::SpinnerElement;
+use crate::nodes::elements::switch::SwitchElement;
+use crate::nodes::elements::table::{
+    TableCellElement, TableColumnElement, TableElement, TableRowElement,
+};
+use crate::nodes::elements::tabs::{TabElement, TabsElement};
 use crate::nodes::elements::textinput::TextInputElement;
-use crate::nodes::events::{InputEvent, MouseEventKind, UiEvent, UiEventKind};
+use crate::nodes::elements::tree::{TreeElement, TreeRowElement};
+use crate::nodes::elements::vstack::VStackElement;
+use crate::nodes::events::{
+    EventPhase, InputEvent, KeyCombination, KeyEventKind, KeyInput, KeyboardKey, MouseEventKind,
+    UiEvent, UiEventKind,
+};
+use crate::nodes::hit_index::HitIndex;
 use crate::nodes::item::{BlockItem, ForItem, IfItem, ItemIdx, NodeItem, NodeItemKind};
+use crate::nodes::layout::LayoutProps;
+use crate::nodes::layout_tree::{LayoutTree, NodeConstraints};
 use crate::nodes::registry::NodeRegistry;
 use crate::nodes::types::NodeEvents;
 use crate::observable_state::ObservableState;
 use crate::render::backend::RenderBackendParameters;
+use crate::render::cache::{CachedNodeCommands, DamageRect, RenderCache};
 use crate::render::command::RenderCommand;
 use crate::render::context::RenderContext;
+use crate::render::optimize::optimize_render_commands;
 use crate::render::parameters::RenderParameters;
 use crate::resource::Resource;
 use crate::result::{context, ViuiResult};
-use crate::types::{Point, Rect, Size};
+use crate::types::{Float, Point, Rect, Size, Vector};
 use bevy_reflect::{
-    DynamicEnum, DynamicTuple, DynamicVariant, FromReflect, GetPath, Reflect, ReflectRef, TypeInfo,
-    Typed, VariantInfo,
+    DynamicEnum, DynamicTuple, DynamicVariant, FromReflect, GetPath, List, Reflect, ReflectRef,
+    TypeInfo, Typed, VariantInfo,
 };
 use crossbeam_channel::{select, tick, Receiver, Sender};
-use itertools::{EitherOrBoth, Itertools};
 use log::debug;
 use notify::{RecommendedWatcher, RecursiveMode};
 use notify_debouncer_mini::{new_debouncer, DebounceEventResult, Debouncer};
 use serde::de::DeserializeOwned;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::fmt::Debug;
 use std::fs::File;
 use std::io::Read;
-use std::mem::take;
+use std::mem::{replace, take};
 use std::ops::IndexMut;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::thread;
 use std::time::{Duration, Instant};
-use taffy::prelude::length;
-use taffy::{FlexDirection, Style, TaffyTree};
+use taffy::prelude::{auto, fr, length, line, percent};
+use taffy::{
+    AlignItems, AvailableSpace, Display, FlexDirection, JustifyContent, Overflow, Position, Style,
+};
 use tracing::error;
 
+/// Layout passes per frame, repeated while nodes request them, e.g. lists instantiating rows that
+/// turned out to be visible
+const MAXIMUM_LAYOUT_PASSES: usize = 3;
+/// Time the pointer has to rest on a node before its tooltip is shown
+const TOOLTIP_DELAY: Duration = Duration::from_millis(600);
+/// Distance of the tooltip from the pointer
+const TOOLTIP_OFFSET: Float = 16.0;
+const TOOLTIP_PADDING: Float = 6.0;
+
 pub type ApplicationEventHandler = Box<dyn Fn(&mut ObservableState, &dyn Reflect) + Send>;
 pub type MessageStringToEnumConverter = Box<dyn Fn(&str) -> ViuiResult<ExpressionValue> + Send>;
 
@@ -74,7 +118,25 @@ pub struct UI {
     file_watcher: Debouncer<RecommendedWatcher>,
     root_node_file: PathBuf,
     active_nodes: Vec<NodeIdx>,
+    /// Node under the pointer and its ancestors
+    hovered_nodes: Vec<NodeIdx>,
+    /// Node receiving keyboard input
+    focused_node: Option<NodeIdx>,
+    /// Shortcuts of the shown `shortcut` nodes, in document order
+    shortcuts: Vec<Shortcut>,
+    /// Shown dialogs and popups in document order, the last one is on top
+    layers: Vec<NodeIdx>,
+    /// Layers that took the focus, with the node focused before
+    layer_focus: Vec<(NodeIdx, Option<NodeIdx>)>,
+    /// Tooltip of the hovered node, shown after a delay
+    tooltip: Option<Tooltip>,
+    hit_index: HitIndex,
     animated_nodes: Vec<NodeIdx>,
+    /// Whether nodes asked to be laid out again on the next frame
+    animated_layout: bool,
+    /// Whether nodes requested another layout pass in the last one, see
+    /// [`crate::nodes::elements::kind::LayoutUpdateContext::request_layout_pass`]
+    needs_layout_pass: bool,
     image_pool: ImagePool,
     font_pool: FontPool,
     start: Instant,
@@ -85,6 +147,8 @@ struct RenderBackend {
     render_backend_sender: Sender<RenderBackendMessage>,
     maximum_font_index_loaded: usize,
     window_size: Size,
+    render_cache: RenderCache,
+    layout_tree: LayoutTree,
 }
 
 pub struct RenderBackendMessage {
@@ -120,9 +184,36 @@ impl UI {
         node_registry.register_node::<TextInputElement>();
         node_registry.register_node::<ButtonElement>();
         node_registry.register_node::<KnobElement>();
+        node_registry.register_node::<SliderElement>();
+        node_registry.register_node::<NumberInputElement>();
+        node_registry.register_node::<CheckboxElement>();
+        node_registry.register_node::<SwitchElement>();
+        node_registry.register_node::<RadioGroupElement>();
+        node_registry.register_node::<RadioElement>();
+        node_registry.register_node::<DropdownElement>();
+        node_registry.register_node::<DialogElement>();
+        node_registry.register_node::<PopupElement>();
+        node_registry.register_node::<TabsElement>();
+        node_registry.register_node::<TabElement>();
+        node_registry.register_node::<CollapsibleElement>();
+        node_registry.register_node::<TableElement>();
+        node_registry.register_node::<TableColumnElement>();
+        node_registry.register_node::<TableRowElement>();
+        node_registry.register_node::<TableCellElement>();
+        node_registry.register_node::<TreeElement>();
+        node_registry.register_node::<TreeRowElement>();
         node_registry.register_node::<HStackElement>();
+        node_registry.register_node::<VStackElement>();
+        node_registry.register_node::<GridElement>();
+        node_registry.register_node::<SpacerElement>();
+        node_registry.register_node::<OverlayElement>();
+        node_registry.register_node::<AlignedBoxElement>();
+        node_registry.register_node::<ScrollElement>();
+        node_registry.register_node::<ListElement>();
         node_registry.register_node::<ImageElement>();
         node_registry.register_node::<SpinnerElement>();
+        node_registry.register_node::<ProgressElement>();
+        node_registry.register_node::<ShortcutElement>();
         let mut font_pool = FontPool::new();
         font_pool.load_font(Resource::from_path("assets/fonts/OpenSans-Regular.ttf"))?;
         Ok(UI {
@@ -144,12 +235,21 @@ impl UI {
             file_watcher,
             root_node_file: Default::default(),
             active_nodes: Default::default(),
+            hovered_nodes: Default::default(),
+            focused_node: None,
+            shortcuts: vec![],
+            layers: vec![],
+            layer_focus: vec![],
+            tooltip: None,
+            hit_index: Default::default(),
             root_item_idx: Default::default(),
             root_node_idx: Default::default(),
             image_pool: Default::default(),
             font_pool,
             start: Instant::now(),
             animated_nodes: Default::default(),
+            animated_layout: false,
+            needs_layout_pass: false,
             //styling: Styling::light(),
             styling: Styling::dark(),
         })
@@ -173,7 +273,9 @@ impl UI {
                 match variant_info {
                     VariantInfo::Unit(_unit_info) => {
                         let dynamic_enum = DynamicEnum::new(variant_name, DynamicVariant::Unit);
-                        let message = MESSAGE::from_reflect(&dynamic_enum).unwrap();
+                        let Some(message) = MESSAGE::from_reflect(&dynamic_enum) else {
+                            bail!("Could not create message {}", variant_name);
+                        };
                         Ok(ExpressionValue::Reflect(Arc::new(message)))
                     }
                     VariantInfo::Tuple(_tuple_info) => {
@@ -187,7 +289,16 @@ impl UI {
                                 }
                                 let dynamic_enum =
                                     DynamicEnum::new(&variant_name, DynamicVariant::Tuple(tuple));
-                                let message = MESSAGE::from_reflect(&dynamic_enum).unwrap();
+                                let Some(message) = MESSAGE::from_reflect(&dynamic_enum) else {
+                                    bail!(
+                                        "Invalid arguments for message {}: {}",
+                                        variant_name,
+                                        args.iter()
+                                            .map(|arg| arg.to_string())
+                                            .collect::<Vec<_>>()
+                                            .join(", ")
+                                    );
+                                };
                                 Ok(ExpressionValue::Reflect(Arc::new(message)))
                             },
                         ))
@@ -218,7 +329,10 @@ impl UI {
                                 self.eval_layout_and_redraw()?;
                             }
                             recv(ticker) -> _ => {
-                                if !self.animated_nodes.is_empty() {
+                                if self.animated_layout {
+                                    self.perform_layout()?;
+                                }
+                                if self.animated_layout || !self.animated_nodes.is_empty() {
                                     self.redraw()?;
                                 }
                             }
@@ -237,6 +351,14 @@ impl UI {
     pub fn eval_layout_and_redraw(&mut self) -> ViuiResult<()> {
         self.eval_expressions()?;
         self.perform_layout()?;
+        // Lists only know which rows are visible once they have been laid out
+        for _ in 1..MAXIMUM_LAYOUT_PASSES {
+            if !self.needs_layout_pass {
+                break;
+            }
+            self.eval_expressions()?;
+            self.perform_layout()?;
+        }
         self.redraw()?;
         Ok(())
     }
@@ -244,11 +366,12 @@ impl UI {
     fn redraw(&mut self) -> ViuiResult<()> {
         let mut render_backends = take(&mut self.render_backends);
         for backend in &mut render_backends {
-            let render_commands = self.make_render_commands(backend)?;
-            backend
-                .render_backend_sender
-                .send(RenderBackendMessage { render_commands })
-                .unwrap();
+            if let Some(render_commands) = self.make_render_commands(backend)? {
+                backend
+                    .render_backend_sender
+                    .send(RenderBackendMessage { render_commands })
+                    .unwrap();
+            }
         }
         self.render_backends = render_backends;
         Ok(())
@@ -266,6 +389,8 @@ impl UI {
             render_backend_sender,
             maximum_font_index_loaded: 0,
             window_size: Size::new(1200.0, 1200.0),
+            render_cache: RenderCache::default(),
+            layout_tree: LayoutTree::default(),
         });
         self.eval_layout_and_redraw()?;
         Ok(RenderBackendParameters {
@@ -288,90 +413,580 @@ impl UI {
         // Clear out nodes that no longer exist
         self.active_nodes
             .retain(|node_idx| self.node_arena.contains(node_idx));
+        self.hovered_nodes
+            .retain(|node_idx| self.node_arena.contains(node_idx));
+        self.focused_node = self
+            .focused_node
+            .filter(|node_idx| self.node_arena.contains(node_idx));
+        // Shortcuts take precedence over the focused node, unless it edits text and the keys type
+        if let UiEventKind::KeyInput(input) = &event.kind {
+            let keys = KeyCombination {
+                key: input.key,
+                modifiers: input.modifiers,
+            };
+            let is_typing = input.modifiers.types_text() && self.focus_edits_text();
+            if input.key_event_kind == KeyEventKind::Pressed
+                && !is_typing
+                && self.trigger_shortcut(keys, input.repeat)?
+            {
+                return Ok(());
+            }
+        }
         match event.kind {
-            UiEventKind::MouseMoved(position) => {
+            UiEventKind::MouseMoved(input) => {
+                let position = input.position;
                 self.mouse_position = position;
                 for node in &self.active_nodes {
-                    add_event_trigger(*node, InputEvent::mouse_move(position));
+                    add_event_trigger(
+                        *node,
+                        InputEvent::mouse_move(position).with_modifiers(input.modifiers),
+                    );
                 }
-                for (node, node_idx) in self.node_arena.entries_mut_indexed() {
-                    if node.layout.bounds.contains(position) {
-                        add_event_trigger(node_idx, InputEvent::mouse_over());
-                    } else {
-                        add_event_trigger(node_idx, InputEvent::mouse_out());
+                let hovered_nodes: Vec<NodeIdx> = self
+                    .hit_test(position)
+                    .map(|node_idx| self.hit_index.ancestry(node_idx).collect())
+                    .unwrap_or_default();
+                for node_idx in &self.hovered_nodes {
+                    if !hovered_nodes.contains(node_idx) {
+                        add_event_trigger(*node_idx, InputEvent::mouse_leave());
+                    }
+                }
+                for node_idx in &hovered_nodes {
+                    if !self.hovered_nodes.contains(node_idx) {
+                        add_event_trigger(*node_idx, InputEvent::mouse_enter());
                     }
                 }
+                self.hovered_nodes = hovered_nodes;
+                self.update_tooltip(position);
+            }
+            UiEventKind::MouseWheel(input) => {
+                self.dismiss_tooltip();
+                if let Some(node_idx) = self.hit_test(self.mouse_position) {
+                    add_event_trigger(
+                        node_idx,
+                        InputEvent::mouse_wheel(input.delta).with_modifiers(input.modifiers),
+                    );
+                }
             }
             UiEventKind::MouseInput(input) => {
+                self.dismiss_tooltip();
                 self.active_nodes.clear();
                 let position = self.mouse_position;
-                for (node, idx) in self.node_arena.entries_mut_indexed() {
-                    if node.layout.bounds.contains(position) {
-                        self.active_nodes.push(idx);
-                        if input.mouse_event_kind == MouseEventKind::Pressed {
-                            add_event_trigger(idx, InputEvent::mouse_press(position));
-                        } else if input.mouse_event_kind == MouseEventKind::Released {
-                            add_event_trigger(idx, InputEvent::mouse_release(position));
-                        }
-                    }
+                let hit_node = self.hit_test(position);
+                if input.mouse_event_kind == MouseEventKind::Pressed {
+                    // Clicking focuses the nearest focusable node, or removes the focus
+                    let focused_node = hit_node.and_then(|node_idx| {
+                        self.hit_index
+                            .ancestry(node_idx)
+                            .find(|node_idx| self.is_focusable(node_idx))
+                    });
+                    self.focus(focused_node)?;
+                }
+                if let Some(node_idx) = hit_node {
+                    self.active_nodes.push(node_idx);
+                    let event = match input.mouse_event_kind {
+                        MouseEventKind::Pressed => InputEvent::mouse_press(position),
+                        MouseEventKind::Released => InputEvent::mouse_release(position),
+                    };
+                    add_event_trigger(node_idx, event.with_modifiers(input.modifiers));
                 }
             }
             UiEventKind::CharInput(character) => {
-                for node in &self.active_nodes {
-                    add_event_trigger(*node, InputEvent::character(character.character));
+                if let Some(node_idx) = self.focused_node {
+                    add_event_trigger(node_idx, InputEvent::character(character.character));
                 }
             }
-            UiEventKind::KeyInput(key_input) => {
-                for node in &self.active_nodes {
-                    add_event_trigger(*node, InputEvent::key_input(key_input.key));
+            UiEventKind::KeyInput(KeyInput {
+                key: KeyboardKey::Tab,
+                key_event_kind,
+                modifiers,
+                ..
+            }) => {
+                if key_event_kind == KeyEventKind::Pressed {
+                    self.move_focus(modifiers.shift)?;
+                }
+            }
+            UiEventKind::KeyInput(input) => {
+                // Without focus, keys go to the topmost layer so that Escape closes it
+                if let Some(node_idx) = self.focused_node.or(self.layers.last().copied()) {
+                    let event = match input.key_event_kind {
+                        KeyEventKind::Pressed => InputEvent::key_down(input.key, input.repeat),
+                        KeyEventKind::Released => InputEvent::key_up(input.key),
+                    };
+                    add_event_trigger(node_idx, event.with_modifiers(input.modifiers));
                 }
             }
             UiEventKind::WindowResized {
                 size,
                 backend_index,
             } => {
-                self.render_backends[backend_index].window_size = size;
+                let backend = &mut self.render_backends[backend_index];
+                backend.window_size = size;
+                backend.render_cache.invalidate();
             }
         }
 
         for (node_idx, event) in events_to_trigger {
-            let node = &mut self.node_arena[&node_idx];
+            self.dispatch_event(node_idx, event)?;
+        }
+        Ok(())
+    }
+
+    /// Dispatches an input event down the ancestors of the target, to the target and back up
+    ///
+    /// Elements handle the event at the target and while it bubbles up, unless the default
+    /// was prevented. Event mappings of the nodes handle it in the phase they were declared for.
+    fn dispatch_event(&mut self, target: NodeIdx, mut event: InputEvent) -> ViuiResult<()> {
+        let path: Vec<NodeIdx> = if event.propagates() {
+            self.hit_index.ancestry(target).collect()
+        } else {
+            vec![target]
+        };
+        let capturing = path.iter().rev().map(|node_idx| (*node_idx, true));
+        let bubbling = path.iter().map(|node_idx| (*node_idx, false));
+        for (node_idx, capture) in capturing.chain(bubbling) {
+            event.set_phase(if node_idx == target {
+                EventPhase::Target
+            } else if capture {
+                EventPhase::Capture
+            } else {
+                EventPhase::Bubble
+            });
+            if !capture {
+                for node_event in self.handle_element_event(&node_idx, &event)? {
+                    self.dispatch_node_event(node_idx, node_event)?;
+                }
+            }
+            if let Some(mapping) = self.event_mapping(&node_idx, event.name(), capture) {
+                self.send_message(&mapping.expression, &|_| Ok(None))?;
+                if mapping.prevent_default {
+                    event.prevent_default();
+                }
+                if mapping.stop_propagation {
+                    event.stop_propagation();
+                }
+            }
+            if event.is_propagation_stopped() {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_element_event(
+        &mut self,
+        node_idx: &NodeIdx,
+        event: &InputEvent,
+    ) -> ViuiResult<Vec<Box<dyn NodeEvents>>> {
+        let mut events = Vec::new();
+        if event.is_default_prevented() {
+            return Ok(events);
+        }
+        let node = &mut self.node_arena[node_idx];
+        let mut event_trigger = |event: Box<dyn NodeEvents>| {
+            events.push(event);
+        };
+        self.node_registry
+            .handle_event(node.kind_index, event, node, &mut event_trigger)?;
+        Ok(events)
+    }
+
+    /// Dispatches an event triggered by an element to the mappings of the node
+    ///
+    /// Unlike input events the event does not propagate, but the closest ancestor triggering
+    /// events in response takes it over.
+    fn dispatch_node_event(
+        &mut self,
+        source: NodeIdx,
+        event: Box<dyn NodeEvents>,
+    ) -> ViuiResult<()> {
+        let ReflectRef::Enum(dyn_enum) = event.reflect_ref() else {
+            bail!(
+                "Event is not an enum: {}",
+                event.get_represented_type_info().unwrap().type_path()
+            );
+        };
+        let name = dyn_enum.variant_name().to_lowercase();
+        let lookup = |name: &str| {
+            Ok(if let Some(field) = dyn_enum.field(name) {
+                Some(field.try_into()?)
+            } else {
+                None
+            })
+        };
+        // The event is at its target, where mappings for either phase apply
+        let mappings = self.node_arena[&source]
+            .event_mappings
+            .get(&name)
+            .cloned()
+            .unwrap_or_default();
+        for mapping in mappings {
+            self.send_message(&mapping.expression, &lookup)?;
+        }
+        let path: Vec<NodeIdx> = self.hit_index.ancestry(source).collect();
+        for node_idx in path.iter().skip(1) {
+            let node = &mut self.node_arena[node_idx];
             let mut events = Vec::new();
             let mut event_trigger = |event: Box<dyn NodeEvents>| {
                 events.push(event);
             };
             self.node_registry
-                .handle_event(node.kind_index, event, node, &mut event_trigger)?;
-            for event in events {
-                let ReflectRef::Enum(dyn_enum) = event.reflect_ref() else {
-                    bail!(
-                        "Event is not an enum: {}",
-                        event.get_represented_type_info().unwrap().type_path()
-                    );
-                };
-                let variant_name = dyn_enum.variant_name().to_lowercase();
-                if let Some(message_expression) = node.event_mappings.get(&variant_name) {
-                    let result = eval_expression(
-                        self.app_state.state(),
-                        &self.message_string_to_enum_converter,
-                        message_expression,
-                        &|name| {
-                            Ok(if let Some(field) = dyn_enum.field(name) {
-                                Some(field.try_into()?)
-                            } else {
-                                None
-                            })
-                        },
-                    )?;
-                    (self.event_handler)(self.app_state.as_mut(), result.as_reflect());
-                } else {
-                    bail!("No event mapping found for event: {:?}", event);
+                .handle_descendant_event(event.as_ref(), node, &mut event_trigger)?;
+            if !events.is_empty() {
+                for ancestor_event in events {
+                    self.dispatch_node_event(*node_idx, ancestor_event)?;
+                }
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    fn event_mapping(&self, node_idx: &NodeIdx, name: &str, capture: bool) -> Option<EventMapping> {
+        self.node_arena[node_idx]
+            .event_mappings
+            .get(name)?
+            .iter()
+            .find(|mapping| mapping.capture == capture)
+            .cloned()
+    }
+
+    /// Evaluates the message expression and passes the message to the event handler
+    fn send_message(
+        &mut self,
+        expression: &ExpressionAst,
+        lookup: &dyn Fn(&str) -> ViuiResult<Option<ExpressionValue>>,
+    ) -> ViuiResult<()> {
+        let message = eval_expression(
+            self.app_state.state(),
+            &self.message_string_to_enum_converter,
+            expression,
+            lookup,
+        )?;
+        (self.event_handler)(self.app_state.as_mut(), message.as_reflect());
+        Ok(())
+    }
+
+    pub fn focused_node(&self) -> Option<NodeIdx> {
+        self.focused_node
+    }
+
+    /// Moves the keyboard focus to the node, sending blur and focus events
+    pub fn focus(&mut self, node_idx: Option<NodeIdx>) -> ViuiResult<()> {
+        if self.focused_node == node_idx {
+            return Ok(());
+        }
+        if let Some(previous) = self.focused_node.take() {
+            self.dispatch_event(previous, InputEvent::blur())?;
+        }
+        self.focused_node = node_idx;
+        if let Some(node_idx) = node_idx {
+            self.dispatch_event(node_idx, InputEvent::focus())?;
+        }
+        Ok(())
+    }
+
+    /// Whether the focused node edits typed text
+    fn focus_edits_text(&self) -> bool {
+        self.focused_node.is_some_and(|node_idx| {
+            let node = &self.node_arena[&node_idx];
+            self.node_registry
+                .get_node_by_kind(node.kind_index)
+                .is_ok_and(|descriptor| descriptor.edits_text)
+        })
+    }
+
+    fn is_focusable(&self, node_idx: &NodeIdx) -> bool {
+        let node = &self.node_arena[node_idx];
+        node.common_props.tab_index.is_some()
+            || self
+                .node_registry
+                .get_node_by_kind(node.kind_index)
+                .is_ok_and(|descriptor| descriptor.focusable)
+    }
+
+    /// Focuses the next node in the Tab order, or the previous one if `backwards` is set
+    fn move_focus(&mut self, backwards: bool) -> ViuiResult<()> {
+        let mut tab_order: Vec<NodeIdx> = self
+            .focus_scope_nodes()
+            .into_iter()
+            .filter(|node_idx| {
+                self.is_focusable(node_idx)
+                    && self.node_arena[node_idx]
+                        .common_props
+                        .tab_index
+                        .unwrap_or(0)
+                        >= 0
+            })
+            .collect();
+        // Positive indices first, the rest in document order
+        tab_order.sort_by_key(
+            |node_idx| match self.node_arena[node_idx].common_props.tab_index {
+                Some(tab_index) if tab_index > 0 => tab_index,
+                _ => i32::MAX,
+            },
+        );
+        if tab_order.is_empty() {
+            return Ok(());
+        }
+        let position = self
+            .focused_node
+            .and_then(|focused| tab_order.iter().position(|node_idx| *node_idx == focused));
+        let count = tab_order.len();
+        let next = match (position, backwards) {
+            (Some(position), false) => (position + 1) % count,
+            (Some(position), true) => (position + count - 1) % count,
+            (None, false) => 0,
+            (None, true) => count - 1,
+        };
+        self.focus(Some(tab_order[next]))
+    }
+
+    /// Nodes currently shown with their parents, in document order
+    fn shown_nodes(&self) -> Vec<(NodeIdx, Option<NodeIdx>)> {
+        let mut nodes = vec![];
+        let mut todo = vec![(self.root_item_idx, None)];
+        while let Some((item_idx, parent)) = todo.pop() {
+            match &self.item_arena[&item_idx].kind {
+                NodeItemKind::Node(node_idx) => {
+                    nodes.push((*node_idx, parent));
+                    let children = self
+                        .node_registry
+                        .shown_children(&self.node_arena[node_idx])
+                        .iter()
+                        .rev();
+                    todo.extend(children.map(|child| (*child, Some(*node_idx))));
+                }
+                NodeItemKind::If(if_item) => {
+                    if if_item.condition {
+                        todo.push((if_item.then_item, parent));
+                    }
+                }
+                NodeItemKind::Block(block_item) => {
+                    todo.extend(block_item.items.iter().rev().map(|item| (*item, parent)))
+                }
+                NodeItemKind::For(for_item) => {
+                    todo.extend(for_item.items.iter().rev().map(|item| (*item, parent)))
+                }
+            }
+        }
+        nodes
+    }
+
+    /// Shown nodes inside the topmost layer, or all shown nodes if no layer is shown
+    fn focus_scope_nodes(&self) -> Vec<NodeIdx> {
+        let shown_nodes = self.shown_nodes().into_iter();
+        let Some(layer) = self.layers.last() else {
+            return shown_nodes.map(|(node_idx, _)| node_idx).collect();
+        };
+        // Parents come before their children in document order
+        let mut nodes = vec![];
+        for (node_idx, parent) in shown_nodes {
+            if node_idx == *layer || parent.is_some_and(|parent| nodes.contains(&parent)) {
+                nodes.push(node_idx);
+            }
+        }
+        nodes
+    }
+
+    /// Moves the focus into newly shown layers and back once closed
+    fn update_layer_focus(&mut self) -> ViuiResult<()> {
+        self.focused_node = self
+            .focused_node
+            .filter(|node_idx| self.node_arena.contains(node_idx));
+        while let Some((layer, previous)) = self.layer_focus.last().copied() {
+            if self.layers.contains(&layer) {
+                break;
+            }
+            self.layer_focus.pop();
+            self.focus(previous.filter(|node_idx| self.node_arena.contains(node_idx)))?;
+        }
+        if let Some(layer) = self.layers.last().copied() {
+            if self.layer_focus.last().map(|(layer, _)| *layer) != Some(layer) {
+                self.layer_focus.push((layer, self.focused_node));
+                let first_focusable = self
+                    .focus_scope_nodes()
+                    .into_iter()
+                    .find(|node_idx| self.is_focusable(node_idx));
+                self.focus(first_focusable)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Starts the tooltip delay when the pointer enters a node with a tooltip
+    ///
+    /// The innermost hovered node with a tooltip is used. Until the tooltip is shown, it follows
+    /// the pointer.
+    fn update_tooltip(&mut self, position: Point) {
+        let hovered = self
+            .hovered_nodes
+            .iter()
+            .find(|node_idx| self.node_arena[node_idx].common_props.tooltip.is_some())
+            .copied();
+        match (hovered, &mut self.tooltip) {
+            (Some(node_idx), Some(tooltip)) if tooltip.node_idx == node_idx => {
+                if !tooltip.is_due() {
+                    tooltip.position = position;
                 }
             }
+            (hovered, tooltip) => {
+                *tooltip = hovered.map(|node_idx| Tooltip {
+                    node_idx,
+                    position,
+                    hovered_since: Instant::now(),
+                    dismissed: false,
+                });
+            }
+        }
+    }
+
+    /// Hides the tooltip until the pointer enters another node
+    fn dismiss_tooltip(&mut self) {
+        if let Some(tooltip) = &mut self.tooltip {
+            tooltip.dismissed = true;
+        }
+    }
+
+    /// Collects the shortcuts of the shown nodes, failing if keys are bound twice in a scope
+    fn update_shortcuts(&mut self) -> ViuiResult<()> {
+        let mut shortcuts: Vec<Shortcut> = vec![];
+        for (node_idx, parent) in self.shown_nodes() {
+            let node = &self.node_arena[&node_idx];
+            let Some(props) = node.props.as_any().downcast_ref::<ShortcutElementProps>() else {
+                continue;
+            };
+            let keys = KeyCombination::parse(&props.keys)?;
+            let scope = parent.filter(|parent| !props.global && *parent != self.root_node_idx);
+            if shortcuts
+                .iter()
+                .any(|shortcut| shortcut.keys == keys && shortcut.scope == scope)
+            {
+                match scope {
+                    Some(scope) => bail!(
+                        "Shortcut '{}' is bound twice in {}",
+                        keys,
+                        self.node_arena[&scope].tag
+                    ),
+                    None => bail!("Shortcut '{}' is bound twice app-wide", keys),
+                }
+            }
+            shortcuts.push(Shortcut {
+                keys,
+                description: props.description.clone(),
+                node_idx,
+                scope,
+            });
         }
+        self.shortcuts = shortcuts;
         Ok(())
     }
 
+    /// Lets the shown elements update themselves and the nodes below them, parents first
+    ///
+    /// Returns whether children were revealed that have not been evaluated yet.
+    fn update_elements(&mut self) -> ViuiResult<bool> {
+        let shown_nodes = self.shown_nodes();
+        let shows_children = |ui: &Self| -> Vec<bool> {
+            shown_nodes
+                .iter()
+                .map(|(node_idx, _)| {
+                    let node = &ui.node_arena[node_idx];
+                    ui.node_registry.shown_children(node).len() == node.children.len()
+                })
+                .collect()
+        };
+        let showed_children = shows_children(self);
+        // End of the subtree of each node, as children follow their parent in document order
+        let positions: HashMap<NodeIdx, usize> = shown_nodes
+            .iter()
+            .enumerate()
+            .map(|(position, (node_idx, _))| (*node_idx, position))
+            .collect();
+        let mut subtree_ends: Vec<usize> = (1..=shown_nodes.len()).collect();
+        for (position, (_, parent)) in shown_nodes.iter().enumerate().rev() {
+            if let Some(parent) = parent.and_then(|parent| positions.get(&parent)) {
+                subtree_ends[*parent] = subtree_ends[*parent].max(subtree_ends[position]);
+            }
+        }
+        for (position, (node_idx, _)) in shown_nodes.iter().enumerate() {
+            let node = &mut self.node_arena[node_idx];
+            let kind_index = node.kind_index;
+            // Taken out of the arena while the nodes below are updated
+            let snapshot = node.state.clone_value();
+            let mut state = replace(&mut node.state, Box::new(()));
+            let props = replace(&mut node.props, Box::new(()));
+            let descendants = &shown_nodes[position + 1..subtree_ends[position]];
+            let mut context = UpdateContext::new(&mut self.node_arena, *node_idx, descendants);
+            let result =
+                self.node_registry
+                    .update_children(kind_index, &mut state, &props, &mut context);
+            let node = &mut self.node_arena[node_idx];
+            node.state = state;
+            node.props = props;
+            node.compare_state(snapshot.as_ref());
+            result?;
+        }
+        let revealed = showed_children
+            .into_iter()
+            .zip(shows_children(self))
+            .any(|(showed, shows)| !showed && shows);
+        Ok(revealed)
+    }
+
+    /// Shortcuts applying with the current focus, innermost scope first
+    ///
+    /// Shortcuts shadowed by a shortcut with the same keys in an inner scope are left out.
+    pub fn active_shortcuts(&self) -> Vec<&Shortcut> {
+        let focus_path: Vec<NodeIdx> = self
+            .focused_node
+            .map(|node_idx| self.hit_index.ancestry(node_idx).collect())
+            .unwrap_or_default();
+        let mut active: Vec<(usize, &Shortcut)> = self
+            .shortcuts
+            .iter()
+            .filter_map(|shortcut| {
+                let depth = match shortcut.scope {
+                    Some(scope) => focus_path.iter().position(|node_idx| *node_idx == scope)?,
+                    None => usize::MAX,
+                };
+                Some((depth, shortcut))
+            })
+            .collect();
+        active.sort_by_key(|(depth, _)| *depth);
+        let mut bound_keys = HashSet::new();
+        active
+            .into_iter()
+            .map(|(_, shortcut)| shortcut)
+            .filter(|shortcut| bound_keys.insert(shortcut.keys))
+            .collect()
+    }
+
+    /// Triggers the active shortcut for the keys, returning whether there was one
+    fn trigger_shortcut(&mut self, keys: KeyCombination, repeat: bool) -> ViuiResult<bool> {
+        let Some(node_idx) = self
+            .active_shortcuts()
+            .into_iter()
+            .find(|shortcut| shortcut.keys == keys)
+            .map(|shortcut| shortcut.node_idx)
+        else {
+            return Ok(false);
+        };
+        let event = InputEvent::key_down(keys.key, repeat).with_modifiers(keys.modifiers);
+        for node_event in self.handle_element_event(&node_idx, &event)? {
+            self.dispatch_node_event(node_idx, node_event)?;
+        }
+        Ok(true)
+    }
+
+    /// The topmost node at the position, as last rendered
+    fn hit_test(&self, position: Point) -> Option<NodeIdx> {
+        self.hit_index.hit_test(position, |node_idx| {
+            self.node_arena.contains(node_idx)
+                && self.node_arena[node_idx].layout.contains(position)
+        })
+    }
+
     pub fn register_node<T: Element>(&mut self) {
         // TODO: Check if node is already registered
         self.node_registry.register_node::<T>();
@@ -379,7 +994,13 @@ impl UI {
 
     pub fn eval_expressions(&mut self) -> ViuiResult<()> {
         let mut binding_stack = BindingStack::new();
-        self.eval_expressions_internal(self.root_item_idx, &mut binding_stack)
+        self.eval_expressions_internal(self.root_item_idx, &mut binding_stack)?;
+        // Content of newly selected tabs and expanded sections is only evaluated once shown
+        while self.update_elements()? {
+            self.eval_expressions_internal(self.root_item_idx, &mut binding_stack)?;
+        }
+        self.update_shortcuts()?;
+        Ok(())
     }
 
     pub fn eval_expressions_internal(
@@ -389,10 +1010,18 @@ impl UI {
     ) -> ViuiResult<()> {
         enum Todo {
             Item(ItemIdx),
+            /// Child of a node, which picks the values that its `for` children are instantiated for
+            ChildItem {
+                node_idx: NodeIdx,
+                item_idx: ItemIdx,
+            },
             CloneItem {
                 template_idx: ItemIdx,
                 for_idx: ItemIdx,
+                offset: usize,
             },
+            /// Recycled item, whose nodes start over with a fresh state
+            ResetState(ItemIdx),
             PushBindings,
             PopBindings,
             SetBinding {
@@ -408,17 +1037,28 @@ impl UI {
                     match &mut item.kind {
                         NodeItemKind::Node(node_idx) => {
                             let node = &mut self.node_arena[node_idx];
-                            todos.extend(node.children.iter().map(|item| Todo::Item(*item)));
+                            let children = self.node_registry.shown_children(node);
+                            todos.extend(children.iter().map(|item| Todo::ChildItem {
+                                node_idx: *node_idx,
+                                item_idx: *item,
+                            }));
                             for expression in &node.prop_expressions {
-                                let prop = node.props.reflect_path_mut(&*expression.field_name)?;
-                                let app_state = self.app_state.state();
                                 let value = eval_expression(
-                                    app_state,
+                                    self.app_state.state(),
                                     &self.message_string_to_enum_converter,
                                     &expression.expression,
                                     &|name| Ok(binding_stack.get_binding(name)),
                                 )?;
-                                if let Some(prop) = prop.downcast_mut::<f32>() {
+                                if CommonProps::is_common_prop(&expression.field_name) {
+                                    node.common_props.set(&expression.field_name, &value)?;
+                                    continue;
+                                }
+                                if LayoutProps::is_layout_prop(&expression.field_name) {
+                                    node.layout_props.set(&expression.field_name, &value)?;
+                                    continue;
+                                }
+                                let prop = node.props.reflect_path_mut(&*expression.field_name)?;
+                                let changed = if let Some(prop) = prop.downcast_mut::<f32>() {
                                     let ExpressionValue::Float(value) = value else {
                                         bail!(
                                             "Expected float for property {}, but was: {}",
@@ -426,7 +1066,9 @@ impl UI {
                                             value
                                         );
                                     };
+                                    let changed = *prop != value;
                                     *prop = value;
+                                    changed
                                 } else if let Some(prop) = prop.downcast_mut::<i32>() {
                                     let ExpressionValue::Float(value) = value else {
                                         bail!(
@@ -435,7 +1077,20 @@ impl UI {
                                             value
                                         );
                                     };
+                                    let changed = *prop != value as i32;
                                     *prop = value as i32;
+                                    changed
+                                } else if let Some(prop) = prop.downcast_mut::<bool>() {
+                                    let ExpressionValue::Bool(value) = value else {
+                                        bail!(
+                                            "Expected boolean for property {}, but was: {}",
+                                            expression.field_name,
+                                            value
+                                        );
+                                    };
+                                    let changed = *prop != value;
+                                    *prop = value;
+                                    changed
                                 } else if let Some(prop) = prop.downcast_mut::<String>() {
                                     let ExpressionValue::String(value) = value else {
                                         bail!(
@@ -444,13 +1099,37 @@ impl UI {
                                             value
                                         );
                                     };
+                                    let changed = *prop != value;
                                     *prop = value;
+                                    changed
+                                } else if let Some(prop) = prop.downcast_mut::<Vec<String>>() {
+                                    let ExpressionValue::Vec(values) = value else {
+                                        bail!(
+                                            "Expected list for property {}, but was: {}",
+                                            expression.field_name,
+                                            value
+                                        );
+                                    };
+                                    let values = values
+                                        .into_iter()
+                                        .map(|value| match value {
+                                            ExpressionValue::String(value) => value,
+                                            value => value.to_string(),
+                                        })
+                                        .collect::<Vec<String>>();
+                                    let changed = *prop != values;
+                                    *prop = values;
+                                    changed
                                 } else {
                                     error!(
                                         "Unsupported property type for {}: {}",
                                         expression.field_name,
                                         prop.reflect_short_type_path()
                                     );
+                                    false
+                                };
+                                if changed {
+                                    node.props_revision += 1;
                                 }
                             }
                         }
@@ -473,69 +1152,126 @@ impl UI {
                             todos.extend(block_item.items.iter().map(|item| Todo::Item(*item)));
                         }
                         NodeItemKind::For(ref mut for_item) => {
-                            let value = eval_expression(
-                                self.app_state.state(),
-                                &self.message_string_to_enum_converter,
-                                &for_item.expression,
-                                &|_name| Ok(None),
-                            )?;
-                            let ExpressionValue::Vec(values) = value else {
-                                bail!("For expression must be a vector, instead got {:?}", value);
+                            // Lists in the app state are converted only for the values picked
+                            let list = match for_item.expression.data() {
+                                ExpressionKind::VarUse(path) => self
+                                    .app_state
+                                    .state()
+                                    .reflect_path(path.as_str())
+                                    .ok()
+                                    .and_then(|value| match value.reflect_ref() {
+                                        ReflectRef::List(list) => Some(ReflectedList(list)),
+                                        _ => None,
+                                    }),
+                                _ => None,
                             };
-                            for (index, value) in values
-                                .into_iter()
-                                .zip_longest(for_item.items.iter())
-                                .enumerate()
-                            {
-                                match value {
-                                    EitherOrBoth::Both(value, item_idx) => {
-                                        todos.push(Todo::PopBindings);
-                                        todos.push(Todo::Item(*item_idx));
-                                        todos.push(Todo::SetBinding {
-                                            name: format!("{}#index", for_item.binding_name),
-                                            value: ExpressionValue::Float(index as f32),
-                                        });
-                                        todos.push(Todo::SetBinding {
-                                            name: for_item.binding_name.clone(),
-                                            value,
-                                        });
-                                        todos.push(Todo::PushBindings);
-                                    }
-                                    EitherOrBoth::Left(value) => {
-                                        //let item_idx = self.clone_item(for_item.item_template)?;
-                                        todos.push(Todo::PopBindings);
-                                        todos.push(Todo::CloneItem {
-                                            template_idx: for_item.item_template,
-                                            for_idx: item_idx,
-                                        });
-                                        todos.push(Todo::SetBinding {
-                                            name: format!("{}#index", for_item.binding_name),
-                                            value: ExpressionValue::Float(index as f32),
-                                        });
-                                        todos.push(Todo::SetBinding {
-                                            name: for_item.binding_name.clone(),
-                                            value,
-                                        });
-                                        todos.push(Todo::PushBindings);
-                                    }
-                                    EitherOrBoth::Right(_) => {
-                                        // TODO: remove items
+                            let evaluated;
+                            let collection: &dyn ForCollection = match &list {
+                                Some(list) => list,
+                                None => {
+                                    let value = eval_expression(
+                                        self.app_state.state(),
+                                        &self.message_string_to_enum_converter,
+                                        &for_item.expression,
+                                        &|_name| Ok(None),
+                                    )?;
+                                    let ExpressionValue::Vec(values) = value else {
+                                        bail!(
+                                            "For expression must be a vector, instead got {:?}",
+                                            value
+                                        );
+                                    };
+                                    evaluated = values;
+                                    &evaluated
+                                }
+                            };
+                            let values = match for_item.owner {
+                                Some(node_idx) => self.node_registry.for_values(
+                                    &mut self.node_arena[&node_idx],
+                                    collection,
+                                    &entry_field,
+                                )?,
+                                None => ForValue::indexed(collection, 0..collection.len())?,
+                            };
+                            // Items are kept for the value at the same index, items no longer
+                            // needed are recycled for new values with a fresh state
+                            let mut previous_items: HashMap<usize, ItemIdx> =
+                                take(&mut for_item.item_indices)
+                                    .into_iter()
+                                    .zip(take(&mut for_item.items))
+                                    .collect();
+                            let mut items: Vec<_> = values
+                                .iter()
+                                .map(|for_value| {
+                                    previous_items
+                                        .remove(&for_value.index)
+                                        .map(|item| (item, false))
+                                })
+                                .collect();
+                            for_item.spare_items.extend(previous_items.into_values());
+                            for item in items.iter_mut().filter(|item| item.is_none()) {
+                                *item = for_item.spare_items.pop().map(|item| (item, true));
+                            }
+                            for_item.item_indices =
+                                values.iter().map(|for_value| for_value.index).collect();
+                            for_item.items =
+                                items.iter().flatten().map(|(item, _)| *item).collect();
+                            // Pushed in reverse, so that clones are inserted in order
+                            for (offset, for_value) in values.into_iter().enumerate().rev() {
+                                todos.push(Todo::PopBindings);
+                                match items[offset] {
+                                    Some((item_idx, is_recycled)) => {
+                                        todos.push(Todo::Item(item_idx));
+                                        if is_recycled {
+                                            todos.push(Todo::ResetState(item_idx));
+                                        }
                                     }
+                                    None => todos.push(Todo::CloneItem {
+                                        template_idx: for_item.item_template,
+                                        for_idx: item_idx,
+                                        offset,
+                                    }),
                                 }
+                                todos.push(Todo::SetBinding {
+                                    name: format!("{}#index", for_item.binding_name),
+                                    value: ExpressionValue::Float(for_value.index as f32),
+                                });
+                                for (name, value) in for_value.bindings {
+                                    todos.push(Todo::SetBinding {
+                                        name: format!("{}#{}", for_item.binding_name, name),
+                                        value,
+                                    });
+                                }
+                                todos.push(Todo::SetBinding {
+                                    name: for_item.binding_name.clone(),
+                                    value: for_value.value,
+                                });
+                                todos.push(Todo::PushBindings);
                             }
                         }
                     }
                 }
+                Todo::ChildItem { node_idx, item_idx } => {
+                    if let NodeItemKind::For(for_item) = &mut self.item_arena[&item_idx].kind {
+                        for_item.owner = Some(node_idx);
+                    }
+                    todos.push(Todo::Item(item_idx));
+                }
                 Todo::CloneItem {
                     template_idx,
                     for_idx,
+                    offset,
                 } => {
                     let item_idx = self.clone_item(template_idx)?;
                     let item = &mut self.item_arena[&for_idx];
                     let NodeItemKind::For(ref mut for_item) = item.kind else {
                         bail!("Expected for item");
                     };
-                    for_item.items.push(item_idx);
+                    for_item.items.insert(offset, item_idx);
+                    todos.push(Todo::Item(item_idx));
+                }
+                Todo::ResetState(item_idx) => {
+                    self.reset_item_state(item_idx)?;
                 }
                 Todo::PushBindings => {
                     binding_stack.push();
@@ -574,12 +1310,41 @@ impl UI {
                 for item in &mut for_item.items {
                     *item = self.clone_item(*item)?;
                 }
+                for_item.spare_items.clear();
             }
         }
         let new_item_idx = self.item_arena.insert(new_item);
         Ok(new_item_idx)
     }
 
+    /// Gives the nodes of the item a fresh state, as when they were created
+    fn reset_item_state(&mut self, item_idx: ItemIdx) -> ViuiResult<()> {
+        let mut todo = vec![item_idx];
+        while let Some(item_idx) = todo.pop() {
+            match &self.item_arena[&item_idx].kind {
+                NodeItemKind::Node(node_idx) => {
+                    let node = &mut self.node_arena[node_idx];
+                    let component = self.node_registry.get_node_by_kind(node.kind_index)?;
+                    node.state = (component.make_state)()?;
+                    node.state_revision += 1;
+                    todo.extend(&node.children);
+                }
+                NodeItemKind::If(if_item) => {
+                    todo.push(if_item.then_item);
+                    todo.extend(if_item.else_item);
+                }
+                NodeItemKind::Block(block_item) => {
+                    todo.extend(&block_item.items);
+                }
+                NodeItemKind::For(for_item) => {
+                    todo.extend(&for_item.items);
+                    todo.extend(&for_item.spare_items);
+                }
+            }
+        }
+        Ok(())
+    }
+
     fn clone_node(&mut self, old_node_idx: NodeIdx) -> ViuiResult<NodeIdx> {
         let mut children = self.node_arena[&old_node_idx].children.clone();
         for child in &mut children {
@@ -594,7 +1359,11 @@ impl UI {
             kind_index: old_node.kind_index,
             state,
             props,
+            props_revision: 0,
+            state_revision: 0,
+            common_props: old_node.common_props.clone(),
             layout: old_node.layout.clone(),
+            layout_props: old_node.layout_props.clone(),
             prop_expressions: old_node.prop_expressions.clone(),
             event_mappings: old_node.event_mappings.clone(),
             children,
@@ -605,9 +1374,13 @@ impl UI {
 
     pub fn perform_layout(&mut self) -> ViuiResult<()> {
         let mut render_backends = take(&mut self.render_backends);
+        self.animated_layout = false;
+        self.needs_layout_pass = false;
         for backend in &mut render_backends {
-            let mut tree: TaffyTree<NodeIdx> = TaffyTree::new();
-            let root_layout_node = tree.new_leaf_with_context(
+            let layout_tree = &mut backend.layout_tree;
+            layout_tree.begin_pass();
+            let root_layout_node = layout_tree.update_node(
+                self.root_node_idx,
                 Style {
                     flex_direction: FlexDirection::Column,
                     size: taffy::Size {
@@ -616,46 +1389,119 @@ impl UI {
                     },
                     ..Default::default()
                 },
-                self.root_node_idx,
+                None,
+                None,
             )?;
+            let mut layout_children = HashMap::from([(root_layout_node, vec![])]);
             let root_node = &self.node_arena[&self.root_node_idx];
-            let mut todo: Vec<_> = root_node
-                .children
+            let mut todo: Vec<_> = self
+                .node_registry
+                .shown_children(root_node)
                 .iter()
                 .map(|child_id| (root_layout_node, *child_id))
                 .rev()
                 .collect();
-            let mut layout_context = LayoutContext::new(&mut self.image_pool);
+            let mut layout_context = LayoutContext::new(&mut self.image_pool, &self.font_pool);
+            let mut overlay_layout_ids = HashSet::new();
+            let mut scroll_offsets = HashMap::new();
+            let mut list_row_heights = HashMap::new();
+            // Anchors of the layers, which are laid out as children of the root
+            let mut layer_anchors = HashMap::new();
+            self.layers.clear();
             while let Some((parent_layout_id, item_idx)) = todo.pop() {
                 let item = &self.item_arena[&item_idx];
                 match &item.kind {
                     NodeItemKind::Node(node_idx) => {
-                        let node = &mut self.node_arena[&node_idx];
-                        let layout_contraints =
-                            self.node_registry.layout_node(&mut layout_context, node)?;
-                        let style = match layout_contraints {
-                            LayoutConstraints::FixedLayout { width, height } => Some(Style {
-                                size: taffy::Size {
-                                    width: length(width),
-                                    height: length(height),
-                                },
-                                ..Default::default()
-                            }),
-                            LayoutConstraints::HorizontalLayout {} => Some(Style {
-                                flex_direction: FlexDirection::Row,
-                                size: taffy::Size::auto(),
+                        let node = &mut self.node_arena[node_idx];
+                        let revisions = (node.props_revision, node.state_revision);
+                        let node_registry = &self.node_registry;
+                        let NodeConstraints {
+                            is_animated,
+                            style,
+                            measure_fn,
+                            is_overlay,
+                            scroll_offset,
+                            list_row_height,
+                            layer,
+                        } = layout_tree.node_constraints(*node_idx, revisions, || {
+                            let layout_constraints =
+                                node_registry.layout_node(&mut layout_context, node)?;
+                            let is_animated = layout_context.reset_animated();
+                            Ok(to_node_constraints(layout_constraints, is_animated))
+                        })?;
+                        self.animated_layout |= *is_animated;
+                        let (is_overlay, scroll_offset, list_row_height, layer) =
+                            (*is_overlay, *scroll_offset, *list_row_height, *layer);
+                        let measure_revision = measure_fn.as_ref().map(|_| node.props_revision);
+                        let style = style.clone();
+                        // Passthrough nodes with layout props need their own layout node
+                        let style = match style {
+                            None if !node.layout_props.is_empty() => Some(Style {
+                                flex_direction: FlexDirection::Column,
                                 ..Default::default()
                             }),
-                            LayoutConstraints::Passthrough => None,
+                            style => style,
                         };
+                        // Layers are laid out in the window instead of their parent
+                        let anchor = layer.map(|anchored| {
+                            anchored.then(|| {
+                                *layout_tree
+                                    .taffy
+                                    .get_node_context(parent_layout_id)
+                                    .unwrap()
+                            })
+                        });
+                        let parent_layout_id = match layer {
+                            Some(_) => {
+                                self.layers.push(*node_idx);
+                                root_layout_node
+                            }
+                            None => parent_layout_id,
+                        };
+                        let style = style.map(|mut style| {
+                            if overlay_layout_ids.contains(&parent_layout_id) {
+                                // Stack all children of an overlay in the same grid cell
+                                style.grid_row = line(1);
+                                style.grid_column = line(1);
+                            }
+                            if let Some(row_height) = list_row_heights.get(&parent_layout_id) {
+                                style.flex_shrink = 0.0;
+                                if let Some(row_height) = row_height {
+                                    style.size.height = length(*row_height);
+                                }
+                            }
+                            node.layout_props.apply_to(&mut style);
+                            style
+                        });
                         let layout_id = if let Some(style) = style {
-                            let child_id = tree.new_leaf_with_context(style, *node_idx)?;
-                            tree.add_child(parent_layout_id, child_id)?;
+                            let child_id = layout_tree.update_node(
+                                *node_idx,
+                                style,
+                                measure_revision,
+                                scroll_offset,
+                            )?;
+                            layout_children
+                                .entry(parent_layout_id)
+                                .or_default()
+                                .push(child_id);
+                            layout_children.insert(child_id, vec![]);
+                            if is_overlay {
+                                overlay_layout_ids.insert(child_id);
+                            }
+                            if let Some(anchor) = anchor {
+                                layer_anchors.insert(child_id, anchor);
+                            }
+                            if let Some(offset) = scroll_offset {
+                                scroll_offsets.insert(child_id, offset);
+                            }
+                            if let Some(row_height) = list_row_height {
+                                list_row_heights.insert(child_id, row_height);
+                            }
                             child_id
                         } else {
                             parent_layout_id
                         };
-                        for child in node.children.iter().rev() {
+                        for child in self.node_registry.shown_children(node).iter().rev() {
                             todo.push((layout_id, *child));
                         }
                     }
@@ -676,34 +1522,164 @@ impl UI {
                     }
                 }
             }
-            // Compute layout
-            tree.compute_layout(root_layout_node, taffy::Size::max_content())?;
+            layout_tree.finish_update(layout_children)?;
+            if !layout_tree.has_changes() {
+                continue;
+            }
 
-            // Set absolute position and bounds for each node
-            let mut todo = vec![(0.0, 0.0, root_layout_node)];
-            while let Some((parent_x, parent_y, node_id)) = todo.pop() {
-                let node_index = tree.get_node_context(node_id).unwrap();
-                let node = &mut self.node_arena[node_index];
-                let layout = tree.layout(node_id)?;
-                let x = parent_x + layout.location.x;
-                let y = parent_y + layout.location.y;
-                node.layout.bounds = Rect::new(
-                    Point::new(x, y),
-                    Size::new(layout.size.width, layout.size.height),
-                );
-                for child in tree.children(node_id)? {
-                    todo.push((x, y, child));
+            // Compute layout of the changed subtrees
+            let measure_context = MeasureContext::new(&self.font_pool);
+            let mut measure_error = None;
+            layout_tree.compute_layout(
+                root_layout_node,
+                |measure_fn, known_dimensions, available_space| {
+                    if measure_error.is_some() {
+                        return taffy::Size::zero();
+                    }
+                    if let taffy::Size {
+                        width: Some(width),
+                        height: Some(height),
+                    } = known_dimensions
+                    {
+                        return taffy::Size { width, height };
+                    }
+                    let max_width = known_dimensions.width.or(match available_space.width {
+                        AvailableSpace::Definite(width) => Some(width),
+                        AvailableSpace::MinContent => Some(0.0),
+                        AvailableSpace::MaxContent => None,
+                    });
+                    match measure_fn(&measure_context, max_width) {
+                        Ok(size) => taffy::Size {
+                            width: known_dimensions.width.unwrap_or(size.width),
+                            height: known_dimensions.height.unwrap_or(size.height),
+                        },
+                        Err(error) => {
+                            measure_error = Some(error);
+                            taffy::Size::zero()
+                        }
+                    }
+                },
+            )?;
+            if let Some(error) = measure_error {
+                return Err(error);
+            }
+
+            // Set absolute position and bounds for each changed node
+            let mut todo = vec![(Point::zero(), None, root_layout_node)];
+            // Layers come last, as anchored layers are placed below their anchors
+            let mut layers = VecDeque::new();
+            while let Some((parent_origin, viewport, node_id)) =
+                todo.pop().or_else(|| layers.pop_front())
+            {
+                let node_index = *layout_tree.taffy.get_node_context(node_id).unwrap();
+                let layout = *layout_tree.taffy.layout(node_id)?;
+                let origin = parent_origin + Vector::new(layout.location.x, layout.location.y);
+                let bounds = Rect::new(origin, Size::new(layout.size.width, layout.size.height));
+                let layer_anchor = layer_anchors.get(&node_id);
+                // Unchanged subtrees that did not move keep their positions, unless their anchor moved
+                if !layout_tree.update_position(&node_index, bounds, viewport)
+                    && layer_anchor.is_none()
+                {
+                    continue;
+                }
+                // Offset of the children of layers, which are moved to their anchor
+                let layer_offset = match layer_anchor {
+                    Some(anchor) => {
+                        let children_bounds = layout_tree
+                            .taffy
+                            .children(node_id)?
+                            .into_iter()
+                            .map(|child| {
+                                let layout = layout_tree.taffy.layout(child)?;
+                                Ok(Rect::new(
+                                    origin + Vector::new(layout.location.x, layout.location.y),
+                                    Size::new(layout.size.width, layout.size.height),
+                                ))
+                            })
+                            .collect::<ViuiResult<Vec<_>>>()?
+                            .into_iter()
+                            .reduce(|bounds, child_bounds| bounds.union(&child_bounds))
+                            .unwrap_or(Rect::new(bounds.center(), Size::zero()));
+                        let placed_bounds = match anchor {
+                            Some(anchor) => place_below(
+                                children_bounds,
+                                &self.node_arena[anchor].layout.bounds,
+                                &bounds,
+                            ),
+                            None => children_bounds,
+                        };
+                        Some(placed_bounds.origin - children_bounds.origin)
+                    }
+                    None => None,
+                };
+                let node = &mut self.node_arena[&node_index];
+                node.set_bounds(bounds);
+                node.layout.content_size =
+                    Size::new(layout.content_size.width, layout.content_size.height);
+                node.layout.viewport = viewport;
+                let (children_origin, children_viewport) = match scroll_offsets.get(&node_id) {
+                    Some(offset) => {
+                        let visible_size = Size::new(
+                            layout.size.width - layout.scrollbar_size.width,
+                            layout.size.height - layout.scrollbar_size.height,
+                        );
+                        let offset =
+                            clamp_scroll_offset(*offset, node.layout.content_size, visible_size);
+                        let visible_rect = Rect::new(origin, visible_size);
+                        let children_viewport = match viewport {
+                            Some(viewport) => visible_rect.intersection(&viewport),
+                            None => Some(visible_rect),
+                        }
+                        .unwrap_or_default();
+                        (origin - offset, Some(children_viewport))
+                    }
+                    None => (origin, viewport),
+                };
+                let children_origin = match layer_offset {
+                    Some(offset) => children_origin + offset,
+                    None => children_origin,
+                };
+                let children = layout_tree.taffy.children(node_id)?;
+                let children_bounds = children
+                    .iter()
+                    .map(|child| {
+                        let layout = layout_tree.taffy.layout(*child)?;
+                        Ok(Rect::new(
+                            children_origin + Vector::new(layout.location.x, layout.location.y),
+                            Size::new(layout.size.width, layout.size.height),
+                        ))
+                    })
+                    .collect::<ViuiResult<Vec<_>>>()?;
+                self.needs_layout_pass |=
+                    self.node_registry.layout_updated(node, &children_bounds)?;
+                for child in children {
+                    if layer_anchors.contains_key(&child) {
+                        layers.push_back((Point::zero(), None, child));
+                    } else {
+                        todo.push((children_origin, children_viewport, child));
+                    }
                 }
             }
         }
         self.render_backends = render_backends;
-        Ok(())
+        self.update_layer_focus()
     }
 
+    /// Discards the cached layout trees, so that the next layout pass lays out every node again
+    pub fn invalidate_layout(&mut self) {
+        for backend in &mut self.render_backends {
+            backend.layout_tree.invalidate();
+        }
+    }
+
+    /// Creates the render commands for the next frame of the backend, reusing cached node commands
+    ///
+    /// Unless the backend needs a full frame, only the area damaged by changed nodes is redrawn.
+    /// Returns `None` if nothing needs to be redrawn.
     fn make_render_commands(
         &mut self,
         backend: &mut RenderBackend,
-    ) -> ViuiResult<Vec<RenderCommand>> {
+    ) -> ViuiResult<Option<Vec<RenderCommand>>> {
         let time = self.start.elapsed().as_secs_f32();
 
         let animated_nodes = &mut self.animated_nodes;
@@ -726,55 +1702,230 @@ impl UI {
         let mut render_context =
             RenderContext::new(&mut self.image_pool, &mut self.font_pool, time)?;
         render_context.add_commands(initial_commands);
-        render_context.add_command(RenderCommand::SetFont {
-            font_idx: self.styling.font_face,
-        });
+        let load_command_count = render_context.command_count();
 
-        render_context.add_command(RenderCommand::SetFillColor(self.styling.background_color));
-        render_context.add_command(RenderCommand::SetWindowSize {
-            size: backend.window_size,
-        });
-        render_context.add_command(RenderCommand::FillRect {
-            rect: Rect::new(Point::new(0.0, 0.0), backend.window_size),
-        });
+        let full_frame = !backend.render_cache.has_frame;
+        if full_frame {
+            backend.render_cache.invalidate();
+        }
+        let window_rect = Rect::from_size(backend.window_size);
+        // Allows culling of nodes outside the window
+        render_context.add_command(RenderCommand::ClipRect(window_rect));
         let render_parameters = RenderParameters::new(&self.styling)?;
-        let mut todo = vec![self.root_item_idx];
-        while let Some(item_idx) = todo.pop() {
+        let mut previous_nodes = take(&mut backend.render_cache.nodes);
+        let mut damage = DamageRect::default();
+        let mut rendered_nodes = vec![];
+        let mut parents = HashMap::new();
+        let mut todo = vec![(self.root_item_idx, None)];
+        // Layers are rendered after the main tree, so that they are drawn and hit above it
+        let mut layers = VecDeque::new();
+        let mut deferred_layers = HashSet::new();
+        while let Some((item_idx, parent)) = todo.pop().or_else(|| layers.pop_front()) {
             let item = &self.item_arena[&item_idx];
             match &item.kind {
                 NodeItemKind::Node(node_idx) => {
-                    let node = &self.node_arena[&node_idx];
-                    render_context.add_command(RenderCommand::Save);
-                    render_context.add_command(RenderCommand::Translate {
-                        x: node.layout.bounds.origin.x,
-                        y: node.layout.bounds.origin.y,
-                    });
-                    render_context.add_command(RenderCommand::ClipRect(Rect::new(
-                        Point::new(0.0, 0.0),
-                        node.layout.bounds.size,
-                    )));
-                    render_context.set_active(self.active_nodes.contains(node_idx));
-                    self.node_registry.render_node(
-                        &mut render_context,
-                        &render_parameters,
-                        node,
-                    )?;
-                    if render_context.reset_animated() {
-                        animated_nodes.push(*node_idx);
+                    let node = &self.node_arena[node_idx];
+                    if self.layers.contains(node_idx) && deferred_layers.insert(*node_idx) {
+                        layers.push_back((item_idx, parent));
+                        continue;
+                    }
+                    let is_active = self.active_nodes.contains(node_idx);
+                    let is_focused = self.focused_node == Some(*node_idx);
+                    let cached = match previous_nodes.remove(node_idx) {
+                        Some(cached) if cached.is_valid_for(node, is_active, is_focused) => cached,
+                        previous => {
+                            if let Some(previous) = previous {
+                                previous.damage_bounds().for_each(|rect| damage.add(rect));
+                            }
+                            let start = render_context.command_count();
+                            render_context.add_command(RenderCommand::Save);
+                            if let Some(viewport) = node.layout.viewport {
+                                render_context.add_command(RenderCommand::ClipRect(viewport));
+                            }
+                            render_context.add_command(RenderCommand::Translate {
+                                x: node.layout.bounds.origin.x,
+                                y: node.layout.bounds.origin.y,
+                            });
+                            render_context.add_command(RenderCommand::ClipRect(Rect::new(
+                                Point::new(0.0, 0.0),
+                                node.layout.bounds.size,
+                            )));
+                            let transform = *render_context.transform();
+                            let clip_bounds = render_context.clip_bounds().copied();
+                            render_context.set_size(node.layout.bounds.size);
+                            render_context.set_active(is_active);
+                            render_context.set_focused(is_focused);
+                            self.node_registry.render_node(
+                                &mut render_context,
+                                &render_parameters,
+                                node,
+                            )?;
+                            if is_focused {
+                                render_context
+                                    .draw_focus_ring(render_parameters.styling().highlight_color);
+                            }
+                            let is_animated = render_context.reset_animated();
+                            if is_animated {
+                                animated_nodes.push(*node_idx);
+                            }
+                            render_context.add_command(RenderCommand::Restore);
+                            let mut cached = CachedNodeCommands::new(
+                                node,
+                                is_active,
+                                is_focused,
+                                is_animated,
+                                render_context.split_off_commands(start),
+                                transform,
+                                clip_bounds,
+                            );
+                            cached.popup = render_context.take_popup();
+                            cached.damage_bounds().for_each(|rect| damage.add(rect));
+                            cached
+                        }
+                    };
+                    rendered_nodes.push((*node_idx, cached));
+                    if let Some(parent) = parent {
+                        parents.insert(*node_idx, parent);
                     }
-                    render_context.add_command(RenderCommand::Restore);
-                    todo.extend(node.children.iter());
+                    let parent = Some(*node_idx);
+                    todo.extend(
+                        self.node_registry
+                            .shown_children(node)
+                            .iter()
+                            .rev()
+                            .map(|child| (*child, parent)),
+                    );
                 }
                 NodeItemKind::If(if_item) => {
                     if if_item.condition {
-                        todo.push(if_item.then_item);
+                        todo.push((if_item.then_item, parent));
                     }
                 }
-                NodeItemKind::Block(block_item) => todo.extend(block_item.items.iter()),
-                NodeItemKind::For(for_item) => todo.extend(for_item.items.iter()),
+                NodeItemKind::Block(block_item) => {
+                    todo.extend(block_item.items.iter().rev().map(|item| (*item, parent)))
+                }
+                NodeItemKind::For(for_item) => {
+                    todo.extend(for_item.items.iter().rev().map(|item| (*item, parent)))
+                }
+            }
+        }
+        // Nodes no longer shown leave damage behind
+        for cached in previous_nodes.values() {
+            cached.damage_bounds().for_each(|rect| damage.add(rect));
+        }
+        // Remember where nodes ended up on screen for hit testing
+        for (node_idx, cached) in &rendered_nodes {
+            let layout = &mut self.node_arena[node_idx].layout;
+            layout.transform = cached.transform;
+            layout.clip_bounds = cached.clip_bounds;
+            layout.popup_bounds = cached.popup.as_ref().map(|popup| popup.screen_bounds);
+        }
+        // Popups are above all nodes
+        let popups = rendered_nodes.iter().filter_map(|(node_idx, cached)| {
+            let popup = cached.popup.as_ref()?;
+            Some((*node_idx, popup.screen_bounds))
+        });
+        self.hit_index = HitIndex::new(
+            rendered_nodes
+                .iter()
+                .map(|(node_idx, cached)| (*node_idx, cached.screen_bounds))
+                .chain(popups),
+            parents,
+        );
+
+        // The tooltip is drawn above all nodes once the pointer rested on its node long enough
+        let mut tooltip = None;
+        if let Some(pending) = self.tooltip.as_ref().filter(|tooltip| !tooltip.dismissed) {
+            let text = self
+                .node_arena
+                .contains(&pending.node_idx)
+                .then(|| {
+                    self.node_arena[&pending.node_idx]
+                        .common_props
+                        .tooltip
+                        .clone()
+                })
+                .flatten();
+            match text {
+                Some(text) if pending.is_due() => {
+                    let text_size = render_context.measure_text(&text)?;
+                    let text_size = Size::new(text_size.width, text_size.height);
+                    tooltip = Some((text, pending.bounds(text_size, &window_rect)));
+                }
+                // Redrawn by the ticker until the delay passed
+                Some(_) => animated_nodes.push(pending.node_idx),
+                None => {}
+            }
+        }
+        if tooltip != backend.render_cache.tooltip {
+            for (_, bounds) in tooltip.iter().chain(&backend.render_cache.tooltip) {
+                damage.add(bounds);
+            }
+        }
+
+        let damage_rect = if full_frame {
+            Some(window_rect)
+        } else {
+            damage.rect()
+        };
+        let Some(damage_rect) = damage_rect else {
+            backend.render_cache.nodes = rendered_nodes.into_iter().collect();
+            backend.render_cache.tooltip = tooltip;
+            // Nothing to redraw, but resources still need to be loaded
+            let mut commands = render_context.render_queue();
+            commands.truncate(load_command_count);
+            return Ok((!commands.is_empty()).then_some(commands));
+        };
+        render_context.add_command(RenderCommand::SetFont {
+            font_idx: self.styling.font_face,
+        });
+        if full_frame {
+            render_context.add_command(RenderCommand::SetWindowSize {
+                size: backend.window_size,
+            });
+        }
+        render_context.add_command(RenderCommand::ClipRect(damage_rect));
+        render_context.add_command(RenderCommand::SetFillColor(self.styling.background_color));
+        render_context.add_command(RenderCommand::FillRect { rect: window_rect });
+        for (_, cached) in &rendered_nodes {
+            if cached.screen_bounds.intersects(&damage_rect) {
+                render_context.add_commands(cached.commands.iter().cloned());
             }
         }
-        Ok(render_context.render_queue())
+        for popup in rendered_nodes
+            .iter()
+            .filter_map(|(_, cached)| cached.popup.as_ref())
+        {
+            if popup.screen_bounds.intersects(&damage_rect) {
+                render_context.add_commands(popup.commands.iter().cloned());
+            }
+        }
+        if let Some((text, bounds)) = &tooltip {
+            if bounds.intersects(&damage_rect) {
+                let text_ascent = render_context.text_ascent();
+                render_context.add_commands([
+                    RenderCommand::Save,
+                    RenderCommand::SetFillColor(self.styling.text_color),
+                    RenderCommand::FillRoundRect {
+                        rect: *bounds,
+                        radius: 3.0,
+                    },
+                    RenderCommand::SetStrokeColor(self.styling.background_color),
+                    RenderCommand::Translate {
+                        x: bounds.min_x() + TOOLTIP_PADDING,
+                        y: bounds.min_y() + TOOLTIP_PADDING + text_ascent,
+                    },
+                    RenderCommand::DrawText(text.clone()),
+                    RenderCommand::Restore,
+                ]);
+            }
+        }
+        backend.render_cache.tooltip = tooltip;
+        backend.render_cache.nodes = rendered_nodes.into_iter().collect();
+        backend.render_cache.has_frame = true;
+        Ok(Some(optimize_render_commands(
+            render_context.render_queue(),
+        )))
     }
 
     pub fn set_node_prop(
@@ -791,11 +1942,23 @@ impl UI {
             });
     }
 
-    pub fn set_event_mapping(&mut self, node_index: &NodeIdx, event: &str, message: ExpressionAst) {
-        self.node_arena
+    pub fn set_event_mapping(
+        &mut self,
+        node_index: &NodeIdx,
+        event: &str,
+        modifiers: &[String],
+        message: ExpressionAst,
+    ) -> ViuiResult<()> {
+        let mapping = EventMapping::new(message, modifiers)?;
+        let mappings = self
+            .node_arena
             .index_mut(node_index)
             .event_mappings
-            .insert(event.to_string(), message);
+            .entry(event.to_string())
+            .or_default();
+        mappings.retain(|existing| existing.capture != mapping.capture);
+        mappings.push(mapping);
+        Ok(())
     }
     pub fn set_root_node_file<P: AsRef<Path>>(&mut self, root_path: P) -> ViuiResult<()> {
         context!("set root context file {:?}", self.root_node_file => {
@@ -813,16 +1976,20 @@ impl UI {
         context!("load root context file {:?}", self.root_node_file => {
             let mut string = String::new();
             File::open(&self.root_node_file)?.read_to_string(&mut string)?;
-            let ast = parse_ui(&string)?;
-            let ast_data = ast.into_data();
-            for component in &ast_data.components {
-                self.register_component_node(component);
-            }
-            self.set_root_node()?;
-            Ok(())
+            self.load_ui_source(&string)
         })
     }
 
+    pub fn load_ui_source(&mut self, source: &str) -> ViuiResult<()> {
+        let ast = parse_ui(source)?;
+        let ast_data = ast.into_data();
+        for component in &ast_data.components {
+            self.register_component_node(component);
+        }
+        self.set_root_node()?;
+        Ok(())
+    }
+
     pub fn set_root_node(&mut self) -> ViuiResult<()> {
         self.node_arena.clear();
         self.item_arena.clear();
@@ -846,7 +2013,11 @@ impl UI {
             kind_index,
             state: (component.make_state)()?,
             props: (component.make_props)()?,
+            props_revision: 0,
+            state_revision: 0,
+            common_props: CommonProps::default(),
             layout: LayoutInfo::default(),
+            layout_props: LayoutProps::default(),
             prop_expressions: Vec::new(),
             event_mappings: Default::default(),
             children,
@@ -856,23 +2027,29 @@ impl UI {
     fn create_children(&mut self, child: &ItemAst) -> ViuiResult<ItemIdx> {
         Ok(match child.data() {
             ItemDefinition::Node { node } => {
+                let expanded_node = self.node_registry.expand_template(node)?;
+                let node = expanded_node.as_ref().unwrap_or(node);
                 let child = node.data();
                 let node_idx = self.create_node(&child.tag)?;
                 for prop in &child.props {
                     self.set_node_prop(&node_idx, &prop.name, prop.expression.clone());
                 }
                 for event in &child.events {
-                    self.set_event_mapping(&node_idx, &event.name, event.expression.clone());
+                    self.set_event_mapping(
+                        &node_idx,
+                        &event.name,
+                        &event.modifiers,
+                        event.expression.clone(),
+                    )?;
                 }
                 let mut children = vec![];
                 for child in &child.children {
                     children.push(self.create_children(child)?);
                 }
                 self.add_children(&node_idx, children);
-                let item_idx = self.item_arena.insert(NodeItem {
+                self.item_arena.insert(NodeItem {
                     kind: NodeItemKind::Node(node_idx),
-                });
-                item_idx
+                })
             }
             ItemDefinition::If(if_item) => {
                 let item = NodeItem {
@@ -887,8 +2064,7 @@ impl UI {
                             .transpose()?,
                     }),
                 };
-                let item_idx = self.item_arena.insert(item);
-                item_idx
+                self.item_arena.insert(item)
             }
             ItemDefinition::Block { items } => {
                 let item = NodeItem {
@@ -899,8 +2075,7 @@ impl UI {
                             .collect::<ViuiResult<Vec<ItemIdx>>>()?,
                     }),
                 };
-                let item_idx = self.item_arena.insert(item);
-                item_idx
+                self.item_arena.insert(item)
             }
             ItemDefinition::For(for_item) => {
                 let item = NodeItem {
@@ -909,24 +2084,19 @@ impl UI {
                         expression: for_item.expression.clone(),
                         item_template: self.create_children(&for_item.each_item)?,
                         items: vec![],
+                        item_indices: vec![],
+                        spare_items: vec![],
+                        owner: None,
                     }),
                 };
-                let item_idx = self.item_arena.insert(item);
-                item_idx
+                self.item_arena.insert(item)
             }
         })
     }
 
     fn register_component_node(&mut self, component_ast: &ComponentAst) {
-        self.node_registry.register(
-            &component_ast.name,
-            || Ok(Box::new(())),
-            || Ok(Box::new(())),
-            |_, _, _| Ok(()),
-            |_, _, _| Ok(()),
-            |_, _| Ok(LayoutConstraints::Passthrough {}),
-            component_ast.children.clone(),
-        )
+        self.node_registry
+            .register_component(&component_ast.name, component_ast.children.clone());
     }
 
     fn add_children(&mut self, parent: &NodeIdx, children: Vec<ItemIdx>) {
@@ -934,15 +2104,272 @@ impl UI {
     }
 }
 
+/// Keyboard shortcut declared by a `shortcut` node
+#[derive(Clone, Debug)]
+pub struct Shortcut {
+    pub keys: KeyCombination,
+    pub description: String,
+    node_idx: NodeIdx,
+    /// Node that has to contain the focus for the shortcut to apply, `None` if app-wide
+    scope: Option<NodeIdx>,
+}
+
+/// Tooltip of a hovered node, see [`UI::update_tooltip`]
+struct Tooltip {
+    node_idx: NodeIdx,
+    /// Pointer position the tooltip is placed next to
+    position: Point,
+    hovered_since: Instant,
+    /// Hidden after pressing or scrolling, until the pointer enters another node
+    dismissed: bool,
+}
+
+impl Tooltip {
+    /// Whether the pointer rested on the node for long enough
+    fn is_due(&self) -> bool {
+        self.hovered_since.elapsed() >= TOOLTIP_DELAY
+    }
+
+    /// Places the bubble below and right of the pointer, flipped to the other side of the pointer
+    /// where it would leave the window
+    fn bounds(&self, text_size: Size, window: &Rect) -> Rect {
+        let size = Size::new(
+            text_size.width + 2.0 * TOOLTIP_PADDING,
+            text_size.height + 2.0 * TOOLTIP_PADDING,
+        );
+        let mut origin = self.position + Vector::new(TOOLTIP_OFFSET, TOOLTIP_OFFSET);
+        if origin.x + size.width > window.max_x() {
+            origin.x = self.position.x - TOOLTIP_OFFSET - size.width;
+        }
+        if origin.y + size.height > window.max_y() {
+            origin.y = self.position.y - TOOLTIP_OFFSET - size.height;
+        }
+        Rect::new(origin.max(window.origin), size)
+    }
+}
+
+/// Places the children of an anchored layer below the anchor, or above it if there is no room
+/// below, keeping them inside the window if possible
+fn place_below(children_bounds: Rect, anchor: &Rect, window: &Rect) -> Rect {
+    let size = children_bounds.size;
+    let below = anchor.max_y() + PANEL_PADDING;
+    let y = if below + size.height + PANEL_PADDING > window.max_y() {
+        (anchor.min_y() - PANEL_PADDING - size.height).max(window.min_y() + PANEL_PADDING)
+    } else {
+        below
+    };
+    let x = (anchor.min_x() + PANEL_PADDING)
+        .min(window.max_x() - PANEL_PADDING - size.width)
+        .max(window.min_x() + PANEL_PADDING);
+    Rect::new(Point::new(x, y), size)
+}
+
+fn to_align(alignment: Alignment) -> AlignItems {
+    match alignment {
+        Alignment::Start => AlignItems::Start,
+        Alignment::Center => AlignItems::Center,
+        Alignment::End => AlignItems::End,
+    }
+}
+
+fn to_justify(alignment: Alignment) -> JustifyContent {
+    match alignment {
+        Alignment::Start => JustifyContent::Start,
+        Alignment::Center => JustifyContent::Center,
+        Alignment::End => JustifyContent::End,
+    }
+}
+
+/// Maps the constraints of an element to the style of its layout node
+fn to_node_constraints(
+    layout_constraints: LayoutConstraints,
+    is_animated: bool,
+) -> NodeConstraints {
+    let is_overlay = matches!(layout_constraints, LayoutConstraints::OverlayLayout {});
+    let mut measure_fn = None;
+    let mut scroll_offset = None;
+    let mut list_row_height = None;
+    let mut layer = None;
+    let style = match layout_constraints {
+        LayoutConstraints::FixedLayout { width, height } => Some(Style {
+            size: taffy::Size {
+                width: length(width),
+                height: length(height),
+            },
+            ..Default::default()
+        }),
+        LayoutConstraints::HorizontalLayout {} => Some(Style {
+            flex_direction: FlexDirection::Row,
+            size: taffy::Size::auto(),
+            ..Default::default()
+        }),
+        LayoutConstraints::Measured(function) => {
+            measure_fn = Some(function);
+            Some(Style::default())
+        }
+        LayoutConstraints::VerticalLayout {} => Some(Style {
+            flex_direction: FlexDirection::Column,
+            ..Default::default()
+        }),
+        LayoutConstraints::GridLayout { columns } => Some(Style {
+            display: Display::Grid,
+            grid_template_columns: vec![fr(1.0); columns],
+            ..Default::default()
+        }),
+        LayoutConstraints::Hidden => Some(Style {
+            display: Display::None,
+            ..Default::default()
+        }),
+        LayoutConstraints::FlexibleSpace => Some(Style {
+            flex_grow: 1.0,
+            ..Default::default()
+        }),
+        LayoutConstraints::OverlayLayout {} => Some(Style {
+            display: Display::Grid,
+            ..Default::default()
+        }),
+        LayoutConstraints::LayerLayout { anchored } => {
+            layer = Some(anchored);
+            // Anchored children are moved below the anchor once positioned
+            let (align, justify) = if anchored {
+                (AlignItems::Start, JustifyContent::Start)
+            } else {
+                (AlignItems::Center, JustifyContent::Center)
+            };
+            Some(Style {
+                position: Position::Absolute,
+                inset: taffy::Rect {
+                    left: length(0.0),
+                    right: length(0.0),
+                    top: length(0.0),
+                    bottom: length(0.0),
+                },
+                flex_direction: FlexDirection::Column,
+                align_items: Some(align),
+                justify_content: Some(justify),
+                ..Default::default()
+            })
+        }
+        LayoutConstraints::BoxLayout(box_layout) => {
+            // Clipped children are given a viewport like scrolled children
+            let overflow = if box_layout.clip {
+                scroll_offset = Some(Vector::zero());
+                Overflow::Hidden
+            } else {
+                Overflow::Visible
+            };
+            let dimension = |size: Option<Float>| size.map_or(taffy::Dimension::Auto, length);
+            let padding = box_layout.padding;
+            let has_size = box_layout.width.is_some() || box_layout.height.is_some();
+            Some(Style {
+                flex_direction: if box_layout.horizontal {
+                    FlexDirection::Row
+                } else {
+                    FlexDirection::Column
+                },
+                overflow: taffy::Point {
+                    x: overflow,
+                    y: overflow,
+                },
+                size: taffy::Size {
+                    width: dimension(box_layout.width),
+                    height: dimension(box_layout.height),
+                },
+                padding: taffy::Rect {
+                    left: length(padding.left),
+                    right: length(padding.right),
+                    top: length(padding.top),
+                    bottom: length(padding.bottom),
+                },
+                align_items: box_layout.align.map(to_align),
+                justify_content: Some(to_justify(box_layout.justify)),
+                flex_grow: if box_layout.fill { 1.0 } else { 0.0 },
+                // Like a list, it would otherwise grow to fit all its content
+                flex_basis: if box_layout.fill { length(0.0) } else { auto() },
+                flex_shrink: if has_size { 0.0 } else { 1.0 },
+                ..Default::default()
+            })
+        }
+        LayoutConstraints::AlignedLayout {
+            horizontal,
+            vertical,
+        } => Some(Style {
+            flex_direction: FlexDirection::Column,
+            flex_grow: 1.0,
+            align_items: Some(to_align(horizontal)),
+            justify_content: Some(to_justify(vertical)),
+            ..Default::default()
+        }),
+        LayoutConstraints::ScrollLayout { offset } => {
+            scroll_offset = Some(offset);
+            Some(Style {
+                flex_direction: FlexDirection::Column,
+                overflow: taffy::Point {
+                    x: Overflow::Scroll,
+                    y: Overflow::Scroll,
+                },
+                scrollbar_width: SCROLLBAR_WIDTH,
+                ..Default::default()
+            })
+        }
+        LayoutConstraints::VirtualListLayout {
+            offset,
+            space_before,
+            space_after,
+            row_height,
+        } => {
+            scroll_offset = Some(offset);
+            list_row_height = Some(row_height);
+            Some(Style {
+                flex_direction: FlexDirection::Column,
+                // Fills the free space, as it would otherwise grow to fit all rows
+                flex_grow: 1.0,
+                flex_basis: length(0.0),
+                // The reserved space would otherwise make it as tall as all rows
+                max_size: taffy::Size {
+                    width: taffy::Dimension::Auto,
+                    height: percent(1.0),
+                },
+                overflow: taffy::Point {
+                    x: Overflow::Scroll,
+                    y: Overflow::Scroll,
+                },
+                scrollbar_width: SCROLLBAR_WIDTH,
+                padding: taffy::Rect {
+                    left: length(0.0),
+                    right: length(0.0),
+                    top: length(space_before),
+                    bottom: length(space_after),
+                },
+                ..Default::default()
+            })
+        }
+        LayoutConstraints::Passthrough => None,
+    };
+    NodeConstraints {
+        is_animated,
+        style,
+        measure_fn,
+        is_overlay,
+        scroll_offset,
+        list_row_height,
+        layer,
+    }
+}
+
 fn reflect_to_value(value: &dyn Reflect) -> ViuiResult<ExpressionValue> {
     match value.reflect_ref() {
         ReflectRef::List(list) => {
             let values = list
                 .iter()
-                .map(|value| reflect_to_value(value))
+                .map(reflect_to_value)
                 .collect::<ViuiResult<Vec<_>>>()?;
             return Ok(ExpressionValue::Vec(values));
         }
+        // Fields are looked up once used, such as `file.name` in a loop over files
+        ReflectRef::Struct(_) => {
+            return Ok(ExpressionValue::Reflect(Arc::from(value.clone_value())));
+        }
         _ => {}
     }
     if let Some(value) = value.downcast_ref::<f32>() {
@@ -961,6 +2388,37 @@ fn reflect_to_value(value: &dyn Reflect) -> ViuiResult<ExpressionValue> {
     }
 }
 
+/// List in the app state iterated by a `for` item, converting the rows once picked
+struct ReflectedList<'a>(&'a dyn List);
+
+impl ForCollection for ReflectedList<'_> {
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    fn value(&self, index: usize) -> ViuiResult<ExpressionValue> {
+        let Some(value) = self.0.get(index) else {
+            bail!(
+                "Index {} out of bounds for list of length {}",
+                index,
+                self.0.len()
+            );
+        };
+        reflect_to_value(value)
+    }
+}
+
+/// Field of a value bound in a `for` loop, `None` if it is missing
+fn entry_field(entry: &ExpressionValue, field: &str) -> ViuiResult<Option<ExpressionValue>> {
+    let ExpressionValue::Reflect(entry) = entry else {
+        return Ok(None);
+    };
+    match entry.reflect_path(field) {
+        Ok(value) => Ok(Some(reflect_to_value(value)?)),
+        Err(_) => Ok(None),
+    }
+}
+
 fn eval_expression(
     app_state: &dyn Reflect,
     converter: &MessageStringToEnumConverter,
@@ -971,11 +2429,2021 @@ fn eval_expression(
         if let Some(value) = lookup(name)? {
             return Ok(value);
         }
+        if let Some((binding, path)) = name.split_once('.') {
+            if let Some(ExpressionValue::Reflect(value)) = lookup(binding)? {
+                return reflect_to_value(value.reflect_path(path)?);
+            }
+        }
         if let Ok(value) = app_state.reflect_path(name) {
             reflect_to_value(value)
         } else {
-            converter(name)
+            match name {
+                "true" => Ok(ExpressionValue::Bool(true)),
+                "false" => Ok(ExpressionValue::Bool(false)),
+                _ => converter(name),
+            }
         }
     })?;
     Ok(value)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nodes::elements::button::{ButtonElementProps, ButtonElementState};
+    use crate::nodes::elements::checkbox::CheckboxElementProps;
+    use crate::nodes::elements::collapsible::{CollapsibleElementProps, CollapsibleElementState};
+    use crate::nodes::elements::dropdown::{DropdownElementProps, DropdownElementState};
+    use crate::nodes::elements::label::LabelElementProps;
+    use crate::nodes::elements::list::{ListElementProps, ListElementState, OVERSCAN};
+    use crate::nodes::elements::number_input::{NumberInputElementProps, NumberInputElementState};
+    use crate::nodes::elements::radio::{RadioElementProps, RadioElementState};
+    use crate::nodes::elements::scroll::ScrollElementState;
+    use crate::nodes::elements::table::{TableRowElementProps, TableRowElementState};
+    use crate::nodes::elements::tabs::{TabsElementProps, TabsElementState};
+    use crate::nodes::elements::textinput::{TextInputElementProps, TextInputElementState};
+    use crate::nodes::events::Modifiers;
+    use crate::observable_state::TypedPath;
+    use crate::render::command::DrawMode;
+    use crate::types::{Color, Float};
+    use bevy_reflect::ParsedPath;
+    use itertools::Itertools;
+    use serde::Deserialize;
+
+    #[derive(Debug, Reflect)]
+    struct TestState {
+        counter: i32,
+        rows: Vec<String>,
+        /// Entries of `Log` messages
+        log: Vec<String>,
+        enabled: bool,
+        mode: String,
+        gain: Float,
+        files: Vec<TestFile>,
+    }
+
+    #[derive(Debug, Reflect)]
+    #[reflect(no_field_bounds)]
+    struct TestFile {
+        name: String,
+        children: Vec<TestFile>,
+    }
+
+    fn test_file(name: &str, children: Vec<TestFile>) -> TestFile {
+        TestFile {
+            name: name.to_string(),
+            children,
+        }
+    }
+
+    #[derive(Debug, Reflect, Deserialize)]
+    enum TestMessage {
+        Increment,
+        Log(String),
+        SetEnabled(bool),
+        SetMode(String),
+        SetGain(Float),
+    }
+
+    fn make_ui(source: &str) -> (UI, Receiver<RenderBackendMessage>) {
+        let mut ui = new_ui();
+        ui.load_ui_source(source).unwrap();
+        let parameters = ui.add_render_backend().unwrap();
+        (ui, parameters.message_receiver)
+    }
+
+    fn new_ui() -> UI {
+        new_ui_with_rows(vec![])
+    }
+
+    fn new_ui_with_rows(rows: Vec<String>) -> UI {
+        let counter_path = TypedPath::<i32>::new(ParsedPath::parse("counter").unwrap());
+        let log_path = TypedPath::<Vec<String>>::new(ParsedPath::parse("log").unwrap());
+        let enabled_path = TypedPath::<bool>::new(ParsedPath::parse("enabled").unwrap());
+        let mode_path = TypedPath::<String>::new(ParsedPath::parse("mode").unwrap());
+        let gain_path = TypedPath::<Float>::new(ParsedPath::parse("gain").unwrap());
+        UI::new(
+            ObservableState::new(TestState {
+                counter: 0,
+                rows,
+                log: vec![],
+                enabled: false,
+                mode: "light".to_string(),
+                gain: 0.0,
+                files: vec![
+                    test_file(
+                        "src",
+                        vec![
+                            test_file("main.rs", vec![]),
+                            test_file("ui", vec![test_file("mod.rs", vec![])]),
+                        ],
+                    ),
+                    test_file("Cargo.toml", vec![]),
+                ],
+            }),
+            "Test".to_string(),
+            move |state, message: &TestMessage| match message {
+                TestMessage::Increment => state.apply_change("Increment", |mutator| {
+                    mutator.mutate(&counter_path, |counter| *counter += 1);
+                }),
+                TestMessage::Log(entry) => state.apply_change("Log", |mutator| {
+                    mutator.mutate(&log_path, |log| log.push(entry.clone()));
+                }),
+                TestMessage::SetEnabled(enabled) => state.apply_change("SetEnabled", |mutator| {
+                    mutator.mutate(&enabled_path, |value| *value = *enabled);
+                }),
+                TestMessage::SetMode(mode) => state.apply_change("SetMode", |mutator| {
+                    mutator.mutate(&mode_path, |value| *value = mode.clone());
+                }),
+                TestMessage::SetGain(gain) => state.apply_change("SetGain", |mutator| {
+                    mutator.mutate(&gain_path, |value| *value = *gain);
+                }),
+            },
+        )
+        .unwrap()
+    }
+
+    fn increment(ui: &mut UI) {
+        (ui.event_handler)(&mut ui.app_state, &TestMessage::Increment);
+        ui.eval_layout_and_redraw().unwrap();
+    }
+
+    #[test]
+    fn redraws_only_changed_nodes() {
+        let (mut ui, receiver) = make_ui(
+            "component Test {
+                label(label=`Counter: ${counter}`)
+                label(label=\"Adjacent\")
+                label(label=\"Static\")
+            }",
+        );
+        let full_frame = receiver.try_recv().unwrap().render_commands;
+        assert!(full_frame.contains(&RenderCommand::DrawText("Counter: 0".to_string())));
+        assert!(full_frame.contains(&RenderCommand::DrawText("Static".to_string())));
+
+        // Nothing changed, so nothing is sent
+        ui.eval_layout_and_redraw().unwrap();
+        assert!(receiver.try_recv().is_err());
+
+        increment(&mut ui);
+        let partial_frame = receiver.try_recv().unwrap().render_commands;
+        assert!(partial_frame.contains(&RenderCommand::DrawText("Counter: 1".to_string())));
+        // Touched by the damaged area, so drawn again
+        assert!(partial_frame.contains(&RenderCommand::DrawText("Adjacent".to_string())));
+        assert!(!partial_frame.contains(&RenderCommand::DrawText("Static".to_string())));
+        let damage = partial_frame
+            .iter()
+            .find_map(|command| match command {
+                RenderCommand::ClipRect(rect) if rect.size != ui.render_backends[0].window_size => {
+                    Some(*rect)
+                }
+                _ => None,
+            })
+            .unwrap();
+        let label = ui
+            .node_arena
+            .entries()
+            .find(|node| node.tag == "label")
+            .unwrap();
+        assert!(damage.contains_rect(label.bounds()));
+    }
+
+    #[test]
+    fn window_resize_causes_full_redraw() {
+        let (mut ui, receiver) = make_ui(
+            "component Test {
+                label(label=\"Static\")
+            }",
+        );
+        receiver.try_recv().unwrap();
+        ui.handle_ui_event(UiEvent::window_resized(Size::new(800.0, 600.0), 0))
+            .unwrap();
+        ui.eval_layout_and_redraw().unwrap();
+        let full_frame = receiver.try_recv().unwrap().render_commands;
+        assert!(full_frame.contains(&RenderCommand::SetWindowSize {
+            size: Size::new(800.0, 600.0)
+        }));
+        assert!(full_frame.contains(&RenderCommand::DrawText("Static".to_string())));
+    }
+
+    fn bounds_of(ui: &UI, tag: &str) -> Vec<Rect> {
+        ui.node_arena
+            .entries()
+            .filter(|node| node.tag == tag)
+            .map(|node| *node.bounds())
+            .collect()
+    }
+
+    #[test]
+    fn layout_props_are_applied() {
+        let (ui, _receiver) = make_ui(
+            "component Test {
+                hstack(padding=\"10 20\" gap=5 margin=3) {
+                    label(label=\"A\")
+                    label(label=\"B\")
+                }
+            }",
+        );
+        let hstack = bounds_of(&ui, "hstack")[0];
+        assert_eq!(hstack.origin, Point::new(3.0, 3.0));
+        let labels = bounds_of(&ui, "label");
+        assert_eq!(labels[0].origin, Point::new(23.0, 13.0));
+        assert_eq!(labels[1].min_x(), labels[0].max_x() + 5.0);
+    }
+
+    #[test]
+    fn grid_layout_places_children_in_columns() {
+        let (ui, _receiver) = make_ui(
+            "component Test {
+                hstack(grid_template_columns=\"100 200\") {
+                    label(label=\"A\")
+                    label(label=\"B\")
+                    label(label=\"C\" grid_column=2)
+                }
+            }",
+        );
+        let labels = bounds_of(&ui, "label");
+        assert_eq!(labels[0].origin.x, 0.0);
+        assert_eq!(labels[1].origin.x, 100.0);
+        assert_eq!(labels[2].origin.x, 100.0);
+        assert!(labels[2].origin.y > labels[1].origin.y);
+    }
+
+    #[test]
+    fn invalid_layout_prop_is_an_error() {
+        let mut ui = new_ui();
+        ui.load_ui_source(
+            "component Test {
+                label(label=\"A\" align=\"middle\")
+            }",
+        )
+        .unwrap();
+        let error = ui.eval_expressions().unwrap_err();
+        assert!(error.to_string().contains("Invalid value 'middle'"));
+    }
+
+    #[test]
+    fn elements_are_sized_to_content() {
+        let (ui, _receiver) = make_ui(
+            "component Test {
+                hstack {
+                    button(label=\"OK\")
+                    button(label=\"Cancel\")
+                }
+                label(label=\"Single line\")
+                label(label=\"Some text that is too long for one line\" max_width=150)
+            }",
+        );
+        let buttons = bounds_of(&ui, "button");
+        assert!(buttons[0].width() < buttons[1].width());
+        assert_eq!(buttons[0].height(), buttons[1].height());
+        let labels = bounds_of(&ui, "label");
+        assert_eq!(labels[1].width(), 150.0);
+        // Wrapped to at least two lines
+        assert!(labels[1].height() > 1.5 * labels[0].height());
+    }
+
+    #[test]
+    fn container_elements_lay_out_children() {
+        let (ui, receiver) = make_ui(
+            "component Test {
+                hstack {
+                    vstack {
+                        label(label=\"A\")
+                        label(label=\"B\")
+                    }
+                    spacer()
+                    label(label=\"C\")
+                }
+                grid(columns=3) {
+                    button(label=\"1\")
+                    button(label=\"2\")
+                    button(label=\"3\")
+                    button(label=\"4\")
+                }
+                overlay {
+                    spinner()
+                    textinput(text=\"On top\")
+                }
+                box(horizontal=\"end\") {
+                    knob(label=\"Aligned\")
+                }
+            }",
+        );
+        let window_size = ui.render_backends[0].window_size;
+        let labels = bounds_of(&ui, "label");
+        assert_eq!(labels[0].origin.x, labels[1].origin.x);
+        assert_eq!(labels[1].min_y(), labels[0].max_y());
+        // The spacer pushes the last label to the right edge
+        assert_eq!(labels[2].max_x(), window_size.width);
+
+        let buttons = bounds_of(&ui, "button");
+        let column_width = window_size.width / 3.0;
+        assert_eq!(buttons[1].origin.x, buttons[0].origin.x + column_width);
+        assert_eq!(buttons[3].origin.x, buttons[0].origin.x);
+        assert!(buttons[3].origin.y > buttons[0].origin.y);
+
+        let spinner = bounds_of(&ui, "spinner")[0];
+        let textinput = bounds_of(&ui, "textinput")[0];
+        assert_eq!(spinner.origin, textinput.origin);
+        // Later children of the overlay are drawn on top
+        let commands = receiver.try_recv().unwrap().render_commands;
+        let spinner_arc = commands
+            .iter()
+            .position(|command| matches!(command, RenderCommand::Arc { .. }))
+            .unwrap();
+        let textinput_text = commands
+            .iter()
+            .position(|command| *command == RenderCommand::DrawText("On top".to_string()))
+            .unwrap();
+        assert!(textinput_text > spinner_arc);
+
+        let aligned_box = bounds_of(&ui, "box")[0];
+        let knob = bounds_of(&ui, "knob")[0];
+        assert_eq!(knob.max_x(), aligned_box.max_x());
+        assert_eq!(knob.center().y.round(), aligned_box.center().y.round());
+        assert_eq!(aligned_box.max_y(), window_size.height);
+    }
+
+    #[test]
+    fn invalid_alignment_is_an_error() {
+        let mut ui = new_ui();
+        ui.load_ui_source(
+            "component Test {
+                box(vertical=\"middle\")
+            }",
+        )
+        .unwrap();
+        let Err(error) = ui.add_render_backend() else {
+            panic!("Expected an error");
+        };
+        assert!(error.to_string().contains("Invalid alignment 'middle'"));
+    }
+
+    fn scroll_ui() -> (UI, Receiver<RenderBackendMessage>) {
+        let labels = (0..20)
+            .map(|index| format!("label(label=\"Row {index}\")"))
+            .join("\n");
+        let (mut ui, receiver) = make_ui(&format!(
+            "component Test {{
+                scroll(max_height=200) {{
+                    {labels}
+                }}
+            }}"
+        ));
+        ui.handle_ui_event(UiEvent::mouse_move(Point::new(50.0, 50.0)))
+            .unwrap();
+        (ui, receiver)
+    }
+
+    fn scroll_state(ui: &UI) -> (Float, Float) {
+        let node = ui
+            .node_arena
+            .entries()
+            .find(|node| node.tag == "scroll")
+            .unwrap();
+        let (state, _) = node
+            .cast_state_and_props::<ScrollElementState, ()>()
+            .unwrap();
+        (state.offset_y, state.content_height)
+    }
+
+    #[test]
+    fn mouse_wheel_scrolls_content() {
+        let (mut ui, _receiver) = scroll_ui();
+        let scroll = bounds_of(&ui, "scroll")[0];
+        assert_eq!(scroll.height(), 200.0);
+        let first_row = bounds_of(&ui, "label")[0];
+        assert_eq!(first_row.origin.y, 0.0);
+
+        ui.handle_ui_event(UiEvent::mouse_wheel(Vector::new(0.0, 100.0)))
+            .unwrap();
+        ui.eval_layout_and_redraw().unwrap();
+        let labels = bounds_of(&ui, "label");
+        assert_eq!(labels[0].origin.y, -100.0);
+        // Only the visible part of a partially scrolled out row is hit
+        let partially_visible_row = ui
+            .node_arena
+            .entries()
+            .filter(|node| node.tag == "label")
+            .find(|node| node.bounds().min_y() < 0.0 && node.bounds().max_y() > 0.0)
+            .unwrap();
+        assert!(!partially_visible_row
+            .layout
+            .contains(Point::new(50.0, -1.0)));
+        assert!(partially_visible_row.layout.contains(Point::new(50.0, 1.0)));
+
+        // The offset is limited to the content size
+        ui.handle_ui_event(UiEvent::mouse_wheel(Vector::new(0.0, 10000.0)))
+            .unwrap();
+        ui.eval_layout_and_redraw().unwrap();
+        let (offset, content_height) = scroll_state(&ui);
+        assert_eq!(offset, content_height - (200.0 - SCROLLBAR_WIDTH));
+        let last_row = *bounds_of(&ui, "label").last().unwrap();
+        assert_eq!(last_row.max_y(), 200.0 - SCROLLBAR_WIDTH);
+    }
+
+    #[test]
+    fn scrollbar_thumb_can_be_dragged() {
+        let (mut ui, _receiver) = scroll_ui();
+        let scroll = bounds_of(&ui, "scroll")[0];
+        let thumb_position = Point::new(scroll.max_x() - SCROLLBAR_WIDTH / 2.0, 5.0);
+        ui.handle_ui_event(UiEvent::mouse_move(thumb_position))
+            .unwrap();
+        ui.handle_ui_event(UiEvent::mouse_input(MouseEventKind::Pressed))
+            .unwrap();
+        ui.handle_ui_event(UiEvent::mouse_move(thumb_position + Vector::new(0.0, 20.0)))
+            .unwrap();
+        ui.handle_ui_event(UiEvent::mouse_input(MouseEventKind::Released))
+            .unwrap();
+        ui.eval_layout_and_redraw().unwrap();
+        let (offset, content_height) = scroll_state(&ui);
+        let visible_height = 200.0 - SCROLLBAR_WIDTH;
+        let thumb_length = visible_height * visible_height / content_height;
+        let expected_offset =
+            20.0 * (content_height - visible_height) / (visible_height - thumb_length);
+        assert!((offset - expected_offset).abs() < 0.01);
+        assert_eq!(bounds_of(&ui, "label")[0].origin.y, -offset);
+    }
+
+    fn list_ui(source: &str, rows: Vec<String>) -> (UI, Receiver<RenderBackendMessage>) {
+        let mut ui = new_ui_with_rows(rows);
+        ui.load_ui_source(source).unwrap();
+        let parameters = ui.add_render_backend().unwrap();
+        ui.handle_ui_event(UiEvent::mouse_move(Point::new(50.0, 50.0)))
+            .unwrap();
+        (ui, parameters.message_receiver)
+    }
+
+    /// Labels and bounds of the instantiated list rows
+    fn list_rows(ui: &UI) -> Vec<(String, Rect)> {
+        let for_item = ui
+            .item_arena
+            .entries()
+            .find_map(|item| match &item.kind {
+                NodeItemKind::For(for_item)
+                    if for_item
+                        .owner
+                        .is_some_and(|owner| ui.node_arena[&owner].tag == "list") =>
+                {
+                    Some(for_item)
+                }
+                _ => None,
+            })
+            .unwrap();
+        for_item
+            .items
+            .iter()
+            .map(|item_idx| {
+                let NodeItemKind::Block(block_item) = &ui.item_arena[item_idx].kind else {
+                    panic!("Expected block item");
+                };
+                let NodeItemKind::Node(node_idx) = ui.item_arena[&block_item.items[0]].kind else {
+                    panic!("Expected node item");
+                };
+                let node = &ui.node_arena[&node_idx];
+                let (_, props) = node
+                    .cast_state_and_props::<(), LabelElementProps>()
+                    .unwrap();
+                (props.label.clone(), *node.bounds())
+            })
+            .collect()
+    }
+
+    fn label_node_count(ui: &UI) -> usize {
+        ui.node_arena
+            .entries()
+            .filter(|node| node.tag == "label")
+            .count()
+    }
+
+    fn scroll_list(ui: &mut UI, delta: Float) {
+        ui.handle_ui_event(UiEvent::mouse_wheel(Vector::new(0.0, delta)))
+            .unwrap();
+        ui.eval_layout_and_redraw().unwrap();
+    }
+
+    #[test]
+    fn list_instantiates_only_visible_rows() {
+        let rows = (0..100_000).map(|index| format!("Row {index}")).collect();
+        let (mut ui, _receiver) = list_ui(
+            "component Test {
+                list(max_height=200 row_height=40) {
+                    for (row in rows) {
+                        label(label=row)
+                    }
+                }
+            }",
+            rows,
+        );
+        // Five visible rows, the overscan below and the row template
+        assert_eq!(label_node_count(&ui), 5 + OVERSCAN + 1);
+        let rows = list_rows(&ui);
+        assert_eq!(rows[0].0, "Row 0");
+        assert_eq!(rows[1].1, Rect::new(Point::new(0.0, 40.0), rows[1].1.size));
+        assert_eq!(rows[1].1.height(), 40.0);
+
+        scroll_list(&mut ui, 4000.0);
+        let rows = list_rows(&ui);
+        assert_eq!(rows.len(), 5 + 2 * OVERSCAN);
+        assert_eq!(rows[0].0, format!("Row {}", 100 - OVERSCAN));
+        let (label, bounds) = &rows[OVERSCAN];
+        assert_eq!(label, "Row 100");
+        assert_eq!(bounds.origin.y, 0.0);
+        let (_, content_height) = list_scroll_state(&ui);
+        assert_eq!(content_height, 100_000.0 * 40.0);
+
+        // Row nodes are recycled instead of cloned again
+        let node_count = label_node_count(&ui);
+        scroll_list(&mut ui, 40_000.0);
+        assert_eq!(label_node_count(&ui), node_count);
+        assert_eq!(list_rows(&ui)[OVERSCAN].0, "Row 1100");
+    }
+
+    #[test]
+    fn recycled_list_rows_start_with_a_fresh_state() {
+        let rows = (0..100).map(|index| format!("Row {index}")).collect();
+        let (mut ui, _receiver) = list_ui(
+            "component Test {
+                list(max_height=200 row_height=40) {
+                    for (row in rows) {
+                        textinput(text=row)
+                    }
+                }
+            }",
+            rows,
+        );
+        for node in ui.nodes() {
+            if let Some(state) = node
+                .state
+                .as_any_mut()
+                .downcast_mut::<TextInputElementState>()
+            {
+                state.edit_position = Some(1);
+            }
+        }
+        scroll_list(&mut ui, 200.0);
+        let edited_rows: Vec<(usize, bool)> = ui
+            .node_arena
+            .entries()
+            .filter_map(|node| {
+                let (state, props) = node
+                    .cast_state_and_props::<TextInputElementState, TextInputElementProps>()
+                    .ok()?;
+                let index = props.text.strip_prefix("Row ")?.parse().ok()?;
+                Some((index, state.edit_position.is_some()))
+            })
+            .sorted()
+            .collect();
+        // Rows that stayed instantiated keep their state, the others are new or recycled
+        let expected: Vec<_> = (5 - OVERSCAN..10 + OVERSCAN)
+            .map(|index| (index, index < 5 + OVERSCAN))
+            .collect();
+        assert_eq!(edited_rows, expected);
+    }
+
+    fn list_scroll_state(ui: &UI) -> (Float, Float) {
+        let node = ui
+            .node_arena
+            .entries()
+            .find(|node| node.tag == "list")
+            .unwrap();
+        let (state, _) = node
+            .cast_state_and_props::<ListElementState, ListElementProps>()
+            .unwrap();
+        (state.scroll.offset_y, state.scroll.content_height)
+    }
+
+    #[test]
+    fn list_measures_row_heights() {
+        let rows = (0..1000)
+            .map(|index| match index % 3 {
+                0 => format!("Row {index} has some text that wraps onto more lines"),
+                _ => format!("Row {index}"),
+            })
+            .collect();
+        let (mut ui, _receiver) = list_ui(
+            "component Test {
+                list(max_height=200) {
+                    for (row in rows) {
+                        label(label=row max_width=150)
+                    }
+                }
+            }",
+            rows,
+        );
+        let assert_rows_are_stacked = |ui: &UI| {
+            let rows = list_rows(ui);
+            for (previous, row) in rows.iter().tuple_windows() {
+                assert_eq!(row.1.min_y(), previous.1.max_y());
+            }
+            // The instantiated rows cover the viewport
+            assert!(rows[0].1.min_y() <= 0.0);
+            assert!(rows.last().unwrap().1.max_y() >= 200.0 - SCROLLBAR_WIDTH);
+            rows
+        };
+        let rows = assert_rows_are_stacked(&ui);
+        assert_eq!(rows[0].1.origin.y, 0.0);
+        assert!(rows[0].1.height() > 1.5 * rows[1].1.height());
+
+        scroll_list(&mut ui, 1000.0);
+        let rows = assert_rows_are_stacked(&ui);
+        assert_ne!(rows[0].0, "Row 0 has some text that wraps onto more lines");
+        let (offset, _) = list_scroll_state(&ui);
+        assert_eq!(offset, 1000.0);
+    }
+
+    #[test]
+    fn layout_only_updates_changed_nodes() {
+        let (mut ui, receiver) = make_ui(
+            "component Test {
+                hstack {
+                    label(label=`Counter: ${counter}`)
+                    label(label=\"Moved\")
+                }
+                label(label=\"Static\")
+            }",
+        );
+        ui.perform_layout().unwrap();
+        assert_eq!(ui.render_backends[0].layout_tree.changed_count(), 0);
+
+        for _ in 0..10 {
+            increment(&mut ui);
+            receiver.try_iter().for_each(drop);
+        }
+        // The counter label, the hstack and the root
+        assert_eq!(ui.render_backends[0].layout_tree.changed_count(), 3);
+        let labels = bounds_of(&ui, "label");
+        assert_eq!(labels[1].min_x(), labels[0].max_x());
+
+        // Same result as laying out everything again
+        ui.invalidate_layout();
+        ui.perform_layout().unwrap();
+        assert_eq!(bounds_of(&ui, "label"), labels);
+    }
+
+    #[test]
+    fn layout_skips_unchanged_nodes() {
+        let (mut ui, receiver) = make_ui(
+            "component Test {
+                label(label=`Counter: ${counter}`)
+                vstack {
+                    label(label=\"Static\")
+                    button(label=\"Button\")
+                }
+            }",
+        );
+        let restyled_tags = |ui: &UI| -> Vec<String> {
+            ui.render_backends[0]
+                .layout_tree
+                .restyled_nodes()
+                .iter()
+                .map(|node_idx| ui.node_arena[node_idx].tag.clone())
+                .collect()
+        };
+        assert_eq!(restyled_tags(&ui), ["label", "vstack", "label", "button"]);
+        ui.perform_layout().unwrap();
+        assert_eq!(restyled_tags(&ui), Vec::<String>::new());
+
+        for _ in 0..10 {
+            increment(&mut ui);
+            receiver.try_iter().for_each(drop);
+        }
+        // The vstack and its children are neither restyled nor measured again
+        assert_eq!(restyled_tags(&ui), ["label"]);
+        let labels = bounds_of(&ui, "label");
+
+        // Same result as laying out everything again
+        ui.invalidate_layout();
+        ui.perform_layout().unwrap();
+        assert_eq!(restyled_tags(&ui).len(), 4);
+        assert_eq!(bounds_of(&ui, "label"), labels);
+    }
+
+    fn button_states(ui: &UI) -> Vec<(bool, bool)> {
+        ui.node_arena
+            .entries()
+            .filter(|node| node.tag == "button")
+            .map(|node| {
+                let (state, _) = node
+                    .cast_state_and_props::<ButtonElementState, ButtonElementProps>()
+                    .unwrap();
+                (state.is_hovering, state.is_pressed)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn only_topmost_node_is_hit() {
+        let (mut ui, _receiver) = make_ui(
+            "component Test {
+                overlay {
+                    button(label=\"Below\" @click=Increment)
+                    button(label=\"Above\" @click=Increment)
+                }
+            }",
+        );
+        let button = bounds_of(&ui, "button")[1];
+        ui.handle_ui_event(UiEvent::mouse_move(button.center()))
+            .unwrap();
+        ui.handle_ui_event(UiEvent::mouse_input(MouseEventKind::Pressed))
+            .unwrap();
+        assert_eq!(button_states(&ui), vec![(false, false), (true, true)]);
+        assert_eq!(ui.active_nodes.len(), 1);
+        let counter = ui.app_state.state().reflect_path("counter").unwrap();
+        assert_eq!(counter.downcast_ref::<i32>(), Some(&1));
+    }
+
+    #[test]
+    fn enter_and_leave_are_sent_on_transitions() {
+        let (mut ui, _receiver) = make_ui(
+            "component Test {
+                button(label=\"Button\")
+            }",
+        );
+        let button = bounds_of(&ui, "button")[0];
+        ui.handle_ui_event(UiEvent::mouse_move(button.center()))
+            .unwrap();
+        assert_eq!(button_states(&ui), vec![(true, false)]);
+
+        // Moving within the button does not enter it again
+        for node in ui.nodes() {
+            if let Ok((state, _)) =
+                node.cast_state_mut_and_props::<ButtonElementState, ButtonElementProps>()
+            {
+                state.is_hovering = false;
+            }
+        }
+        ui.handle_ui_event(UiEvent::mouse_move(button.center() + Vector::new(1.0, 0.0)))
+            .unwrap();
+        assert_eq!(button_states(&ui), vec![(false, false)]);
+
+        ui.handle_ui_event(UiEvent::mouse_move(Point::new(-10.0, -10.0)))
+            .unwrap();
+        ui.handle_ui_event(UiEvent::mouse_move(button.center()))
+            .unwrap();
+        assert_eq!(button_states(&ui), vec![(true, false)]);
+        ui.handle_ui_event(UiEvent::mouse_move(Point::new(-10.0, -10.0)))
+            .unwrap();
+        assert_eq!(button_states(&ui), vec![(false, false)]);
+    }
+
+    /// Clicks the button with the label and returns the entries it logged
+    fn click_logged(ui: &mut UI, label: &str) -> Vec<String> {
+        let button = ui
+            .node_arena
+            .entries()
+            .find(|node| {
+                node.cast_state_and_props::<ButtonElementState, ButtonElementProps>()
+                    .is_ok_and(|(_, props)| props.label == label)
+            })
+            .unwrap()
+            .bounds()
+            .center();
+        let previous_entries = log_entries(ui).len();
+        ui.handle_ui_event(UiEvent::mouse_move(button)).unwrap();
+        ui.handle_ui_event(UiEvent::mouse_input(MouseEventKind::Pressed))
+            .unwrap();
+        ui.handle_ui_event(UiEvent::mouse_input(MouseEventKind::Released))
+            .unwrap();
+        log_entries(ui).split_off(previous_entries)
+    }
+
+    #[test]
+    fn events_are_captured_and_bubble_up() {
+        let (mut ui, _receiver) = make_ui(
+            "component Inner {
+                button(label=\"Inner\" @click=Log(\"button\"))
+                button(label=\"Stopped\" @click.stop=Log(\"stopped\"))
+            }
+            component Test {
+                vstack(@press.capture=Log(\"capture\") @press=Log(\"vstack\")) {
+                    Inner(@click=Log(\"component\"))
+                }
+            }",
+        );
+        // The click is triggered by the button and only sent to its own mapping
+        assert_eq!(
+            click_logged(&mut ui, "Inner"),
+            vec!["capture", "button", "vstack"]
+        );
+        // Stopping the click does not stop the press
+        assert_eq!(
+            click_logged(&mut ui, "Stopped"),
+            vec!["capture", "stopped", "vstack"]
+        );
+    }
+
+    #[test]
+    fn prevented_events_are_not_handled_by_elements() {
+        let (mut ui, _receiver) = make_ui(
+            "component Test {
+                vstack(@press.capture.prevent=Log(\"prevented\")) {
+                    button(label=\"Button\" @click=Log(\"button\"))
+                }
+            }",
+        );
+        assert_eq!(click_logged(&mut ui, "Button"), vec!["prevented"]);
+    }
+
+    #[test]
+    fn unknown_event_modifier_is_an_error() {
+        let mut ui = new_ui();
+        let Err(error) = ui.load_ui_source(
+            "component Test {
+                button(@click.once=Increment)
+            }",
+        ) else {
+            panic!("Expected an error");
+        };
+        assert!(error.to_string().contains("Unknown event modifier 'once'"));
+    }
+
+    #[test]
+    fn element_events_are_not_sent_to_ancestors() {
+        let (mut ui, receiver) = make_ui(
+            "component Test {
+                tabs(selected=mode @change=SetMode(new_value)) {
+                    tab(title=\"Light\" value=\"light\") {
+                        checkbox(checked=enabled @change=SetEnabled(new_value))
+                        radio_group(selected=mode @change=SetMode(new_value)) {
+                            radio(value=\"light\")
+                            radio_group(@change=Log(new_value)) {
+                                radio(value=\"nested\")
+                            }
+                        }
+                    }
+                    tab(title=\"Dark\" value=\"dark\") {}
+                }
+            }",
+        );
+        click_tag(&mut ui, "checkbox", 0);
+        assert!(checked_states(&ui)[0]);
+        assert_eq!(mode(&ui), "light");
+        receiver.try_iter().for_each(drop);
+
+        // Only the group closest to the radio is changed
+        click_tag(&mut ui, "radio", 1);
+        assert_eq!(log_entries(&ui), vec!["nested"]);
+        assert_eq!(mode(&ui), "light");
+    }
+
+    #[test]
+    fn message_with_wrong_argument_type_is_an_error() {
+        let (mut ui, _receiver) = make_ui(
+            "component Test {
+                checkbox(@change=SetMode(new_value))
+            }",
+        );
+        let position = bounds_of(&ui, "checkbox")[0].center();
+        ui.handle_ui_event(UiEvent::mouse_move(position)).unwrap();
+        let Err(error) = ui.handle_ui_event(UiEvent::mouse_input(MouseEventKind::Pressed)) else {
+            panic!("Expected an error");
+        };
+        assert!(error
+            .to_string()
+            .contains("Invalid arguments for message SetMode: true"));
+    }
+
+    fn focused_tag(ui: &UI) -> Option<String> {
+        ui.focused_node()
+            .map(|node_idx| ui.node_arena[&node_idx].tag.clone())
+    }
+
+    fn log_entries(ui: &UI) -> Vec<String> {
+        let log = ui.app_state.state().reflect_path("log").unwrap();
+        log.downcast_ref::<Vec<String>>().unwrap().clone()
+    }
+
+    #[test]
+    fn tab_moves_focus_in_tab_order() {
+        let (mut ui, receiver) = make_ui(
+            "component Test {
+                textinput(text=\"First\" @focus=Log(\"focus\") @blur=Log(\"blur\"))
+                label(label=\"Not focusable\")
+                button(label=\"Second\")
+                button(label=\"Skipped\" tab_index=-1)
+                knob(label=\"Early\" tab_index=1)
+            }",
+        );
+        let mut tab_order = vec![];
+        for _ in 0..4 {
+            ui.handle_ui_event(UiEvent::key_input(KeyboardKey::Tab))
+                .unwrap();
+            tab_order.push(focused_tag(&ui).unwrap());
+        }
+        assert_eq!(tab_order, vec!["knob", "textinput", "button", "knob"]);
+        assert_eq!(log_entries(&ui), vec!["focus", "blur"]);
+        ui.handle_ui_event(UiEvent::key_input(KeyboardKey::Tab).with_modifiers(Modifiers::SHIFT))
+            .unwrap();
+        assert_eq!(focused_tag(&ui).as_deref(), Some("button"));
+        ui.handle_ui_event(UiEvent::key_input(KeyboardKey::Tab))
+            .unwrap();
+        assert_eq!(focused_tag(&ui).as_deref(), Some("knob"));
+
+        // The focused node is outlined
+        receiver.try_iter().for_each(drop);
+        ui.eval_layout_and_redraw().unwrap();
+        let knob = bounds_of(&ui, "knob")[0];
+        let commands = receiver.try_recv().unwrap().render_commands;
+        assert!(commands.contains(&RenderCommand::DrawRoundRect {
+            rect: Rect::from_size(knob.size).inflate(-1.0, -1.0),
+            radius: 3.0,
+            mode: DrawMode::Stroke,
+        }));
+    }
+
+    #[test]
+    fn clicking_focuses_nearest_focusable_node() {
+        let (mut ui, _receiver) = make_ui(
+            "component Test {
+                button(label=\"Button\") {
+                    label(label=\"Inside\")
+                }
+                label(label=\"Outside\")
+            }",
+        );
+        let click = |ui: &mut UI, position: Point| {
+            ui.handle_ui_event(UiEvent::mouse_move(position)).unwrap();
+            ui.handle_ui_event(UiEvent::mouse_input(MouseEventKind::Pressed))
+                .unwrap();
+            ui.handle_ui_event(UiEvent::mouse_input(MouseEventKind::Released))
+                .unwrap();
+        };
+        let labels = bounds_of(&ui, "label");
+        click(&mut ui, labels[0].center());
+        assert_eq!(focused_tag(&ui).as_deref(), Some("button"));
+        click(&mut ui, labels[1].center());
+        assert_eq!(focused_tag(&ui), None);
+    }
+
+    #[test]
+    fn key_events_are_sent_to_focused_node() {
+        let (mut ui, _receiver) = make_ui(
+            "component Test {
+                button(label=\"Button\" @keydown=Log(\"down\") @keyup=Log(\"up\"))
+            }",
+        );
+        ui.handle_ui_event(UiEvent::key_input(KeyboardKey::A))
+            .unwrap();
+        assert!(log_entries(&ui).is_empty());
+
+        ui.handle_ui_event(UiEvent::key_input(KeyboardKey::Tab))
+            .unwrap();
+        let control_shift = Modifiers::CONTROL | Modifiers::SHIFT;
+        for (key_event_kind, repeat) in [
+            (KeyEventKind::Pressed, false),
+            (KeyEventKind::Pressed, true),
+            (KeyEventKind::Released, false),
+        ] {
+            ui.handle_ui_event(
+                UiEvent::key_event(KeyboardKey::Z, key_event_kind, repeat)
+                    .with_modifiers(control_shift),
+            )
+            .unwrap();
+        }
+        assert_eq!(log_entries(&ui), vec!["down", "down", "up"]);
+    }
+
+    #[test]
+    fn shortcuts_apply_by_focus_scope() {
+        let (mut ui, _receiver) = make_ui(
+            "component Editor {
+                shortcut(keys=\"Ctrl+S\" description=\"Save document\" @trigger=Log(\"save\"))
+                textinput(text=\"Editor\" @keydown=Log(\"typed\"))
+            }
+            component Test {
+                shortcut(keys=\"Ctrl+S\" description=\"Save all\" @trigger=Log(\"save all\"))
+                shortcut(keys=\"Ctrl+Q\" description=\"Quit\" @trigger=Log(\"quit\"))
+                Editor
+            }",
+        );
+        let press = |ui: &mut UI, key: KeyboardKey, modifiers: Modifiers| {
+            ui.handle_ui_event(UiEvent::key_input(key).with_modifiers(modifiers))
+                .unwrap();
+        };
+        let descriptions = |ui: &UI| {
+            ui.active_shortcuts()
+                .iter()
+                .map(|shortcut| format!("{}: {}", shortcut.keys, shortcut.description))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(descriptions(&ui), vec!["Ctrl+S: Save all", "Ctrl+Q: Quit"]);
+        press(&mut ui, KeyboardKey::S, Modifiers::CONTROL);
+
+        // The editor's shortcut shadows the app-wide one while it has the focus
+        press(&mut ui, KeyboardKey::Tab, Modifiers::NONE);
+        assert_eq!(
+            descriptions(&ui),
+            vec!["Ctrl+S: Save document", "Ctrl+Q: Quit"]
+        );
+        press(&mut ui, KeyboardKey::S, Modifiers::CONTROL);
+        press(&mut ui, KeyboardKey::S, Modifiers::NONE);
+        press(&mut ui, KeyboardKey::Q, Modifiers::CONTROL);
+        assert_eq!(log_entries(&ui), vec!["save all", "save", "typed", "quit"]);
+    }
+
+    #[test]
+    fn focused_text_input_takes_keys_without_modifiers() {
+        let (mut ui, _receiver) = make_ui(
+            "component Test {
+                shortcut(keys=\"Backspace\" @trigger=Log(\"delete\"))
+                shortcut(keys=\"Ctrl+Backspace\" @trigger=Log(\"delete all\"))
+                textinput(text=\"Name\" @change=Log(new_value))
+            }",
+        );
+        let press = |ui: &mut UI, key: KeyboardKey, modifiers: Modifiers| {
+            ui.handle_ui_event(UiEvent::key_input(key).with_modifiers(modifiers))
+                .unwrap();
+        };
+        press(&mut ui, KeyboardKey::Backspace, Modifiers::NONE);
+        press(&mut ui, KeyboardKey::Tab, Modifiers::NONE);
+        press(&mut ui, KeyboardKey::Backspace, Modifiers::NONE);
+        press(&mut ui, KeyboardKey::Backspace, Modifiers::CONTROL);
+        assert_eq!(log_entries(&ui), vec!["delete", "Nam", "delete all"]);
+    }
+
+    #[test]
+    fn conflicting_shortcuts_are_an_error() {
+        let mut ui = new_ui();
+        ui.load_ui_source(
+            "component Panel {
+                shortcut(keys=\"Ctrl+S\" global=true)
+            }
+            component Test {
+                shortcut(keys=\"ctrl+s\")
+                Panel
+            }",
+        )
+        .unwrap();
+        let Err(error) = ui.add_render_backend() else {
+            panic!("Expected an error");
+        };
+        assert!(error
+            .to_string()
+            .contains("Shortcut 'Ctrl+S' is bound twice app-wide"));
+    }
+
+    fn click_tag(ui: &mut UI, tag: &str, index: usize) {
+        let position = bounds_of(ui, tag)[index].center();
+        ui.handle_ui_event(UiEvent::mouse_move(position)).unwrap();
+        ui.handle_ui_event(UiEvent::mouse_input(MouseEventKind::Pressed))
+            .unwrap();
+        ui.handle_ui_event(UiEvent::mouse_input(MouseEventKind::Released))
+            .unwrap();
+        ui.eval_layout_and_redraw().unwrap();
+    }
+
+    fn checked_states(ui: &UI) -> Vec<bool> {
+        ui.node_arena
+            .entries()
+            .filter_map(|node| {
+                node.props
+                    .as_any()
+                    .downcast_ref::<CheckboxElementProps>()
+                    .map(|props| props.checked)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn checkbox_and_switch_change_their_value() {
+        let (mut ui, receiver) = make_ui(
+            "component Test {
+                checkbox(checked=enabled label=\"Enabled\" @change=SetEnabled(new_value))
+                switch(checked=enabled @change=SetEnabled(new_value))
+            }",
+        );
+        assert_eq!(checked_states(&ui), vec![false, false]);
+        click_tag(&mut ui, "checkbox", 0);
+        assert_eq!(checked_states(&ui), vec![true, true]);
+        click_tag(&mut ui, "switch", 0);
+        assert_eq!(checked_states(&ui), vec![false, false]);
+        receiver.try_iter().for_each(drop);
+
+        ui.handle_ui_event(UiEvent::key_input(KeyboardKey::Space))
+            .unwrap();
+        ui.eval_layout_and_redraw().unwrap();
+        assert_eq!(checked_states(&ui), vec![true, true]);
+    }
+
+    fn selected_radios(ui: &UI) -> Vec<String> {
+        ui.node_arena
+            .entries()
+            .filter_map(|node| {
+                node.cast_state_and_props::<RadioElementState, RadioElementProps>()
+                    .ok()
+                    .filter(|(state, _)| state.is_selected)
+                    .map(|(_, props)| props.value.clone())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn radio_group_selects_one_radio() {
+        let (mut ui, receiver) = make_ui(
+            "component Test {
+                radio_group(selected=mode @change=SetMode(new_value)) {
+                    radio(value=\"light\" label=\"Light\")
+                    hstack {
+                        radio(value=\"dark\" label=\"Dark\" @change=Log(new_value))
+                    }
+                }
+            }",
+        );
+        assert_eq!(selected_radios(&ui), vec!["light"]);
+        click_tag(&mut ui, "radio", 1);
+        assert_eq!(selected_radios(&ui), vec!["dark"]);
+        assert_eq!(log_entries(&ui), vec!["dark"]);
+        receiver.try_iter().for_each(drop);
+
+        // Selecting the selected radio again is not a change
+        click_tag(&mut ui, "radio", 1);
+        assert_eq!(log_entries(&ui), vec!["dark"]);
+        click_tag(&mut ui, "radio", 0);
+        assert_eq!(selected_radios(&ui), vec!["light"]);
+    }
+
+    fn gain(ui: &UI) -> Float {
+        *ui.app_state
+            .state()
+            .reflect_path("gain")
+            .unwrap()
+            .downcast_ref::<Float>()
+            .unwrap()
+    }
+
+    #[test]
+    fn slider_is_dragged_and_moved_with_keys() {
+        let (mut ui, receiver) = make_ui(
+            "component Test {
+                slider(value=gain min=0 max=100 step=1 @change=SetGain(new_value))
+            }",
+        );
+        let bounds = bounds_of(&ui, "slider")[0];
+        // The track spans the slider minus the thumb radius on each side
+        let track_start = bounds.min_x() + 8.0;
+        let track_length = bounds.width() - 16.0;
+        let y = bounds.center().y;
+        ui.handle_ui_event(UiEvent::mouse_move(Point::new(
+            track_start + track_length * 0.25,
+            y,
+        )))
+        .unwrap();
+        ui.handle_ui_event(UiEvent::mouse_input(MouseEventKind::Pressed))
+            .unwrap();
+        assert_eq!(gain(&ui), 25.0);
+
+        // Shift slows dragging down
+        ui.handle_ui_event(
+            UiEvent::mouse_move(Point::new(track_start + track_length * 0.75, y))
+                .with_modifiers(Modifiers::SHIFT),
+        )
+        .unwrap();
+        assert_eq!(gain(&ui), 30.0);
+        ui.handle_ui_event(UiEvent::mouse_input(MouseEventKind::Released))
+            .unwrap();
+        ui.eval_layout_and_redraw().unwrap();
+        receiver.try_iter().for_each(drop);
+
+        ui.handle_ui_event(UiEvent::key_input(KeyboardKey::ArrowRight))
+            .unwrap();
+        assert_eq!(gain(&ui), 31.0);
+        ui.eval_layout_and_redraw().unwrap();
+        ui.handle_ui_event(UiEvent::key_input(KeyboardKey::PageDown))
+            .unwrap();
+        assert_eq!(gain(&ui), 21.0);
+    }
+
+    fn type_text(ui: &mut UI, text: &str) {
+        for character in text.chars() {
+            ui.handle_ui_event(UiEvent::character_input(character))
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn number_input_steps_and_validates() {
+        let (mut ui, receiver) = make_ui(
+            "component Test {
+                number_input(value=gain min=0 max=10 step=0.5 @change=SetGain(new_value))
+            }",
+        );
+        // Clicking the upper half of the stepper increments
+        let bounds = bounds_of(&ui, "number_input")[0];
+        ui.handle_ui_event(UiEvent::mouse_move(Point::new(
+            bounds.max_x() - 5.0,
+            bounds.min_y() + 3.0,
+        )))
+        .unwrap();
+        ui.handle_ui_event(UiEvent::mouse_input(MouseEventKind::Pressed))
+            .unwrap();
+        assert_eq!(gain(&ui), 0.5);
+        ui.eval_layout_and_redraw().unwrap();
+        receiver.try_iter().for_each(drop);
+
+        ui.handle_ui_event(UiEvent::key_input(KeyboardKey::ArrowUp))
+            .unwrap();
+        assert_eq!(gain(&ui), 1.0);
+        ui.eval_layout_and_redraw().unwrap();
+
+        // Typed text is applied on Enter and snapped to the step
+        for _ in 0..3 {
+            ui.handle_ui_event(UiEvent::key_input(KeyboardKey::Backspace))
+                .unwrap();
+        }
+        type_text(&mut ui, "7.4");
+        assert_eq!(gain(&ui), 1.0);
+        ui.handle_ui_event(UiEvent::key_input(KeyboardKey::Enter))
+            .unwrap();
+        assert_eq!(gain(&ui), 7.5);
+        ui.eval_layout_and_redraw().unwrap();
+        receiver.try_iter().for_each(drop);
+
+        // Values outside the range are invalid and discarded on blur
+        ui.handle_ui_event(UiEvent::key_input(KeyboardKey::Home))
+            .unwrap();
+        type_text(&mut ui, "1");
+        ui.handle_ui_event(UiEvent::key_input(KeyboardKey::Enter))
+            .unwrap();
+        let number_input = ui
+            .node_arena
+            .entries()
+            .find(|node| node.tag == "number_input")
+            .unwrap();
+        let (state, _) = number_input
+            .cast_state_and_props::<NumberInputElementState, NumberInputElementProps>()
+            .unwrap();
+        assert!(state.is_invalid);
+        ui.focus(None).unwrap();
+        assert_eq!(gain(&ui), 7.5);
+    }
+
+    fn mode(ui: &UI) -> String {
+        ui.app_state
+            .state()
+            .reflect_path("mode")
+            .unwrap()
+            .downcast_ref::<String>()
+            .unwrap()
+            .clone()
+    }
+
+    fn dropdown_is_open(ui: &UI) -> bool {
+        let dropdown = ui
+            .node_arena
+            .entries()
+            .find(|node| node.tag == "dropdown")
+            .unwrap();
+        let (state, _) = dropdown
+            .cast_state_and_props::<DropdownElementState, DropdownElementProps>()
+            .unwrap();
+        state.is_open
+    }
+
+    fn click_at(ui: &mut UI, position: Point) {
+        ui.handle_ui_event(UiEvent::mouse_move(position)).unwrap();
+        ui.handle_ui_event(UiEvent::mouse_input(MouseEventKind::Pressed))
+            .unwrap();
+        ui.handle_ui_event(UiEvent::mouse_input(MouseEventKind::Released))
+            .unwrap();
+        ui.eval_layout_and_redraw().unwrap();
+    }
+
+    #[test]
+    fn dropdown_popup_is_above_other_content() {
+        let (mut ui, receiver) = list_ui(
+            "component Test {
+                dropdown(options=rows selected=mode @change=SetMode(new_value))
+                button(label=\"Below\")
+            }",
+            vec!["Apple".into(), "Banana".into(), "Cherry".into()],
+        );
+        let dropdown = bounds_of(&ui, "dropdown")[0];
+        let button = bounds_of(&ui, "button")[0];
+        click_at(&mut ui, dropdown.center());
+        assert!(dropdown_is_open(&ui));
+        let commands: Vec<RenderCommand> = receiver
+            .try_iter()
+            .flat_map(|message| message.render_commands)
+            .collect();
+        let text_position = |text: &str| {
+            commands.iter().position(
+                |command| matches!(command, RenderCommand::DrawText(drawn) if drawn == text),
+            )
+        };
+        assert!(text_position("Cherry") > text_position("Below"));
+
+        // The popup covers the button, so the click picks the second option
+        assert!(button.contains(Point::new(dropdown.min_x() + 10.0, dropdown.max_y() + 36.0)));
+        click_at(
+            &mut ui,
+            Point::new(dropdown.min_x() + 10.0, dropdown.max_y() + 36.0),
+        );
+        assert_eq!(mode(&ui), "Banana");
+        assert!(!dropdown_is_open(&ui));
+        assert_eq!(button_states(&ui), vec![(false, false)]);
+        receiver.try_iter().for_each(drop);
+
+        // Clicking outside closes the popup
+        click_at(&mut ui, dropdown.center());
+        assert!(dropdown_is_open(&ui));
+        click_at(
+            &mut ui,
+            Point::new(dropdown.max_x() + 100.0, dropdown.center().y),
+        );
+        assert!(!dropdown_is_open(&ui));
+    }
+
+    #[test]
+    fn dropdown_is_navigated_with_keys() {
+        let (mut ui, receiver) = list_ui(
+            "component Test {
+                dropdown(options=rows selected=mode @change=SetMode(new_value))
+            }",
+            vec!["Apple".into(), "Banana".into(), "Blueberry".into()],
+        );
+        ui.handle_ui_event(UiEvent::key_input(KeyboardKey::Tab))
+            .unwrap();
+        ui.handle_ui_event(UiEvent::key_input(KeyboardKey::ArrowDown))
+            .unwrap();
+        assert!(dropdown_is_open(&ui));
+        ui.handle_ui_event(UiEvent::key_input(KeyboardKey::ArrowDown))
+            .unwrap();
+        ui.handle_ui_event(UiEvent::key_input(KeyboardKey::Enter))
+            .unwrap();
+        assert!(!dropdown_is_open(&ui));
+        assert_eq!(mode(&ui), "Banana");
+        ui.eval_layout_and_redraw().unwrap();
+        receiver.try_iter().for_each(drop);
+
+        // Typed characters are searched for together
+        type_text(&mut ui, "bl");
+        assert_eq!(mode(&ui), "Blueberry");
+        ui.eval_layout_and_redraw().unwrap();
+
+        ui.handle_ui_event(UiEvent::key_input(KeyboardKey::Space))
+            .unwrap();
+        assert!(dropdown_is_open(&ui));
+        ui.handle_ui_event(UiEvent::key_input(KeyboardKey::Escape))
+            .unwrap();
+        assert!(!dropdown_is_open(&ui));
+    }
+
+    fn counter(ui: &UI) -> i32 {
+        let counter = ui.app_state.state().reflect_path("counter").unwrap();
+        *counter.downcast_ref::<i32>().unwrap()
+    }
+
+    fn button_bounds(ui: &UI, label: &str) -> Rect {
+        let button = ui
+            .node_arena
+            .entries()
+            .find(|node| {
+                node.props
+                    .as_any()
+                    .downcast_ref::<ButtonElementProps>()
+                    .is_some_and(|props| props.label == label)
+            })
+            .unwrap();
+        *button.bounds()
+    }
+
+    fn focused_label(ui: &UI) -> Option<String> {
+        let node = &ui.node_arena[&ui.focused_node()?];
+        let props = node.props.as_any().downcast_ref::<ButtonElementProps>()?;
+        Some(props.label.clone())
+    }
+
+    #[test]
+    fn dialog_blocks_pointer_and_traps_focus() {
+        let (mut ui, receiver) = make_ui(
+            "component Test {
+                button(label=\"Open\" @click=SetEnabled(true))
+                button(label=\"Count\" @click=Increment)
+                if (enabled) {
+                    dialog(backdrop=true @close=SetEnabled(false)) {
+                        label(label=\"Discard changes?\")
+                        hstack {
+                            button(label=\"Discard\" @click=Log(\"discard\"))
+                            button(label=\"Cancel\" @click=SetEnabled(false))
+                        }
+                    }
+                }
+            }",
+        );
+        let count_button = button_bounds(&ui, "Count");
+        let open_button = button_bounds(&ui, "Open");
+        click_at(&mut ui, open_button.center());
+        // The dialog takes the focus and is drawn above the main tree
+        assert_eq!(focused_label(&ui).as_deref(), Some("Discard"));
+        let commands: Vec<RenderCommand> = receiver
+            .try_iter()
+            .flat_map(|message| message.render_commands)
+            .collect();
+        let command_position =
+            |expected: &RenderCommand| commands.iter().position(|command| command == expected);
+        let backdrop = RenderCommand::SetFillColor(ui.styling.backdrop_color);
+        assert!(
+            command_position(&backdrop)
+                > command_position(&RenderCommand::DrawText("Count".into()))
+        );
+        assert!(
+            command_position(&RenderCommand::DrawText("Discard".into()))
+                > command_position(&backdrop)
+        );
+        let dialog = bounds_of(&ui, "dialog")[0];
+        assert_eq!(dialog.size, ui.render_backends[0].window_size);
+        let discard = button_bounds(&ui, "Discard");
+        assert!((discard.center().y - dialog.center().y).abs() < 50.0);
+
+        // The backdrop blocks the pointer
+        click_at(&mut ui, count_button.center());
+        assert_eq!(counter(&ui), 0);
+        click_at(&mut ui, discard.center());
+        assert_eq!(log_entries(&ui), vec!["discard"]);
+
+        // Tab cycles through the dialog
+        for expected in ["Cancel", "Discard", "Cancel"] {
+            ui.handle_ui_event(UiEvent::key_input(KeyboardKey::Tab))
+                .unwrap();
+            assert_eq!(focused_label(&ui).as_deref(), Some(expected));
+        }
+
+        // Escape closes the dialog and the focus returns
+        ui.handle_ui_event(UiEvent::key_input(KeyboardKey::Escape))
+            .unwrap();
+        ui.eval_layout_and_redraw().unwrap();
+        assert!(ui.layers.is_empty());
+        assert_eq!(focused_label(&ui).as_deref(), Some("Open"));
+        click_at(&mut ui, count_button.center());
+        assert_eq!(counter(&ui), 1);
+    }
+
+    #[test]
+    fn popup_is_placed_below_its_anchor() {
+        let (mut ui, receiver) = make_ui(
+            "component Test {
+                vstack {
+                    button(label=\"Menu\" @click=SetEnabled(true))
+                    if (enabled) {
+                        popup(@close=SetEnabled(false)) {
+                            button(label=\"Item\" @click=Log(\"item\"))
+                        }
+                    }
+                }
+                button(label=\"Other\" @click=Increment)
+            }",
+        );
+        let menu = button_bounds(&ui, "Menu");
+        let other = button_bounds(&ui, "Other");
+        click_at(&mut ui, menu.center());
+        // Not part of the layout, so the button below stays in place
+        assert_eq!(button_bounds(&ui, "Other"), other);
+        let item = button_bounds(&ui, "Item");
+        assert!(item.min_y() > menu.max_y());
+        assert!(item.min_x() > menu.min_x());
+        assert!(item.intersects(&other));
+        receiver.try_iter().for_each(drop);
+
+        // The popup covers the button below
+        click_at(&mut ui, item.center());
+        assert_eq!(log_entries(&ui), vec!["item"]);
+        assert_eq!(counter(&ui), 0);
+
+        // Pressing outside closes the popup without reaching the nodes below
+        click_at(
+            &mut ui,
+            Point::new(other.min_x() + 5.0, other.max_y() + 100.0),
+        );
+        assert!(ui.layers.is_empty());
+        click_at(&mut ui, other.center());
+        assert_eq!(counter(&ui), 1);
+    }
+
+    fn rendered_tooltip(ui: &UI) -> Option<(String, Rect)> {
+        ui.render_backends[0].render_cache.tooltip.clone()
+    }
+
+    #[test]
+    fn tooltip_is_shown_after_hover_delay() {
+        let (mut ui, receiver) = make_ui(
+            "component Test {
+                button(label=\"Save\" tooltip=\"Saves the file\")
+                button(label=\"Plain\")
+            }",
+        );
+        let save = button_bounds(&ui, "Save");
+        ui.handle_ui_event(UiEvent::mouse_move(save.center()))
+            .unwrap();
+        ui.eval_layout_and_redraw().unwrap();
+        // Redrawn by the ticker while waiting
+        assert_eq!(rendered_tooltip(&ui), None);
+        assert!(!ui.animated_nodes.is_empty());
+        receiver.try_iter().for_each(drop);
+
+        ui.tooltip.as_mut().unwrap().hovered_since -= TOOLTIP_DELAY;
+        ui.redraw().unwrap();
+        let frame = receiver.try_recv().unwrap().render_commands;
+        assert!(frame.contains(&RenderCommand::DrawText("Saves the file".into())));
+        let (_, bounds) = rendered_tooltip(&ui).unwrap();
+        assert_eq!(
+            bounds.origin,
+            save.center() + Vector::new(TOOLTIP_OFFSET, TOOLTIP_OFFSET)
+        );
+        assert!(ui.animated_nodes.is_empty());
+
+        // Pressing hides the tooltip until another node is entered
+        ui.handle_ui_event(UiEvent::mouse_input(MouseEventKind::Pressed))
+            .unwrap();
+        ui.eval_layout_and_redraw().unwrap();
+        assert_eq!(rendered_tooltip(&ui), None);
+        ui.handle_ui_event(UiEvent::mouse_move(button_bounds(&ui, "Plain").center()))
+            .unwrap();
+        assert!(ui.tooltip.is_none());
+    }
+
+    #[test]
+    fn tooltip_is_flipped_to_stay_in_window() {
+        let window = Rect::from_size(Size::new(400.0, 300.0));
+        let text_size = Size::new(100.0, 20.0);
+        let tooltip_at = |position: Point| Tooltip {
+            node_idx: NodeIdx::default(),
+            position,
+            hovered_since: Instant::now(),
+            dismissed: false,
+        };
+        let bounds = tooltip_at(Point::new(50.0, 50.0)).bounds(text_size, &window);
+        assert_eq!(bounds.origin, Point::new(66.0, 66.0));
+        assert_eq!(bounds.size, Size::new(112.0, 32.0));
+        let bounds = tooltip_at(Point::new(350.0, 290.0)).bounds(text_size, &window);
+        assert_eq!(
+            bounds.origin,
+            Point::new(350.0 - 16.0 - 112.0, 290.0 - 16.0 - 32.0)
+        );
+    }
+
+    fn shown_labels(ui: &UI) -> Vec<String> {
+        ui.shown_nodes()
+            .into_iter()
+            .filter_map(|(node_idx, _)| {
+                let props = ui.node_arena[&node_idx].props.as_any();
+                Some(props.downcast_ref::<LabelElementProps>()?.label.clone())
+            })
+            .collect()
+    }
+
+    fn tab_titles(ui: &UI) -> (Vec<String>, String) {
+        let tabs = ui.node_arena.entries().find(|node| node.tag == "tabs");
+        let (state, _) = tabs
+            .unwrap()
+            .cast_state_and_props::<TabsElementState, TabsElementProps>()
+            .unwrap();
+        (state.titles.clone(), state.selected.clone())
+    }
+
+    #[test]
+    fn tabs_show_only_the_selected_tab() {
+        let (mut ui, _receiver) = make_ui(
+            "component Test {
+                tabs(selected=mode @change=SetMode(new_value)) {
+                    tab(title=\"Light\" value=\"light\") { label(label=\"Light content\") }
+                    tab(title=\"Dark\" value=\"dark\") { label(label=`Dark ${counter}`) }
+                }
+            }",
+        );
+        ui.eval_layout_and_redraw().unwrap();
+        let titles = vec!["Light".to_string(), "Dark".to_string()];
+        assert_eq!(tab_titles(&ui), (titles.clone(), "light".to_string()));
+        assert_eq!(shown_labels(&ui), vec!["Light content"]);
+        // Content of other tabs is not evaluated
+        let dark_label = ui
+            .node_arena
+            .entries()
+            .filter_map(|node| node.props.as_any().downcast_ref::<LabelElementProps>())
+            .any(|props| props.label.starts_with("Dark"));
+        assert!(!dark_label);
+
+        let tabs = bounds_of(&ui, "tabs")[0];
+        click_at(
+            &mut ui,
+            Point::new(tabs.max_x() - 10.0, tabs.min_y() + 10.0),
+        );
+        assert_eq!(mode(&ui), "dark");
+        assert_eq!(shown_labels(&ui), vec!["Dark 0"]);
+        assert!(bounds_of(&ui, "label")
+            .iter()
+            .any(|bounds| bounds.min_y() >= 28.0));
+
+        ui.handle_ui_event(UiEvent::key_input(KeyboardKey::ArrowLeft))
+            .unwrap();
+        ui.eval_layout_and_redraw().unwrap();
+        assert_eq!(tab_titles(&ui), (titles, "light".to_string()));
+        assert_eq!(shown_labels(&ui), vec!["Light content"]);
+    }
+
+    #[test]
+    fn tabs_keep_their_selection_if_not_bound() {
+        let (mut ui, _receiver) = make_ui(
+            "component Test {
+                tabs {
+                    tab(title=\"First\") { label(label=\"One\") }
+                    tab(title=\"Second\") { label(label=\"Two\") }
+                }
+            }",
+        );
+        ui.eval_layout_and_redraw().unwrap();
+        assert_eq!(shown_labels(&ui), vec!["One"]);
+        let tabs = bounds_of(&ui, "tabs")[0];
+        click_at(
+            &mut ui,
+            Point::new(tabs.max_x() - 10.0, tabs.min_y() + 10.0),
+        );
+        assert_eq!(tab_titles(&ui).1, "Second");
+        assert_eq!(shown_labels(&ui), vec!["Two"]);
+    }
+
+    #[test]
+    fn input_inside_a_tab_does_not_change_the_tabs() {
+        let (mut ui, _receiver) = make_ui(
+            "component Test {
+                tabs(selected=mode @change=SetMode(new_value)) {
+                    tab(title=\"Light\" value=\"light\") {
+                        textinput(text=\"Name\" @change=Log(new_value))
+                    }
+                    tab(title=\"Dark\" value=\"dark\") {}
+                }
+            }",
+        );
+        ui.eval_layout_and_redraw().unwrap();
+        click_tag(&mut ui, "textinput", 0);
+        for event in [
+            UiEvent::key_input(KeyboardKey::ArrowRight),
+            UiEvent::character_input('s'),
+        ] {
+            ui.handle_ui_event(event).unwrap();
+            ui.eval_layout_and_redraw().unwrap();
+        }
+        assert_eq!(log_entries(&ui), vec!["Names"]);
+        assert_eq!(mode(&ui), "light");
+        assert_eq!(tab_titles(&ui).1, "light");
+        assert_eq!(bounds_of(&ui, "textinput").len(), 1);
+    }
+
+    fn collapsible_state(ui: &UI) -> (bool, Float) {
+        let node = ui
+            .node_arena
+            .entries()
+            .find(|node| node.tag == "collapsible");
+        let (state, _) = node
+            .unwrap()
+            .cast_state_and_props::<CollapsibleElementState, CollapsibleElementProps>()
+            .unwrap();
+        (state.expanded, state.progress)
+    }
+
+    #[test]
+    fn collapsible_expands_with_animated_height() {
+        let (mut ui, _receiver) = make_ui(
+            "component Test {
+                collapsible(title=\"Details\") { label(label=\"Inside\") }
+                label(label=\"Below\")
+            }",
+        );
+        ui.eval_layout_and_redraw().unwrap();
+        let header_height = bounds_of(&ui, "collapsible")[0].height();
+        assert_eq!(shown_labels(&ui), vec!["Below"]);
+        assert!(!ui.animated_layout);
+
+        click_tag(&mut ui, "collapsible", 0);
+        assert!(collapsible_state(&ui).0);
+        assert_eq!(shown_labels(&ui), vec!["Inside", "Below"]);
+        assert!(ui.animated_layout);
+        assert!(bounds_of(&ui, "collapsible")[0].height() < header_height + 10.0);
+
+        thread::sleep(Duration::from_millis(250));
+        ui.perform_layout().unwrap();
+        assert_eq!(collapsible_state(&ui), (true, 1.0));
+        assert!(!ui.animated_layout);
+        let bounds = bounds_of(&ui, "collapsible")[0];
+        assert!(bounds.height() > header_height + 10.0);
+        let below = bounds_of(&ui, "label")[1];
+        assert_eq!(below.min_y(), bounds.max_y());
+
+        // Collapsing keeps the content shown until the animation finished
+        ui.handle_ui_event(UiEvent::key_input(KeyboardKey::Space))
+            .unwrap();
+        ui.eval_layout_and_redraw().unwrap();
+        assert_eq!(shown_labels(&ui), vec!["Inside", "Below"]);
+        thread::sleep(Duration::from_millis(250));
+        ui.perform_layout().unwrap();
+        assert_eq!(collapsible_state(&ui), (false, 0.0));
+        assert_eq!(shown_labels(&ui), vec!["Below"]);
+        assert_eq!(bounds_of(&ui, "collapsible")[0].height(), header_height);
+    }
+
+    #[test]
+    fn bound_collapsible_follows_app_state() {
+        let (mut ui, _receiver) = make_ui(
+            "component Test {
+                collapsible(title=\"Details\" expanded=enabled @change=SetEnabled(new_value)) {
+                    label(label=\"Inside\")
+                }
+            }",
+        );
+        ui.eval_layout_and_redraw().unwrap();
+        assert!(!collapsible_state(&ui).0);
+        (ui.event_handler)(&mut ui.app_state, &TestMessage::SetEnabled(true));
+        ui.eval_layout_and_redraw().unwrap();
+        assert!(collapsible_state(&ui).0);
+        assert_eq!(shown_labels(&ui), vec!["Inside"]);
+        click_tag(&mut ui, "collapsible", 0);
+        assert!(!collapsible_state(&ui).0);
+        let enabled = ui.app_state.state().reflect_path("enabled").unwrap();
+        assert_eq!(enabled.downcast_ref::<bool>(), Some(&false));
+    }
+
+    fn table_ui() -> (UI, Receiver<RenderBackendMessage>) {
+        let rows = (0..1000).map(|index| format!("Row {}", index)).collect();
+        let (mut ui, receiver) = list_ui(
+            "component Test {
+                table(rows=rows selected=gain sort_column=\"name\"
+                        @sort=Log(column) @select=SetGain(index)) {
+                    column(header=\"Name\" key=\"name\") { label(label=row) }
+                    column(header=\"Index\" width=60 align=\"end\") { label(label=`${row#index}`) }
+                }
+            }",
+            rows,
+        );
+        ui.eval_layout_and_redraw().unwrap();
+        (ui, receiver)
+    }
+
+    /// Index, bounds and selection of the shown table rows
+    fn table_rows(ui: &UI) -> Vec<(i32, Rect, bool)> {
+        ui.shown_nodes()
+            .into_iter()
+            .filter_map(|(node_idx, _)| {
+                let node = &ui.node_arena[&node_idx];
+                let (state, props) = node
+                    .cast_state_and_props::<TableRowElementState, TableRowElementProps>()
+                    .ok()?;
+                Some((props.index, *node.bounds(), state.is_selected))
+            })
+            .collect()
+    }
+
+    /// Bounds of the shown table cells, leaving out the row template
+    fn table_cells(ui: &UI) -> Vec<Rect> {
+        ui.shown_nodes()
+            .into_iter()
+            .map(|(node_idx, _)| &ui.node_arena[&node_idx])
+            .filter(|node| node.tag == "table_cell")
+            .map(|node| *node.bounds())
+            .collect()
+    }
+
+    #[test]
+    fn table_shows_visible_rows_below_its_header() {
+        let (mut ui, _receiver) = table_ui();
+        let table = bounds_of(&ui, "table")[0];
+        let rows = table_rows(&ui);
+        assert!(rows.len() < 100);
+        assert_eq!(rows[0].0, 0);
+        assert_eq!(rows[0].1.min_y(), table.min_y() + 28.0);
+        assert_eq!(rows[0].1.height(), 24.0);
+        let cells = table_cells(&ui);
+        assert_eq!(cells[0].width(), 120.0);
+        assert_eq!(cells[1].width(), 60.0);
+        assert_eq!(cells[1].min_x(), cells[0].max_x());
+        let labels = shown_labels(&ui);
+        assert_eq!(labels[..4], ["Row 0", "0", "Row 1", "1"]);
+
+        // The header stays in place while the rows are scrolled below it
+        scroll_list(&mut ui, 240.0);
+        let rows = table_rows(&ui);
+        let first_visible = rows
+            .iter()
+            .find(|(_, bounds, _)| bounds.min_y() >= table.min_y() + 28.0)
+            .unwrap();
+        assert_eq!(first_visible.0, 10);
+        let (row_idx, _) = ui
+            .shown_nodes()
+            .into_iter()
+            .find(|(node_idx, _)| ui.node_arena[node_idx].tag == "table_row")
+            .unwrap();
+        assert_eq!(
+            ui.node_arena[&row_idx].layout.viewport.unwrap().min_y(),
+            table.min_y() + 28.0
+        );
+    }
+
+    #[test]
+    fn table_selects_rows_and_sorts_by_column() {
+        let (mut ui, receiver) = table_ui();
+        let rows = table_rows(&ui);
+        click_at(&mut ui, rows[2].1.center());
+        assert_eq!(gain(&ui), 2.0);
+        assert!(table_rows(&ui)[2].2);
+        ui.handle_ui_event(UiEvent::key_input(KeyboardKey::ArrowDown))
+            .unwrap();
+        ui.eval_layout_and_redraw().unwrap();
+        assert_eq!(gain(&ui), 3.0);
+        let selected: Vec<_> = table_rows(&ui)
+            .into_iter()
+            .filter(|(_, _, is_selected)| *is_selected)
+            .map(|(index, _, _)| index)
+            .collect();
+        assert_eq!(selected, vec![3]);
+
+        receiver.try_iter().for_each(drop);
+        let table = bounds_of(&ui, "table")[0];
+        click_at(
+            &mut ui,
+            Point::new(table.min_x() + 50.0, table.min_y() + 10.0),
+        );
+        assert_eq!(log_entries(&ui), vec!["name"]);
+        // Columns without a key are not sortable
+        click_at(
+            &mut ui,
+            Point::new(table.min_x() + 150.0, table.min_y() + 10.0),
+        );
+        assert_eq!(log_entries(&ui), vec!["name"]);
+    }
+
+    #[test]
+    fn table_scrolls_the_selected_row_into_view() {
+        let (mut ui, receiver) = table_ui();
+        let table = bounds_of(&ui, "table")[0];
+        let first_row = table_rows(&ui)[0].1;
+        click_at(&mut ui, first_row.center());
+        let mut press = |key| {
+            ui.handle_ui_event(UiEvent::key_input(key)).unwrap();
+            ui.eval_layout_and_redraw().unwrap();
+            receiver.try_iter().for_each(drop);
+            let (index, bounds, _) = table_rows(&ui)
+                .into_iter()
+                .find(|(_, _, is_selected)| *is_selected)
+                .unwrap();
+            assert_eq!(index as Float, gain(&ui));
+            assert!(bounds.min_y() >= table.min_y() + 28.0);
+            assert!(bounds.max_y() <= table.max_y());
+            index
+        };
+        let page = press(KeyboardKey::PageDown);
+        assert!(page > 1);
+        assert_eq!(press(KeyboardKey::PageDown), 2 * page);
+        assert_eq!(press(KeyboardKey::End), 999);
+        assert_eq!(press(KeyboardKey::PageUp), 999 - page);
+        assert_eq!(press(KeyboardKey::Home), 0);
+        assert_eq!(press(KeyboardKey::ArrowDown), 1);
+    }
+
+    #[test]
+    fn table_columns_are_resized_by_dragging() {
+        let (mut ui, _receiver) = table_ui();
+        let table = bounds_of(&ui, "table")[0];
+        let edge = Point::new(table.min_x() + 120.0, table.min_y() + 10.0);
+        ui.handle_ui_event(UiEvent::mouse_move(edge)).unwrap();
+        ui.handle_ui_event(UiEvent::mouse_input(MouseEventKind::Pressed))
+            .unwrap();
+        ui.handle_ui_event(UiEvent::mouse_move(edge + Vector::new(40.0, 0.0)))
+            .unwrap();
+        ui.handle_ui_event(UiEvent::mouse_input(MouseEventKind::Released))
+            .unwrap();
+        ui.eval_layout_and_redraw().unwrap();
+        let cells = table_cells(&ui);
+        assert_eq!(cells[0].width(), 160.0);
+        assert_eq!(cells[1].min_x(), table.min_x() + 160.0);
+        // Resizing does not sort
+        assert!(log_entries(&ui).is_empty());
+    }
+
+    fn tree_ui() -> (UI, Receiver<RenderBackendMessage>) {
+        make_ui(
+            "component Test {
+                tree(key=\"name\" selected=mode @select=SetMode(key) @toggle=Log(key)) {
+                    for (file in files) {
+                        label(label=file.name)
+                    }
+                }
+            }",
+        )
+    }
+
+    fn press_key(ui: &mut UI, receiver: &Receiver<RenderBackendMessage>, key: KeyboardKey) {
+        receiver.try_iter().for_each(drop);
+        ui.handle_ui_event(UiEvent::key_input(key)).unwrap();
+        ui.eval_layout_and_redraw().unwrap();
+    }
+
+    #[test]
+    fn tree_shows_expanded_entries_indented() {
+        let (mut ui, receiver) = tree_ui();
+        assert_eq!(shown_labels(&ui), ["src", "Cargo.toml"]);
+        let tree = bounds_of(&ui, "tree")[0];
+        assert_eq!(tree.height(), 2.0 * 24.0);
+
+        // Clicking the chevron expands the entry
+        click_at(&mut ui, Point::new(tree.min_x() + 8.0, tree.min_y() + 12.0));
+        assert_eq!(shown_labels(&ui), ["src", "main.rs", "ui", "Cargo.toml"]);
+        assert_eq!(log_entries(&ui), ["src"]);
+        let label_x = |ui: &UI, index: usize| {
+            let (label_idx, _) = ui
+                .shown_nodes()
+                .into_iter()
+                .filter(|(node_idx, _)| ui.node_arena[node_idx].tag == "label")
+                .nth(index)
+                .unwrap();
+            ui.node_arena[&label_idx].bounds().min_x() - tree.min_x()
+        };
+        assert_eq!(label_x(&ui, 0), 16.0);
+        assert_eq!(label_x(&ui, 1), 32.0);
+        // Expanding does not select
+        assert_eq!(mode(&ui), "light");
+
+        receiver.try_iter().for_each(drop);
+        click_at(&mut ui, Point::new(tree.min_x() + 8.0, tree.min_y() + 12.0));
+        assert_eq!(shown_labels(&ui), ["src", "Cargo.toml"]);
+    }
+
+    #[test]
+    fn tree_is_navigated_with_the_keyboard() {
+        let (mut ui, receiver) = tree_ui();
+        let tree = bounds_of(&ui, "tree")[0];
+        click_at(
+            &mut ui,
+            Point::new(tree.min_x() + 50.0, tree.min_y() + 12.0),
+        );
+        assert_eq!(mode(&ui), "src");
+
+        press_key(&mut ui, &receiver, KeyboardKey::ArrowRight);
+        assert_eq!(shown_labels(&ui), ["src", "main.rs", "ui", "Cargo.toml"]);
+        press_key(&mut ui, &receiver, KeyboardKey::ArrowDown);
+        press_key(&mut ui, &receiver, KeyboardKey::ArrowDown);
+        assert_eq!(mode(&ui), "ui");
+        press_key(&mut ui, &receiver, KeyboardKey::ArrowRight);
+        assert_eq!(
+            shown_labels(&ui),
+            ["src", "main.rs", "ui", "mod.rs", "Cargo.toml"]
+        );
+        press_key(&mut ui, &receiver, KeyboardKey::ArrowRight);
+        assert_eq!(mode(&ui), "mod.rs");
+
+        // Left moves to the parent, then collapses it
+        press_key(&mut ui, &receiver, KeyboardKey::ArrowLeft);
+        assert_eq!(mode(&ui), "ui");
+        press_key(&mut ui, &receiver, KeyboardKey::ArrowLeft);
+        assert_eq!(shown_labels(&ui), ["src", "main.rs", "ui", "Cargo.toml"]);
+        press_key(&mut ui, &receiver, KeyboardKey::ArrowLeft);
+        assert_eq!(mode(&ui), "src");
+    }
+
+    fn animated_tags(ui: &UI) -> Vec<&str> {
+        ui.animated_nodes
+            .iter()
+            .map(|node_idx| ui.node_arena[node_idx].tag.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn progress_is_filled_to_the_value() {
+        let (ui, receiver) = make_ui(
+            "component Test {
+                progress(value=2.5 max=10)
+            }",
+        );
+        let commands = receiver.try_recv().unwrap().render_commands;
+        let bar_widths: Vec<_> = commands
+            .iter()
+            .filter_map(|command| match command {
+                RenderCommand::FillRoundRect { rect, .. } => Some(rect.width()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(bar_widths, [160.0, 40.0]);
+        assert!(ui.animated_nodes.is_empty());
+    }
+
+    #[test]
+    fn activity_indicators_are_animated_while_visible() {
+        let (mut ui, receiver) = make_ui(
+            "component Test {
+                scroll(max_height=100) {
+                    spinner(size=100 color=\"#ff0000\" speed=2 shrink=0)
+                    progress(indeterminate=true shrink=0)
+                    vstack(min_height=200)
+                }
+            }",
+        );
+        let spinner = bounds_of(&ui, "spinner")[0];
+        assert_eq!(spinner.size, Size::new(100.0, 100.0));
+        let commands = receiver.try_recv().unwrap().render_commands;
+        assert!(commands.contains(&RenderCommand::SetStrokeColor(Color::rgb(255, 0, 0))));
+        // The bar below the viewport is not redrawn by the ticker
+        assert_eq!(animated_tags(&ui), ["spinner"]);
+
+        scroll_list(&mut ui, 100.0);
+        assert_eq!(animated_tags(&ui), ["progress"]);
+    }
+}